@@ -0,0 +1,96 @@
+use std::collections::VecDeque;
+
+use serde::Deserialize;
+
+use crate::ui;
+
+/// What a batch does when one of its scripts fails, set by
+/// `batch_on_failure` in the config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OnFailure {
+    /// Ask whether to run the rest.
+    #[default]
+    Ask,
+    Stop,
+    Continue,
+}
+
+/// Marked scripts run one after another, started with `R`.
+#[derive(Debug, Default, PartialEq)]
+pub struct BatchRun {
+    /// Paths still to run, in list order.
+    pub queue: VecDeque<String>,
+    /// Name of the script running now, or of the one that just failed.
+    pub current: String,
+    /// Output of the finished scripts, each under a header.
+    pub log: String,
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    /// Scripts dropped from the queue after a failure or cancel.
+    pub skipped: usize,
+    /// A script failed and the user is being asked whether to go on.
+    pub awaiting_decision: bool,
+    /// Path of the next script, held back until its `herring-confirm:`
+    /// warning is answered.
+    pub awaiting_confirm: Option<String>,
+    /// Plain-text header rules, for `App::ascii`.
+    pub ascii: bool,
+}
+
+impl BatchRun {
+    pub fn new(queue: VecDeque<String>) -> BatchRun {
+        BatchRun {
+            total: queue.len(),
+            queue,
+            ..BatchRun::default()
+        }
+    }
+
+    pub fn finished(&self) -> usize {
+        self.passed + self.failed
+    }
+
+    /// Header above the current script's output.
+    pub fn header(&self) -> String {
+        let rule = ui::glyph(self.ascii, "━").repeat(2);
+        format!(
+            "{} [{}/{}] {} {}\n",
+            rule,
+            self.finished() + 1,
+            self.total,
+            self.current,
+            rule
+        )
+    }
+
+    /// Moves the current script's final output into the log.
+    pub fn record(&mut self, output: &str, exit_code: i32) {
+        self.log.push_str(&self.header());
+        self.log.push_str(output);
+        self.log.push_str("\n\n");
+        if exit_code == 0 {
+            self.passed += 1;
+        } else {
+            self.failed += 1;
+        }
+    }
+
+    /// Drops everything still queued.
+    pub fn skip_rest(&mut self) {
+        self.skipped += self.queue.len();
+        self.queue.clear();
+    }
+
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "=== SUMMARY ===\n{} passed, {} failed",
+            self.passed, self.failed
+        );
+        if self.skipped > 0 {
+            summary.push_str(&format!(", {} skipped", self.skipped));
+        }
+        summary
+    }
+}
@@ -0,0 +1,32 @@
+/// Why a copy didn't happen when no clipboard can be reached.
+pub const UNAVAILABLE: &str = "Clipboard unavailable";
+
+/// Puts `text` on the system clipboard, opening it on first use.
+#[cfg(feature = "clipboard")]
+pub fn copy(text: &str) -> Result<(), String> {
+    use std::cell::RefCell;
+
+    thread_local! {
+        // Kept open, as X11 and Wayland only hand out the copied text
+        // while its owner is alive
+        static CLIPBOARD: RefCell<Option<arboard::Clipboard>> = const { RefCell::new(None) };
+    }
+
+    CLIPBOARD.with_borrow_mut(|clipboard| {
+        let clipboard = match clipboard {
+            Some(clipboard) => clipboard,
+            None => clipboard.insert(
+                arboard::Clipboard::new().map_err(|e| format!("{}: {}", UNAVAILABLE, e))?,
+            ),
+        };
+        clipboard
+            .set_text(text)
+            .map_err(|e| format!("{}: {}", UNAVAILABLE, e))
+    })
+}
+
+/// Built without the `clipboard` feature: nothing can be copied.
+#[cfg(not(feature = "clipboard"))]
+pub fn copy(_text: &str) -> Result<(), String> {
+    Err(format!("{} (built without the clipboard feature)", UNAVAILABLE))
+}
@@ -0,0 +1,270 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::batch::OnFailure;
+use crate::input::DefaultAction;
+use crate::keymap::KeyList;
+use crate::run::{InvalidUtf8, WorkingDir};
+use crate::theme::ThemeColors;
+
+/// Per-directory override read from the scanned directory.
+pub const LOCAL_CONFIG_FILE: &str = ".herring.toml";
+/// Environment variables for runs, read from the scanned directory.
+pub const ENV_FILE: &str = ".herring.env";
+
+/// herring's directory under `$XDG_CONFIG_HOME`, or `~/.config`, if a
+/// home directory is known.
+pub fn config_dir() -> Option<PathBuf> {
+    app_dir("XDG_CONFIG_HOME", ".config")
+}
+
+/// herring's directory under `$XDG_CACHE_HOME`, or `~/.cache`, if a
+/// home directory is known.
+pub fn cache_dir() -> Option<PathBuf> {
+    app_dir("XDG_CACHE_HOME", ".cache")
+}
+
+fn app_dir(var: &str, home_dir: &str) -> Option<PathBuf> {
+    let base = env::var_os(var)
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|h| PathBuf::from(h).join(home_dir)))?;
+    Some(base.join("rusty-herring"))
+}
+
+/// The contents of `path`, or `None` if there is no such file, which
+/// callers treat as all defaults.
+pub fn read_if_exists(path: &Path) -> Result<Option<String>, io::Error> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// User settings read from `~/.config/rusty-herring/config.toml`.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Config {
+    /// List view title; `{count}`, `{categories}` and `{dir}` are
+    /// substituted.
+    pub title_template: Option<String>,
+    /// Script names or relative paths to pin to the top of the list.
+    pub pinned: Vec<String>,
+    /// Ask for y/n confirmation before every run.
+    pub require_confirmation: Option<bool>,
+    /// Kill runs that take longer than this, e.g. `"30s"` or `"5m"`.
+    pub timeout: Option<String>,
+    /// `"ask"`, `"stop"` or `"continue"` when a batch script fails.
+    pub batch_on_failure: Option<OnFailure>,
+    /// Directory scanned when none is given on the command line. Only
+    /// read from the global config; a leading `~/` means the home
+    /// directory.
+    pub scan_dir: Option<String>,
+    /// Default for `--max-depth`.
+    pub max_depth: Option<usize>,
+    /// Default for `--follow-symlinks`.
+    pub follow_symlinks: Option<bool>,
+    /// Default for `--dedupe`.
+    pub dedupe: Option<bool>,
+    /// `"lossy"`, `"hex"` or `"suppress"` for output lines that aren't
+    /// valid UTF-8.
+    pub invalid_utf8: Option<InvalidUtf8>,
+    /// Keys for `next`, `previous`, `run`, `quit`, `help`, `scroll_up`
+    /// and `scroll_down`, replacing their defaults.
+    pub keybindings: BTreeMap<String, KeyList>,
+    /// Lines of each output stream kept per run; `0` keeps all.
+    pub max_output_lines: Option<usize>,
+    /// `"script"`, `"root"` or `"launch"`: where scripts run.
+    pub working_dir: Option<WorkingDir>,
+    /// Default for `--ext`.
+    pub extensions: Option<Vec<String>>,
+    /// Default for `--exclude-ext`.
+    pub exclude_extensions: Option<Vec<String>>,
+    /// Whether files without an extension are listed.
+    pub extensionless: Option<bool>,
+    /// Shade every other row of the script list; on by default.
+    pub zebra_rows: Option<bool>,
+    /// Make the list filter case-sensitive once the query has an
+    /// uppercase letter; off by default.
+    pub filter_smart_case: Option<bool>,
+    /// Show how many times each script has been run after its name;
+    /// off by default.
+    pub show_run_counts: Option<bool>,
+    /// Default for `--pty`.
+    pub pty: Option<bool>,
+    /// Colors laid over `theme.toml`'s, one by one.
+    pub theme: ThemeColors,
+    /// `"run"`, `"dry-run"`, `"source"`, `"info"` or `"edit"`: what
+    /// Enter does in the list.
+    pub default_action: Option<DefaultAction>,
+    /// Categories, like `db/migrations`, collapsed at startup.
+    pub collapsed_categories: Vec<String>,
+    /// Environment variables for runs, under those `.herring.env` sets.
+    pub env: BTreeMap<String, String>,
+}
+
+impl Config {
+    /// Location of the global config file, if a home directory is known.
+    pub fn default_path() -> Option<PathBuf> {
+        Some(config_dir()?.join("config.toml"))
+    }
+
+    /// Reads a config file. A missing file yields the defaults.
+    pub fn load_from(path: &Path) -> Result<Config, String> {
+        let contents = read_if_exists(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let Some(contents) = contents else {
+            return Ok(Config::default());
+        };
+
+        toml::from_str(&contents).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Loads the global config, warning on stderr and falling back to
+    /// the defaults if it can't be parsed.
+    pub fn load() -> Config {
+        let Some(path) = Config::default_path() else {
+            return Config::default();
+        };
+
+        Config::load_or_warn(&path)
+    }
+
+    /// Loads just `directory`'s `.herring.toml`, if there is one.
+    pub fn load_local(directory: &Path) -> Config {
+        Config::load_or_warn(&directory.join(LOCAL_CONFIG_FILE))
+    }
+
+    /// Layers `local` over `self`. Settings present in `local` win,
+    /// key by key for tables; lists are combined.
+    pub fn merge(mut self, local: Config) -> Config {
+        if local.title_template.is_some() {
+            self.title_template = local.title_template;
+        }
+        if local.require_confirmation.is_some() {
+            self.require_confirmation = local.require_confirmation;
+        }
+        if local.timeout.is_some() {
+            self.timeout = local.timeout;
+        }
+        if local.batch_on_failure.is_some() {
+            self.batch_on_failure = local.batch_on_failure;
+        }
+        if local.max_depth.is_some() {
+            self.max_depth = local.max_depth;
+        }
+        if local.follow_symlinks.is_some() {
+            self.follow_symlinks = local.follow_symlinks;
+        }
+        if local.dedupe.is_some() {
+            self.dedupe = local.dedupe;
+        }
+        if local.invalid_utf8.is_some() {
+            self.invalid_utf8 = local.invalid_utf8;
+        }
+        if local.max_output_lines.is_some() {
+            self.max_output_lines = local.max_output_lines;
+        }
+        if local.working_dir.is_some() {
+            self.working_dir = local.working_dir;
+        }
+        if local.extensions.is_some() {
+            self.extensions = local.extensions;
+        }
+        if local.exclude_extensions.is_some() {
+            self.exclude_extensions = local.exclude_extensions;
+        }
+        if local.extensionless.is_some() {
+            self.extensionless = local.extensionless;
+        }
+        if local.zebra_rows.is_some() {
+            self.zebra_rows = local.zebra_rows;
+        }
+        if local.filter_smart_case.is_some() {
+            self.filter_smart_case = local.filter_smart_case;
+        }
+        if local.show_run_counts.is_some() {
+            self.show_run_counts = local.show_run_counts;
+        }
+        if local.pty.is_some() {
+            self.pty = local.pty;
+        }
+        if local.default_action.is_some() {
+            self.default_action = local.default_action;
+        }
+        self.theme = local.theme.or(self.theme);
+        self.pinned.extend(local.pinned);
+        self.collapsed_categories.extend(local.collapsed_categories);
+        self.keybindings.extend(local.keybindings);
+        self.env.extend(local.env);
+        self
+    }
+
+    /// Variables for runs: the `env` table, with `directory`'s
+    /// `.herring.env` on top.
+    pub fn run_env(&self, directory: &Path) -> Vec<(String, String)> {
+        let mut env: Vec<_> = self.env.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        env.extend(load_env(directory));
+        env
+    }
+
+    /// `scan_dir` with a leading `~/` expanded.
+    pub fn scan_dir(&self) -> Option<String> {
+        let dir = self.scan_dir.as_deref()?;
+        match (dir.strip_prefix("~/"), env::var_os("HOME")) {
+            (Some(rest), Some(home)) => Some(PathBuf::from(home).join(rest).display().to_string()),
+            _ => Some(dir.to_string()),
+        }
+    }
+
+    fn load_or_warn(path: &Path) -> Config {
+        Config::load_from(path).unwrap_or_else(|e| {
+            eprintln!("Warning: ignoring config {}", e);
+            Config::default()
+        })
+    }
+}
+
+/// Parses `KEY=VALUE` lines. Blank lines and `#` comments are skipped,
+/// a leading `export ` is allowed, and matching quotes around the value
+/// are removed. Lines without `=` are ignored.
+pub fn parse_env(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let key = key.trim();
+            if key.is_empty() {
+                return None;
+            }
+
+            let value = value.trim();
+            let value = ['"', '\'']
+                .iter()
+                .find_map(|&q| value.strip_prefix(q)?.strip_suffix(q))
+                .unwrap_or(value);
+            Some((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Reads `directory`'s `.herring.env`. A missing file means no extra
+/// variables; other read errors are warned about on stderr.
+pub fn load_env(directory: &Path) -> Vec<(String, String)> {
+    let path = directory.join(ENV_FILE);
+    match read_if_exists(&path) {
+        Ok(contents) => contents.as_deref().map(parse_env).unwrap_or_default(),
+        Err(e) => {
+            eprintln!("Warning: ignoring {}: {}", path.display(), e);
+            Vec::new()
+        }
+    }
+}
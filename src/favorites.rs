@@ -0,0 +1,58 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::config::{config_dir, read_if_exists};
+
+/// Scripts marked with `f`, keyed by absolute path.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Favorites {
+    pub paths: BTreeSet<String>,
+}
+
+impl Favorites {
+    /// Location of the favorites file, if a home directory is known.
+    pub fn default_path() -> Option<PathBuf> {
+        Some(config_dir()?.join("favorites"))
+    }
+
+    /// Reads one path per line. A missing file means no favorites.
+    pub fn load_from(path: &Path) -> Result<Favorites, io::Error> {
+        let Some(contents) = read_if_exists(path)? else {
+            return Ok(Favorites::default());
+        };
+
+        let paths = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect();
+
+        Ok(Favorites { paths })
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), io::Error> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let contents: String = self.paths.iter().map(|p| format!("{}\n", p)).collect();
+        fs::write(path, contents)
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.paths.contains(path)
+    }
+
+    /// Adds or removes `path`, returning whether it's now a favorite.
+    pub fn toggle(&mut self, path: &str) -> bool {
+        if self.paths.remove(path) {
+            false
+        } else {
+            self.paths.insert(path.to_string());
+            true
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::RunStatus;
+use crate::config::{cache_dir, read_if_exists};
+
+/// Most runs kept; older ones are dropped.
+pub const HISTORY_LIMIT: usize = 50;
+
+/// One finished run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    pub path: String,
+    pub ran_at: SystemTime,
+    pub status: RunStatus,
+}
+
+/// Recent runs, newest first.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct History {
+    pub entries: Vec<HistoryEntry>,
+}
+
+impl History {
+    /// Location of the history file, if a home directory is known.
+    pub fn default_path() -> Option<PathBuf> {
+        Some(cache_dir()?.join("history"))
+    }
+
+    /// Reads a history file of `epoch<TAB>exit code<TAB>path` lines,
+    /// with `sig<N>` for the code of a script killed by signal N. A
+    /// missing file is an empty history; malformed lines are skipped.
+    pub fn load_from(path: &Path) -> Result<History, io::Error> {
+        let Some(contents) = read_if_exists(path)? else {
+            return Ok(History::default());
+        };
+
+        let entries = contents
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.splitn(3, '\t');
+                let epoch: u64 = fields.next()?.parse().ok()?;
+                let code = fields.next()?;
+                let status = match code.strip_prefix("sig") {
+                    Some(signal) => RunStatus::Signal(signal.parse().ok()?),
+                    None => RunStatus::from_exit_code(code.parse().ok()?),
+                };
+                let path = fields.next()?.to_string();
+                Some(HistoryEntry {
+                    path,
+                    ran_at: UNIX_EPOCH + Duration::from_secs(epoch),
+                    status,
+                })
+            })
+            .take(HISTORY_LIMIT)
+            .collect();
+
+        Ok(History { entries })
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), io::Error> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let contents: String = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let epoch = entry
+                    .ran_at
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+                let code = match entry.status {
+                    RunStatus::Signal(signal) => format!("sig{}", signal),
+                    status => status.exit_code().to_string(),
+                };
+                format!("{}\t{}\t{}\n", epoch, code, entry.path)
+            })
+            .collect();
+        fs::write(path, contents)
+    }
+
+    /// Adds a run at the front, dropping the oldest past `HISTORY_LIMIT`.
+    pub fn record(&mut self, path: &str, status: RunStatus) {
+        self.entries.insert(0, HistoryEntry {
+            path: path.to_string(),
+            ran_at: SystemTime::now(),
+            status,
+        });
+        self.entries.truncate(HISTORY_LIMIT);
+    }
+}
@@ -0,0 +1,108 @@
+use std::fs;
+use std::path::Path;
+
+/// Exclude patterns read from the scan root.
+pub const IGNORE_FILE: &str = ".herringignore";
+
+/// A single `.gitignore`-style pattern.
+#[derive(Debug, Clone, PartialEq)]
+struct Pattern {
+    glob: String,
+    /// Contains a `/`, so it's matched against the whole relative path
+    /// instead of just the file name.
+    anchored: bool,
+    /// Written with a trailing `/`; only matches directories.
+    dir_only: bool,
+}
+
+/// `.gitignore`-style patterns matched against paths relative to the
+/// scan root. Supports `*`, `?` and `**`; negation is not supported.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct IgnorePatterns {
+    patterns: Vec<Pattern>,
+}
+
+impl IgnorePatterns {
+    /// Parses one pattern per line, skipping blanks and `#` comments.
+    pub fn parse(text: &str) -> IgnorePatterns {
+        let mut ignore = IgnorePatterns::default();
+        for line in text.lines() {
+            ignore.add(line);
+        }
+        ignore
+    }
+
+    /// Reads `.herringignore` from `root`. A missing or unreadable file
+    /// means nothing is ignored.
+    pub fn load(root: &Path) -> IgnorePatterns {
+        fs::read_to_string(root.join(IGNORE_FILE))
+            .map(|text| IgnorePatterns::parse(&text))
+            .unwrap_or_default()
+    }
+
+    pub fn add(&mut self, line: &str) {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return;
+        }
+
+        let dir_only = line.ends_with('/');
+        let line = line.trim_end_matches('/');
+        let anchored = line.contains('/');
+        let glob = line.trim_start_matches('/').to_string();
+
+        if !glob.is_empty() {
+            self.patterns.push(Pattern { glob, anchored, dir_only });
+        }
+    }
+
+    pub fn extend(&mut self, other: &IgnorePatterns) {
+        self.patterns.extend(other.patterns.iter().cloned());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Whether `relative` (a path under the scan root) should be skipped.
+    pub fn is_ignored(&self, relative: &Path, is_dir: bool) -> bool {
+        let Some(path) = relative.to_str() else {
+            return false;
+        };
+        let name = path.rsplit('/').next().unwrap_or(path);
+
+        self.patterns.iter().any(|pattern| {
+            if pattern.dir_only && !is_dir {
+                return false;
+            }
+            let target = if pattern.anchored { path } else { name };
+            glob_match(pattern.glob.as_bytes(), target.as_bytes())
+        })
+    }
+}
+
+/// Matches `text` against a glob where `*` and `?` stay within one path
+/// component and `**` spans any number of them.
+fn glob_match(glob: &[u8], text: &[u8]) -> bool {
+    match glob {
+        [] => text.is_empty(),
+        [b'*', b'*', b'/', rest @ ..] => {
+            // `**/` may match zero components
+            glob_match(rest, text)
+                || text
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, &c)| c == b'/')
+                    .any(|(i, _)| glob_match(rest, &text[i + 1..]))
+        }
+        [b'*', b'*', rest @ ..] => (0..=text.len()).any(|i| glob_match(rest, &text[i..])),
+        [b'*', rest @ ..] => {
+            let end = text.iter().position(|&c| c == b'/').unwrap_or(text.len());
+            (0..=end).any(|i| glob_match(rest, &text[i..]))
+        }
+        [b'?', rest @ ..] => {
+            matches!(text.first(), Some(&c) if c != b'/') && glob_match(rest, &text[1..])
+        }
+        [c, rest @ ..] => text.first() == Some(c) && glob_match(rest, &text[1..]),
+    }
+}
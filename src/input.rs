@@ -0,0 +1,818 @@
+use std::io;
+use std::path::Path;
+use std::time::Instant;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use serde::Deserialize;
+
+use crate::keymap::KeyMap;
+use crate::{App, AppMode, Overlay, RunStatus, ui};
+
+/// Output lines assumed visible until the first draw measures the
+/// terminal.
+pub const OUTPUT_VISIBLE_LINES: usize = 20;
+/// Output columns assumed visible until the first draw.
+pub const OUTPUT_VISIBLE_COLUMNS: usize = 78;
+
+/// Key bindings shown in the help view, as `(section, [(keys, description)])`.
+/// Add an entry here alongside any new binding in `dispatch`.
+pub const KEY_HELP: &[(&str, &[(&str, &str)])] = &[
+    ("Script List View", &[
+        ("↑/k", "Move selection up"),
+        ("↓/j", "Move selection down"),
+        ("←/h, →/l", "Move between columns on wide terminals"),
+        ("Alt-<key>", "Jump to the next script starting with <key>"),
+        ("<number>", "Jump to that row, on Enter or after a pause"),
+        ("Enter", "Run selected script, or the config's default_action"),
+        ("a", "Run with arguments"),
+        ("I", "Run attached to the terminal, for scripts reading stdin"),
+        ("d", "Dry run: show the command without running it"),
+        ("y", "Copy the command line to the clipboard"),
+        ("/", "Filter by name or description"),
+        ("Ctrl-P", "Search every script by name, description, category or tag"),
+        ("Space", "Collapse/expand category"),
+        ("v", "View script source"),
+        ("e", "Edit in $EDITOR, then rescan"),
+        ("r", "Recent runs"),
+        ("W", "Directories and files the scan skipped"),
+        ("i", "Size, modified time, permissions and run count of the script"),
+        ("s", "Sort by category, name, modified time or most used"),
+        ("t", "Cycle tag filter"),
+        ("F", "Show only scripts whose last run failed"),
+        ("F5/Ctrl-R", "Rescan the directories"),
+        ("p", "Toggle absolute/relative paths"),
+        ("c", "Compact list: descriptions only in the footer"),
+        ("f", "Star or unstar the selected script"),
+        ("m", "Mark or unmark for a batch run"),
+        ("R", "Run the marked scripts one after another"),
+        ("A", "Run every script in the selected category"),
+        ("?", "Show this help"),
+        ("Esc", "Clear filters or marks, or quit"),
+        ("q", "Quit application"),
+    ]),
+    ("Palette", &[
+        ("Type", "Rank scripts by how well they match"),
+        ("↑/↓", "Move between matches"),
+        ("Enter", "Select the match in the list"),
+        ("Alt-Enter", "Run the match"),
+        ("Esc", "Close"),
+    ]),
+    ("Filter", &[
+        ("Type", "Narrow the list"),
+        ("↑/↓", "Move selection"),
+        ("Enter", "Keep filter and stop typing"),
+        ("Esc", "Clear filter"),
+    ]),
+    ("Output View", &[
+        ("↑/k", "Scroll up"),
+        ("↓/j", "Scroll down"),
+        ("g/G", "Jump to top/bottom"),
+        ("PgUp/PgDn", "Scroll a page"),
+        ("Ctrl-U/D", "Scroll half a page"),
+        ("x/Ctrl-C", "Cancel running script"),
+        ("s", "Save output to a log file"),
+        (".", "Run the same script again with the same arguments"),
+        ("w", "Toggle line wrapping"),
+        ("l", "Toggle line numbers"),
+        ("/", "Search the output"),
+        ("n/N", "Next/previous match"),
+        ("Any other", "Return to script list"),
+    ]),
+    ("Output Search", &[
+        ("Type", "Highlight matches, ignoring case"),
+        ("Enter", "Jump to the first match"),
+        ("Esc", "Clear the search"),
+    ]),
+    ("Batch Failure Prompt", &[
+        ("y/Enter", "Run the remaining scripts"),
+        ("Any other", "Stop and show the summary"),
+    ]),
+    ("Quit While Running", &[
+        ("y", "Kill the running script and quit"),
+        ("Any other", "Keep running"),
+    ]),
+    ("Source View", &[
+        ("↑/k", "Scroll up"),
+        ("↓/j", "Scroll down"),
+        ("l", "Toggle line numbers"),
+        ("Any other", "Return to script list"),
+    ]),
+    ("Recent Runs", &[
+        ("↑/↓", "Move selection"),
+        ("Enter", "Run again"),
+        ("D", "Clear the run history and last-run badges"),
+        ("Any other", "Return to script list"),
+    ]),
+    ("Help", &[
+        ("↑/k", "Scroll up"),
+        ("↓/j", "Scroll down"),
+        ("Any other", "Close help"),
+    ]),
+];
+
+/// `KEY_HELP` laid out as text, one line per binding.
+pub fn help_lines() -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for (i, (section, bindings)) in KEY_HELP.iter().enumerate() {
+        if i > 0 {
+            lines.push(String::new());
+        }
+        lines.push(format!("{}:", section));
+        for (keys, description) in bindings.iter() {
+            lines.push(format!("  {:<12}- {}", keys, description));
+        }
+    }
+
+    lines
+}
+
+/// One entry of a footer hint bar, like `s: Save`.
+#[derive(Debug, Clone, Copy)]
+pub struct Hint {
+    /// Default keys, one per entry of `actions` when there are any.
+    pub keys: &'static [&'static str],
+    /// Rebindable actions the keys stand for; their keys from the
+    /// `keybindings` config are shown instead of the defaults.
+    pub actions: &'static [Action],
+    pub label: &'static str,
+}
+
+const fn hint(keys: &'static [&'static str], actions: &'static [Action], label: &'static str) -> Hint {
+    Hint { keys, actions, label }
+}
+
+/// Hints under the script list.
+pub const LIST_HINTS: &[Hint] = &[
+    hint(&["↑", "↓"], &[Action::Previous, Action::Next], "Move"),
+    hint(&["Enter"], &[Action::Run], "Run"),
+    hint(&["a"], &[], "Args"),
+    hint(&["v"], &[], "Source"),
+    hint(&["e"], &[], "Edit"),
+    hint(&["r"], &[], "History"),
+    hint(&["s"], &[], "Sort"),
+    hint(&["/"], &[], "Filter"),
+    hint(&["p"], &[], "Paths"),
+    hint(&["?"], &[Action::ShowHelp], "Help"),
+    hint(&["q"], &[Action::Quit], "Quit"),
+];
+
+/// Hints under the recent runs.
+pub const HISTORY_HINTS: &[Hint] = &[
+    hint(&["↑", "↓"], &[Action::Previous, Action::Next], "Move"),
+    hint(&["Enter"], &[], "Run again"),
+    hint(&["D"], &[], "Clear"),
+];
+
+/// Hint for scrolling the output, source and help views.
+pub const SCROLL_HINTS: &[Hint] = &[
+    hint(&["↑", "↓"], &[Action::ScrollUp, Action::ScrollDown], "Scroll"),
+];
+
+/// Hints under a finished run's output.
+pub const OUTPUT_HINTS: &[Hint] = &[
+    hint(&["/"], &[], "Search"),
+    hint(&["s"], &[], "Save"),
+    hint(&["w"], &[], "Wrap"),
+    hint(&["."], &[], "Rerun"),
+];
+
+/// Hints under the output of a script still running.
+pub const RUNNING_HINTS: &[Hint] = &[
+    hint(&["↑", "↓"], &[Action::ScrollUp, Action::ScrollDown], "Scroll"),
+    hint(&["x", "Ctrl-C"], &[], "Cancel"),
+];
+
+/// `hints` as a footer line, e.g. `↑/↓: Move | Enter: Run`, with any
+/// keys `keymap` rebinds in place of the defaults.
+pub fn hint_bar(hints: &[Hint], keymap: &KeyMap) -> String {
+    hints
+        .iter()
+        .map(|hint| {
+            let keys: Vec<String> = hint
+                .keys
+                .iter()
+                .enumerate()
+                .map(|(i, &key)| {
+                    hint.actions
+                        .get(i)
+                        .and_then(|&action| keymap.keys_for(action))
+                        .unwrap_or_else(|| key.to_string())
+                })
+                .collect();
+            format!("{}: {}", keys.join("/"), hint.label)
+        })
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// Executes scripts on behalf of `handle_key`. The TUI implements this
+/// with a real terminal; tests can substitute a stub.
+pub trait Runner {
+    fn run_selected(&mut self, app: &mut App) -> Result<(), io::Error>;
+}
+
+/// The screen currently receiving input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum View {
+    List,
+    /// Typing into the list filter.
+    Filter,
+    /// Typing arguments for the next run.
+    Args,
+    /// Answering the run confirmation prompt.
+    Confirm,
+    Output,
+    /// Typing a search into the output view.
+    OutputSearch,
+    /// Reading the selected script's source.
+    Source,
+    /// Browsing recent runs.
+    History,
+    /// Reading what the scan had to skip.
+    Warnings,
+    /// Reading the selected script's details.
+    Info,
+    /// Searching every script from the `Ctrl-P` palette.
+    Palette,
+    /// Deciding whether a batch goes on after a failure.
+    BatchPrompt,
+    /// Answering a batch script's confirmation prompt.
+    BatchConfirm,
+    /// Deciding whether to kill the running script and quit.
+    QuitPrompt,
+    Help,
+}
+
+/// What Enter, the `run` key and a double-click do in the list, set
+/// by `default_action` in the config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DefaultAction {
+    #[default]
+    Run,
+    DryRun,
+    Source,
+    Info,
+    Edit,
+}
+
+impl DefaultAction {
+    pub fn action(self) -> Action {
+        match self {
+            DefaultAction::Run => Action::Run,
+            DefaultAction::DryRun => Action::DryRun,
+            DefaultAction::Source => Action::ViewSource,
+            DefaultAction::Info => Action::ShowInfo,
+            DefaultAction::Edit => Action::EditScript,
+        }
+    }
+}
+
+/// Something a key press asks the app to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Next,
+    /// Selects the next script whose name starts with the letter.
+    JumpTo(char),
+    /// Adds a digit to the row number being typed.
+    TypeDigit(char),
+    Previous,
+    /// Moves to the next column over, in the two-column list.
+    ColumnLeft,
+    ColumnRight,
+    Run,
+    Quit,
+    ShowHelp,
+    HideHelp,
+    ToggleAbsolutePaths,
+    /// Hides description lines in the list.
+    ToggleCompact,
+    ToggleCollapse,
+    ScrollUp,
+    ScrollDown,
+    ScrollTop,
+    ScrollBottom,
+    PageUp,
+    PageDown,
+    HalfPageUp,
+    HalfPageDown,
+    BackToList,
+    StartFilter,
+    FilterChar(char),
+    FilterBackspace,
+    ConfirmFilter,
+    ClearFilter,
+    /// Clears an active filter or tag filter, otherwise quits.
+    Cancel,
+    StartArgs,
+    ArgsChar(char),
+    ArgsBackspace,
+    CancelArgs,
+    /// Runs the selected script with `pending_args`.
+    RunWithArgs,
+    /// Runs the selected script attached to the terminal.
+    RunInteractive,
+    /// Shows what the selected script would run, without running it.
+    DryRun,
+    /// Copies the selected script's command line to the clipboard.
+    CopyCommand,
+    /// Stars or unstars the selected script.
+    ToggleFavorite,
+    /// Marks or unmarks the selected script for a batch run.
+    ToggleMark,
+    RunMarked,
+    /// Runs every script in the selected category as a batch.
+    RunCategory,
+    ContinueBatch,
+    StopBatch,
+    /// Runs the batch script waiting on its confirmation.
+    ConfirmBatchScript,
+    /// Skips the batch script waiting on its confirmation.
+    SkipBatchScript,
+    /// Kills the running script and quits.
+    ConfirmQuit,
+    CancelQuit,
+    /// Runs the last script again with the same arguments.
+    RerunLast,
+    /// Kills the running script, or leaves the output if none is running.
+    CancelRun,
+    ConfirmRun,
+    CancelConfirm,
+    ViewSource,
+    /// Opens the selected script in `$EDITOR`.
+    EditScript,
+    CloseSource,
+    /// Writes the output view's text to a log file.
+    SaveOutput,
+    ToggleWrap,
+    ToggleLineNumbers,
+    StartOutputSearch,
+    SearchChar(char),
+    SearchBackspace,
+    ConfirmOutputSearch,
+    CancelOutputSearch,
+    NextMatch,
+    PreviousMatch,
+    /// Cycles the list's sort mode.
+    CycleSort,
+    CycleTagFilter,
+    /// Shows only the scripts whose last run failed, or all again.
+    ToggleFailedOnly,
+    Rescan,
+    ShowHistory,
+    HideHistory,
+    /// Forgets every past run: the history file and the list badges.
+    ClearHistory,
+    ShowWarnings,
+    HideWarnings,
+    ShowInfo,
+    HideInfo,
+    OpenPalette,
+    ClosePalette,
+    PaletteChar(char),
+    PaletteBackspace,
+    NextPaletteMatch,
+    PreviousPaletteMatch,
+    /// Selects the highlighted palette match in the list.
+    PaletteJump,
+    /// Selects and runs the highlighted palette match.
+    PaletteRun,
+    /// Runs the script of the highlighted history entry.
+    RunFromHistory,
+}
+
+pub fn current_view(app: &App) -> View {
+    match (app.mode, app.overlay) {
+        (_, Some(Overlay::QuitPrompt)) => View::QuitPrompt,
+        (AppMode::Help, _) => View::Help,
+        (AppMode::Running | AppMode::Output, _) => {
+            if app.batch_awaiting_decision() {
+                View::BatchPrompt
+            } else if app.batch_awaiting_confirm() {
+                View::BatchConfirm
+            } else if app.searching_output {
+                View::OutputSearch
+            } else {
+                View::Output
+            }
+        }
+        (AppMode::List, None) => View::List,
+        (AppMode::List, Some(overlay)) => match overlay {
+            Overlay::Filter => View::Filter,
+            Overlay::Args => View::Args,
+            Overlay::Confirm => View::Confirm,
+            Overlay::QuitPrompt => View::QuitPrompt,
+            Overlay::Source => View::Source,
+            Overlay::History => View::History,
+            Overlay::Warnings => View::Warnings,
+            Overlay::Info => View::Info,
+            Overlay::Palette => View::Palette,
+        },
+    }
+}
+
+/// Maps a key press in `view` to an action, if the key is bound there.
+pub fn dispatch(view: View, key: KeyEvent) -> Option<Action> {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let alt = key.modifiers.contains(KeyModifiers::ALT);
+
+    match view {
+        View::Help => match key.code {
+            KeyCode::Up | KeyCode::Char('k') => Some(Action::ScrollUp),
+            KeyCode::Down | KeyCode::Char('j') => Some(Action::ScrollDown),
+            _ => Some(Action::HideHelp),
+        },
+        View::Output => match key.code {
+            KeyCode::Char('c') if ctrl => Some(Action::CancelRun),
+            KeyCode::Char('u') if ctrl => Some(Action::HalfPageUp),
+            KeyCode::Char('d') if ctrl => Some(Action::HalfPageDown),
+            KeyCode::Char('x') => Some(Action::CancelRun),
+            KeyCode::Char('s') => Some(Action::SaveOutput),
+            KeyCode::Char('.') => Some(Action::RerunLast),
+            KeyCode::Char('w') => Some(Action::ToggleWrap),
+            KeyCode::Char('l') => Some(Action::ToggleLineNumbers),
+            KeyCode::Char('/') => Some(Action::StartOutputSearch),
+            KeyCode::Char('n') => Some(Action::NextMatch),
+            KeyCode::Char('N') => Some(Action::PreviousMatch),
+            KeyCode::Up | KeyCode::Char('k') => Some(Action::ScrollUp),
+            KeyCode::Down | KeyCode::Char('j') => Some(Action::ScrollDown),
+            KeyCode::Char('g') => Some(Action::ScrollTop),
+            KeyCode::Char('G') => Some(Action::ScrollBottom),
+            KeyCode::PageUp => Some(Action::PageUp),
+            KeyCode::PageDown => Some(Action::PageDown),
+            _ => Some(Action::BackToList),
+        },
+        View::OutputSearch => match key.code {
+            KeyCode::Esc => Some(Action::CancelOutputSearch),
+            KeyCode::Enter => Some(Action::ConfirmOutputSearch),
+            KeyCode::Backspace => Some(Action::SearchBackspace),
+            KeyCode::Char(c) => Some(Action::SearchChar(c)),
+            _ => None,
+        },
+        View::Source => match key.code {
+            KeyCode::Up | KeyCode::Char('k') => Some(Action::ScrollUp),
+            KeyCode::Down | KeyCode::Char('j') => Some(Action::ScrollDown),
+            KeyCode::Char('l') => Some(Action::ToggleLineNumbers),
+            _ => Some(Action::CloseSource),
+        },
+        View::History => match key.code {
+            KeyCode::Up | KeyCode::Char('k') => Some(Action::Previous),
+            KeyCode::Down | KeyCode::Char('j') => Some(Action::Next),
+            KeyCode::Enter => Some(Action::RunFromHistory),
+            KeyCode::Char('D') => Some(Action::ClearHistory),
+            _ => Some(Action::HideHistory),
+        },
+        View::Warnings => Some(Action::HideWarnings),
+        View::Info => Some(Action::HideInfo),
+        View::Palette => match key.code {
+            KeyCode::Esc => Some(Action::ClosePalette),
+            KeyCode::Enter if alt => Some(Action::PaletteRun),
+            KeyCode::Enter => Some(Action::PaletteJump),
+            KeyCode::Backspace => Some(Action::PaletteBackspace),
+            KeyCode::Down => Some(Action::NextPaletteMatch),
+            KeyCode::Up => Some(Action::PreviousPaletteMatch),
+            KeyCode::Char('n') if ctrl => Some(Action::NextPaletteMatch),
+            KeyCode::Char('p') if ctrl => Some(Action::PreviousPaletteMatch),
+            KeyCode::Char(c) if !ctrl => Some(Action::PaletteChar(c)),
+            _ => None,
+        },
+        View::BatchPrompt => match key.code {
+            KeyCode::Char('y') | KeyCode::Enter => Some(Action::ContinueBatch),
+            _ => Some(Action::StopBatch),
+        },
+        View::BatchConfirm => match key.code {
+            KeyCode::Char('y') => Some(Action::ConfirmBatchScript),
+            _ => Some(Action::SkipBatchScript),
+        },
+        View::QuitPrompt => match key.code {
+            KeyCode::Char('y') => Some(Action::ConfirmQuit),
+            _ => Some(Action::CancelQuit),
+        },
+        View::Filter => match key.code {
+            KeyCode::Esc => Some(Action::ClearFilter),
+            KeyCode::Enter => Some(Action::ConfirmFilter),
+            KeyCode::Backspace => Some(Action::FilterBackspace),
+            KeyCode::Down => Some(Action::Next),
+            KeyCode::Up => Some(Action::Previous),
+            KeyCode::Char(c) => Some(Action::FilterChar(c)),
+            _ => None,
+        },
+        View::Confirm => match key.code {
+            KeyCode::Char('y') => Some(Action::ConfirmRun),
+            // Anything else, including `n`, backs out safely
+            _ => Some(Action::CancelConfirm),
+        },
+        View::Args => match key.code {
+            KeyCode::Esc => Some(Action::CancelArgs),
+            KeyCode::Enter => Some(Action::RunWithArgs),
+            KeyCode::Backspace => Some(Action::ArgsBackspace),
+            KeyCode::Char(c) => Some(Action::ArgsChar(c)),
+            _ => None,
+        },
+        View::List => match key.code {
+            KeyCode::Char(c) if alt && c.is_alphanumeric() => Some(Action::JumpTo(c)),
+            KeyCode::Char(c) if c.is_ascii_digit() => Some(Action::TypeDigit(c)),
+            KeyCode::Char('r') if ctrl => Some(Action::Rescan),
+            KeyCode::Char('p') if ctrl => Some(Action::OpenPalette),
+            KeyCode::F(5) => Some(Action::Rescan),
+            KeyCode::Char('?') => Some(Action::ShowHelp),
+            KeyCode::Char('q') => Some(Action::Quit),
+            KeyCode::Esc => Some(Action::Cancel),
+            KeyCode::Char('/') => Some(Action::StartFilter),
+            KeyCode::Down | KeyCode::Char('j') => Some(Action::Next),
+            KeyCode::Up | KeyCode::Char('k') => Some(Action::Previous),
+            KeyCode::Left | KeyCode::Char('h') => Some(Action::ColumnLeft),
+            KeyCode::Right | KeyCode::Char('l') => Some(Action::ColumnRight),
+            KeyCode::Char('p') => Some(Action::ToggleAbsolutePaths),
+            KeyCode::Char('c') => Some(Action::ToggleCompact),
+            KeyCode::Char('a') => Some(Action::StartArgs),
+            KeyCode::Char(' ') => Some(Action::ToggleCollapse),
+            KeyCode::Char('v') => Some(Action::ViewSource),
+            KeyCode::Char('e') => Some(Action::EditScript),
+            KeyCode::Char('r') => Some(Action::ShowHistory),
+            KeyCode::Char('W') => Some(Action::ShowWarnings),
+            KeyCode::Char('i') => Some(Action::ShowInfo),
+            KeyCode::Char('s') => Some(Action::CycleSort),
+            KeyCode::Char('t') => Some(Action::CycleTagFilter),
+            KeyCode::Char('F') => Some(Action::ToggleFailedOnly),
+            KeyCode::Char('I') => Some(Action::RunInteractive),
+            KeyCode::Char('d') => Some(Action::DryRun),
+            KeyCode::Char('y') => Some(Action::CopyCommand),
+            KeyCode::Char('f') => Some(Action::ToggleFavorite),
+            KeyCode::Char('m') => Some(Action::ToggleMark),
+            KeyCode::Char('R') => Some(Action::RunMarked),
+            KeyCode::Char('A') => Some(Action::RunCategory),
+            KeyCode::Enter => Some(Action::Run),
+            _ => None,
+        },
+    }
+}
+
+/// Performs `action` against `app`, running scripts through `runner`.
+pub fn apply_action(app: &mut App, action: Action, runner: &mut dyn Runner) {
+    match action {
+        Action::Next if app.overlay == Some(Overlay::History) => app.next_history(),
+        Action::Previous if app.overlay == Some(Overlay::History) => app.previous_history(),
+        Action::Next => app.next(),
+        Action::Previous => app.previous(),
+        Action::JumpTo(c) => app.jump_to_letter(c),
+        Action::TypeDigit(c) => app.push_jump_digit(c, Instant::now()),
+        Action::ColumnLeft | Action::ColumnRight => {
+            if let Some(index) = ui::script_beside(app, action == Action::ColumnRight) {
+                app.selected_index = index;
+            }
+        }
+        Action::Quit => app.quit(),
+        Action::ShowHelp => app.show_help(),
+        Action::HideHelp => app.hide_help(),
+        Action::ToggleAbsolutePaths => app.toggle_absolute_paths(),
+        Action::ToggleCompact => app.toggle_compact(),
+        Action::ToggleCollapse => app.toggle_collapse(),
+        Action::ScrollUp if app.mode == AppMode::Help => app.scroll_help_up(),
+        Action::ScrollDown if app.mode == AppMode::Help => {
+            let total = help_lines().len();
+            app.scroll_help_down(total.saturating_sub(app.viewport_height));
+        }
+        Action::ScrollUp if app.overlay == Some(Overlay::Source) => app.scroll_source_up(),
+        Action::ScrollDown if app.overlay == Some(Overlay::Source) => {
+            let total = app.source_text.lines().count();
+            app.scroll_source_down(total.saturating_sub(app.viewport_height));
+        }
+        Action::ScrollUp => app.scroll_output_up(),
+        Action::ScrollDown => app.scroll_output_down(app.max_output_scroll()),
+        Action::ScrollTop => app.scroll_output_to_top(),
+        Action::ScrollBottom => app.scroll_output_to_bottom(app.max_output_scroll()),
+        Action::PageUp => app.page_output_up(app.viewport_height),
+        Action::PageDown => {
+            app.page_output_down(app.viewport_height, app.max_output_scroll());
+        }
+        Action::HalfPageUp => app.half_page_up(app.viewport_height),
+        Action::HalfPageDown => app.half_page_down(app.viewport_height),
+        Action::BackToList => {
+            // Stay on the output until the run finishes
+            if !app.is_running() {
+                app.back_to_list();
+            }
+        }
+        Action::StartFilter => app.start_filter(),
+        Action::FilterChar(c) => app.push_filter_char(c),
+        Action::FilterBackspace => app.pop_filter_char(),
+        Action::ConfirmFilter => app.confirm_filter(),
+        Action::ClearFilter => app.clear_filter(),
+        Action::Cancel => {
+            if !app.filter_query.is_empty() {
+                app.clear_filter();
+            } else if app.tag_filter.is_some() {
+                app.tag_filter = None;
+            } else if app.failed_only {
+                app.toggle_failed_only();
+            } else if !app.marked.is_empty() {
+                app.marked.clear();
+            } else {
+                app.quit();
+            }
+        }
+        Action::StartArgs => app.start_args(),
+        Action::ArgsChar(c) => app.push_args_char(c),
+        Action::ArgsBackspace => app.pop_args_char(),
+        Action::CancelArgs => app.cancel_args(),
+        Action::Run => {
+            app.pending_args.clear();
+            run_or_confirm(app, runner);
+        }
+        Action::RunInteractive => {
+            app.pending_args.clear();
+            if app.selected_script().is_some() {
+                app.force_interactive = true;
+                run_or_confirm(app, runner);
+            }
+        }
+        Action::DryRun => {
+            app.pending_args.clear();
+            app.dry_run();
+        }
+        Action::CopyCommand => app.copy_command(),
+        Action::ToggleFavorite => app.toggle_favorite(),
+        Action::ToggleMark => app.toggle_mark(),
+        Action::RunMarked => app.start_batch(),
+        Action::ContinueBatch => app.resume_batch(true),
+        Action::StopBatch => app.resume_batch(false),
+        Action::RunCategory => app.run_category(),
+        Action::ConfirmBatchScript => app.answer_batch_confirm(true),
+        Action::SkipBatchScript => app.answer_batch_confirm(false),
+        Action::RerunLast => {
+            if app.select_last_run() {
+                // The confirmation prompt is drawn over the list
+                if app.needs_confirmation() {
+                    app.back_to_list();
+                }
+                run_or_confirm(app, runner);
+            }
+        }
+        Action::ConfirmQuit => app.confirm_quit(),
+        Action::CancelQuit => app.cancel_quit(),
+        Action::RunWithArgs => {
+            app.finish_args();
+            run_or_confirm(app, runner);
+        }
+        Action::ConfirmRun => {
+            app.confirm_run();
+            run(app, runner);
+        }
+        Action::CancelConfirm => app.cancel_confirm(),
+        Action::ViewSource => app.view_source(),
+        Action::EditScript => app.edit_selected(),
+        Action::CloseSource => app.close_source(),
+        Action::SaveOutput => app.save_output(Path::new(".")),
+        Action::ToggleWrap => app.toggle_wrap(),
+        Action::ToggleLineNumbers => app.toggle_line_numbers(),
+        Action::StartOutputSearch => app.start_output_search(),
+        Action::SearchChar(c) => app.push_search_char(c),
+        Action::SearchBackspace => app.pop_search_char(),
+        Action::ConfirmOutputSearch => app.confirm_output_search(),
+        Action::CancelOutputSearch => app.cancel_output_search(),
+        Action::NextMatch => app.next_match(),
+        Action::PreviousMatch => app.previous_match(),
+        Action::CycleSort => app.cycle_sort(),
+        Action::CycleTagFilter => app.cycle_tag_filter(),
+        Action::ToggleFailedOnly => app.toggle_failed_only(),
+        Action::Rescan => app.rescan(),
+        Action::ShowHistory => app.show_history(),
+        Action::HideHistory => app.hide_history(),
+        Action::ClearHistory => app.clear_history(),
+        Action::ShowWarnings => app.show_warnings(),
+        Action::HideWarnings => app.hide_warnings(),
+        Action::ShowInfo => app.show_info(),
+        Action::HideInfo => app.hide_info(),
+        Action::OpenPalette => app.open_palette(),
+        Action::ClosePalette => app.close_palette(),
+        Action::PaletteChar(c) => app.push_palette_char(c),
+        Action::PaletteBackspace => app.pop_palette_char(),
+        Action::NextPaletteMatch => app.next_palette_match(),
+        Action::PreviousPaletteMatch => app.previous_palette_match(),
+        Action::PaletteJump => {
+            app.palette_jump();
+        }
+        Action::PaletteRun => {
+            if app.palette_jump() {
+                app.pending_args.clear();
+                run_or_confirm(app, runner);
+            }
+        }
+        Action::RunFromHistory => {
+            if app.select_history_entry() {
+                app.pending_args.clear();
+                run_or_confirm(app, runner);
+            }
+        }
+        Action::CancelRun => {
+            if app.is_running() {
+                app.cancel_run();
+            } else {
+                app.back_to_list();
+            }
+        }
+    }
+}
+
+fn run_or_confirm(app: &mut App, runner: &mut dyn Runner) {
+    // A `herring-confirm:` marker may not have been read yet
+    if let Some(path) = app.selected_script().map(|s| s.path.clone()) {
+        app.ensure_metadata(&path);
+    }
+    if app.needs_confirmation() {
+        app.request_confirm();
+    } else {
+        run(app, runner);
+    }
+}
+
+fn run(app: &mut App, runner: &mut dyn Runner) {
+    let Some(script) = app.selected_script() else {
+        return;
+    };
+    app.last_run = Some((script.path.clone(), app.pending_args.clone()));
+    if let Err(e) = runner.run_selected(app) {
+        app.output_text = format!("{} Error running script:\n{}", ui::glyph(app.ascii, "✗"), e);
+        app.last_status = Some(RunStatus::Failure(-1));
+        app.mode = AppMode::Output;
+    }
+}
+
+/// Applies a single key press to `app`.
+pub fn handle_key(app: &mut App, key: KeyEvent, runner: &mut dyn Runner) {
+    // Status messages only last until the next key
+    app.status_message = None;
+    app.dirty = true;
+    let view = current_view(app);
+    let action = app.keymap.resolve(view, key, dispatch(view, key));
+
+    // Enter ends a typed row number; any other key drops it
+    if !app.jump_number.is_empty() && !matches!(action, Some(Action::TypeDigit(_))) {
+        if key.code == KeyCode::Enter {
+            app.finish_number_jump();
+            return;
+        }
+        app.cancel_number_jump();
+    }
+
+    if let Some(action) = action {
+        let action = match action {
+            Action::Run if view == View::List => app.default_action.action(),
+            other => other,
+        };
+        apply_action(app, action, runner);
+    }
+}
+
+/// What the mouse wheel does in `view`, if anything.
+fn wheel_action(view: View, up: bool) -> Option<Action> {
+    match view {
+        View::Output | View::Source | View::Help => {
+            Some(if up { Action::ScrollUp } else { Action::ScrollDown })
+        }
+        View::List | View::Filter | View::History => {
+            Some(if up { Action::Previous } else { Action::Next })
+        }
+        _ => None,
+    }
+}
+
+/// Applies a mouse event to `app`: a click selects a list row, a
+/// double-click runs it (or does the `default_action`), and the wheel scrolls or moves the selection.
+pub fn handle_mouse(app: &mut App, mouse: MouseEvent, runner: &mut dyn Runner) {
+    let view = current_view(app);
+    let action = match mouse.kind {
+        MouseEventKind::ScrollUp => wheel_action(view, true),
+        MouseEventKind::ScrollDown => wheel_action(view, false),
+        MouseEventKind::Down(MouseButton::Left)
+            if matches!(view, View::List | View::Filter) =>
+        {
+            let area = app.list_area;
+            if mouse.column < area.x
+                || mouse.column >= area.right()
+                || mouse.row < area.y
+                || mouse.row >= area.bottom()
+            {
+                return;
+            }
+            let Some(index) = ui::script_at(app, mouse.column - area.x, mouse.row - area.y) else {
+                return;
+            };
+            app.status_message = None;
+            app.dirty = true;
+            if app.click_script(index, Instant::now()) {
+                apply_action(app, app.default_action.action(), runner);
+            }
+            return;
+        }
+        _ => None,
+    };
+
+    if let Some(action) = action {
+        app.status_message = None;
+        app.dirty = true;
+        apply_action(app, action, runner);
+    }
+}
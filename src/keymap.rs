@@ -0,0 +1,197 @@
+use std::collections::BTreeMap;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+use crate::input::{Action, View};
+
+/// Keys given for one action in the `keybindings` config table: a
+/// single key string or a list of them.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    fn keys(&self) -> &[String] {
+        match self {
+            KeyList::One(key) => std::slice::from_ref(key),
+            KeyList::Many(keys) => keys,
+        }
+    }
+}
+
+/// Actions that can be rebound, by their config name, with the views
+/// they apply in.
+const REBINDABLE: &[(&str, Action, &[View])] = &[
+    ("next", Action::Next, &[View::List, View::History]),
+    ("previous", Action::Previous, &[View::List, View::History]),
+    ("run", Action::Run, &[View::List]),
+    ("quit", Action::Quit, &[View::List]),
+    ("help", Action::ShowHelp, &[View::List]),
+    ("scroll_up", Action::ScrollUp, &[View::Output, View::Source, View::Help]),
+    ("scroll_down", Action::ScrollDown, &[View::Output, View::Source, View::Help]),
+];
+
+/// Key bindings from the config, consulted before the defaults in
+/// `dispatch`. An action given new keys no longer answers to its
+/// default ones.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct KeyMap {
+    bindings: Vec<(Action, KeyCode, KeyModifiers)>,
+}
+
+impl KeyMap {
+    /// Builds a key map from the `keybindings` table. Unknown actions
+    /// and keys that can't be parsed are left out, each with a warning.
+    pub fn from_config(table: &BTreeMap<String, KeyList>) -> (KeyMap, Vec<String>) {
+        let mut keymap = KeyMap::default();
+        let mut warnings = Vec::new();
+
+        for (name, keys) in table {
+            let Some(&(_, action, _)) = REBINDABLE.iter().find(|(n, _, _)| n == name) else {
+                warnings.push(format!("unknown action {:?}", name));
+                continue;
+            };
+            for key in keys.keys() {
+                match parse_key(key) {
+                    Some((code, modifiers)) => keymap.bindings.push((action, code, modifiers)),
+                    None => warnings.push(format!("unknown key {:?} for {}", key, name)),
+                }
+            }
+        }
+
+        (keymap, warnings)
+    }
+
+    /// The action `key` triggers in `view`, given what the defaults
+    /// would do with it.
+    pub fn resolve(&self, view: View, key: KeyEvent, default: Option<Action>) -> Option<Action> {
+        let applies = |action: Action| {
+            REBINDABLE
+                .iter()
+                .any(|(_, a, views)| *a == action && views.contains(&view))
+        };
+        let (code, modifiers) = normalize(key.code, key.modifiers);
+
+        let bound = self
+            .bindings
+            .iter()
+            .find(|(action, c, m)| *c == code && *m == modifiers && applies(*action));
+        if let Some(&(action, _, _)) = bound {
+            return Some(action);
+        }
+
+        match default {
+            Some(action) if applies(action) && self.rebinds(action) => None,
+            other => other,
+        }
+    }
+
+    fn rebinds(&self, action: Action) -> bool {
+        self.bindings.iter().any(|(a, _, _)| *a == action)
+    }
+
+    /// Labels of the keys the config gives `action`, or `None` if it
+    /// keeps its defaults.
+    pub fn keys_for(&self, action: Action) -> Option<String> {
+        let labels: Vec<String> = self
+            .bindings
+            .iter()
+            .filter(|(a, _, _)| *a == action)
+            .map(|&(_, code, modifiers)| key_label(code, modifiers))
+            .collect();
+        (!labels.is_empty()).then(|| labels.join("/"))
+    }
+}
+
+/// How a key is written in hints, e.g. `Ctrl-N`, `Enter` or `↓`.
+pub fn key_label(code: KeyCode, modifiers: KeyModifiers) -> String {
+    let mut label = String::new();
+    for (modifier, name) in [
+        (KeyModifiers::CONTROL, "Ctrl-"),
+        (KeyModifiers::ALT, "Alt-"),
+        (KeyModifiers::SHIFT, "Shift-"),
+    ] {
+        if modifiers.contains(modifier) {
+            label.push_str(name);
+        }
+    }
+
+    match code {
+        // `Ctrl-n` reads better as `Ctrl-N`, but a plain `n` is not `N`
+        KeyCode::Char(' ') => label.push_str("Space"),
+        KeyCode::Char(c) if !modifiers.is_empty() => label.push(c.to_ascii_uppercase()),
+        KeyCode::Char(c) => label.push(c),
+        KeyCode::Enter => label.push_str("Enter"),
+        KeyCode::Esc => label.push_str("Esc"),
+        KeyCode::Tab => label.push_str("Tab"),
+        KeyCode::Backspace => label.push_str("Backspace"),
+        KeyCode::Up => label.push('↑'),
+        KeyCode::Down => label.push('↓'),
+        KeyCode::Left => label.push('←'),
+        KeyCode::Right => label.push('→'),
+        KeyCode::Home => label.push_str("Home"),
+        KeyCode::End => label.push_str("End"),
+        KeyCode::PageUp => label.push_str("PgUp"),
+        KeyCode::PageDown => label.push_str("PgDn"),
+        KeyCode::F(n) => label.push_str(&format!("F{}", n)),
+        other => label.push_str(&format!("{:?}", other)),
+    }
+    label
+}
+
+/// Parses a key like `j`, `J`, `ctrl-n`, `alt-x`, `enter` or `f5`.
+pub fn parse_key(text: &str) -> Option<(KeyCode, KeyModifiers)> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = text.trim();
+
+    // `-` on its own is a key, not a separator
+    while let Some((prefix, key)) = rest.split_once('-').filter(|(_, key)| !key.is_empty()) {
+        modifiers |= match prefix.to_ascii_lowercase().as_str() {
+            "ctrl" => KeyModifiers::CONTROL,
+            "alt" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => return None,
+        };
+        rest = key;
+    }
+
+    let mut chars = rest.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match rest.to_ascii_lowercase().as_str() {
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "space" => KeyCode::Char(' '),
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            name => {
+                let number = name.strip_prefix('f')?.parse().ok()?;
+                (1..=24).contains(&number).then_some(KeyCode::F(number))?
+            }
+        },
+    };
+
+    Some(normalize(code, modifiers))
+}
+
+/// Terminals report `J` as shift+`J`, or just `J`; treat both alike.
+fn normalize(code: KeyCode, modifiers: KeyModifiers) -> (KeyCode, KeyModifiers) {
+    match code {
+        KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+            (KeyCode::Char(c.to_ascii_uppercase()), modifiers - KeyModifiers::SHIFT)
+        }
+        _ => (code, modifiers),
+    }
+}
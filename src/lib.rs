@@ -1,25 +1,398 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
 use std::fs;
-use std::io::{self, BufRead};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{self, BufRead, Read};
+#[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+use ratatui::layout::Rect;
+use serde::Serialize;
+
+use batch::{BatchRun, OnFailure};
+use favorites::Favorites;
+use history::History;
+use config::{ENV_FILE, LOCAL_CONFIG_FILE};
+use ignore::{IGNORE_FILE, IgnorePatterns};
+use keymap::KeyMap;
+use manifest::{MANIFEST_FILE, Manifest};
+use palette::Palette;
+use run_counts::RunCounts;
+use run::{Capture, InvalidUtf8, RunningScript, WorkingDir};
+use theme::Theme;
+
+pub mod batch;
+pub mod clipboard;
+pub mod config;
+pub mod favorites;
+pub mod history;
+pub mod ignore;
+pub mod input;
+pub mod keymap;
+pub mod manifest;
+pub mod palette;
+pub mod run;
+pub mod run_counts;
+pub mod selection;
+pub mod theme;
 pub mod ui;
 
-#[derive(Debug, PartialEq)]
+/// Heading for pinned scripts, listed before any category.
+pub const PINNED_GROUP: &str = "Pinned";
+/// Heading for scripts directly in the scan root.
+pub const UNCATEGORIZED_GROUP: &str = "Uncategorized";
+
+/// Longest gap between two clicks on a script that counts as a
+/// double-click.
+pub const DOUBLE_CLICK_WINDOW: Duration = Duration::from_millis(400);
+
+/// How long the event loop waits for input while a script is
+/// streaming output.
+pub const ACTIVE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// How long it waits when nothing is running; only input can change
+/// the screen then.
+pub const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// How long after the last digit a typed row number is jumped to.
+pub const NUMBER_JUMP_TIMEOUT: Duration = Duration::from_millis(800);
+/// Frames of the spinner shown while a script runs.
+pub const SPINNER_FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+/// How long each spinner frame is shown.
+pub const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+/// List view title when the config sets no `title_template`. `{count}`
+/// becomes the number of scripts, `{categories}` the number of distinct
+/// categories, uncategorized scripts not counting as one, and `{dir}`
+/// the scan root.
+pub const DEFAULT_TITLE_TEMPLATE: &str = "Script Runner - {count} scripts in {categories} categories";
+
+#[derive(Debug, PartialEq, Serialize)]
 pub struct Script {
     pub path: String,
+    /// Path relative to the scan root.
+    pub relative_path: String,
     pub name: String,
     pub description: Option<String>,
+    /// The whole comment block the description starts, if it runs
+    /// over several lines.
+    pub description_full: Option<String>,
+    /// Directory path below the scan root, e.g. `db/migrations`; `None`
+    /// for scripts directly in the root.
     pub category: Option<String>,
+    pub pinned: bool,
+    /// Starred with `f`; listed first within its category.
+    pub favorite: bool,
+    /// Runs across sessions, from `RunCounts`.
+    #[serde(skip)]
+    pub run_count: u64,
+    /// From a `herring-tags:` marker.
+    pub tags: Vec<String>,
+    /// Runs with the real terminal instead of captured output.
+    pub interactive: bool,
+    /// Shebang command line for a script without execute permission,
+    /// which is run through it. `None` for directly executable files.
+    pub interpreter: Option<String>,
+    /// From a `herring-run:` marker: the command to run the script
+    /// with, `{}` standing for its path. Overrides `interpreter`.
+    pub run_command: Option<String>,
+    /// From a `herring-confirm:` marker: a warning shown, and a y/n
+    /// answer asked for, before every run.
+    pub confirm_message: Option<String>,
+    /// Last modification time, for sorting newest first.
+    #[serde(skip)]
+    pub modified: Option<SystemTime>,
+}
+
+/// A run waiting for the event loop to hand it the terminal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InteractiveRun {
+    pub path: String,
+    pub interpreter: Option<String>,
+    pub run_command: Option<String>,
+    pub args: Vec<String>,
+    /// Directory to run in; `None` keeps herring's own.
+    pub dir: Option<PathBuf>,
+}
+
+/// How a script's last run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunResult {
+    pub status: RunStatus,
+    pub duration: Duration,
+}
+
+impl RunResult {
+    /// Short list badge, e.g. `[✓ 0.4s]`, `[✗ code 2]` or
+    /// `[✗ SIGSEGV]`.
+    pub fn badge(&self) -> String {
+        if self.status == RunStatus::Success {
+            format!("[✓ {:.1}s]", self.duration.as_secs_f64())
+        } else {
+            format!("[✗ {}]", self.status.outcome())
+        }
+    }
+}
+
+/// Where the run shown in the output view stands, for coloring it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunStatus {
+    Running,
+    Success,
+    /// Exited with this code, or -1 if it couldn't be started or
+    /// waited for.
+    Failure(i32),
+    /// Killed by this signal.
+    Signal(i32),
+}
+
+impl RunStatus {
+    pub fn from_exit_code(exit_code: i32) -> RunStatus {
+        if exit_code == 0 {
+            RunStatus::Success
+        } else {
+            RunStatus::Failure(exit_code)
+        }
+    }
+
+    pub fn from_exit_status(status: ExitStatus) -> RunStatus {
+        match run::killed_by(status) {
+            Some(signal) => RunStatus::Signal(signal),
+            None => RunStatus::from_exit_code(status.code().unwrap_or(-1)),
+        }
+    }
+
+    /// The script's exit code, or -1 for one without a code.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            RunStatus::Success => 0,
+            RunStatus::Failure(code) => code,
+            RunStatus::Running | RunStatus::Signal(_) => -1,
+        }
+    }
+
+    /// How a finished run ended, for badges and the history: `code 2`,
+    /// or the signal's name such as `SIGSEGV` for a killed script.
+    pub fn outcome(self) -> String {
+        match self {
+            RunStatus::Signal(signal) => match run::signal_name(signal) {
+                Some(name) => name.to_string(),
+                None => format!("signal {}", signal),
+            },
+            other => format!("code {}", other.exit_code()),
+        }
+    }
+
+    /// The code a shell would report, as `run::shell_exit_code` gives
+    /// it: 128 plus the signal for a killed script, and 1 for one
+    /// without a code.
+    pub fn shell_exit_code(self) -> i32 {
+        match self {
+            RunStatus::Signal(signal) => 128 + signal,
+            RunStatus::Failure(-1) => 1,
+            other => other.exit_code(),
+        }
+    }
+}
+
+/// Restricts the list to scripts carrying a tag, cycled with `t`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagFilter {
+    Tag(String),
+    /// Scripts without any tags.
+    Untagged,
+}
+
+/// The screen the app is on, under whatever `Overlay` is open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AppMode {
+    #[default]
+    List,
+    /// Watching the output of a script that hasn't finished yet.
+    Running,
+    /// Reading finished output, a batch summary or a dry run.
+    Output,
+    Help,
+}
+
+/// A view or prompt open over the screen, in `App::overlay`. All but
+/// `QuitPrompt` open over the list; only one is open at a time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overlay {
+    /// Typing into `filter_query` after `/`.
+    Filter,
+    /// Typing `pending_args` at the `a` prompt.
+    Args,
+    /// Waiting on a y/n answer before running the selected script.
+    Confirm,
+    /// Asking whether to kill the running script and quit.
+    QuitPrompt,
+    /// Previewing the selected script's source.
+    Source,
+    History,
+    /// The entries the last scan had to skip.
+    Warnings,
+    /// The selected script's info popup.
+    Info,
+    /// The `Ctrl-P` script palette.
+    Palette,
 }
 
 pub struct App {
     pub scripts: Vec<Script>,
     pub selected_index: usize,
     pub should_quit: bool,
-    pub viewing_output: bool,
+    pub mode: AppMode,
+    /// The view or prompt open over `mode`'s screen, if any.
+    pub overlay: Option<Overlay>,
     pub output_text: String,
     pub output_scroll: usize,
-    pub showing_help: bool,
+    /// Keep the output scrolled to the bottom as new lines arrive.
+    /// Cleared when the user scrolls up, restored at the bottom.
+    pub follow_output: bool,
+    /// Canonical directory the scripts were scanned from; empty when
+    /// several directories were scanned.
+    pub root: PathBuf,
+    pub show_absolute_paths: bool,
+    /// One row per script: descriptions move from the list to the
+    /// footer, for the selected script only.
+    pub compact: bool,
+    pub title_template: Option<String>,
+    /// Render with colors; off for `NO_COLOR` or dumb terminals.
+    pub use_color: bool,
+    /// Draw `[OK]`, `[FAIL]` and other plain text in place of symbols,
+    /// for `--ascii` and `NO_COLOR`.
+    pub ascii: bool,
+    /// Text typed after `/`; narrows the list when non-empty.
+    pub filter_query: String,
+    /// Match `filter_query` case-sensitively once it has an uppercase
+    /// letter; otherwise case never matters.
+    pub filter_smart_case: bool,
+    /// Arguments typed at the `a` prompt for the next run.
+    pub pending_args: String,
+    /// The script currently executing, if any.
+    pub running: Option<RunningScript>,
+    /// Ask before running scripts.
+    pub require_confirmation: bool,
+    /// Groups whose scripts are hidden in the list.
+    pub collapsed: HashSet<String>,
+    pub source_text: String,
+    pub source_scroll: usize,
+    /// One-line result of the last footer action, such as saving output.
+    pub status_message: Option<String>,
+    /// Keep the ✓/✗ banner at the top of saved output logs.
+    pub keep_banner_in_logs: bool,
+    /// Kill runs that take longer than this.
+    pub timeout: Option<Duration>,
+    /// Add `RunningScript::diagnostics` to the output of failed runs.
+    pub verbose_failures: bool,
+    /// Recent runs, newest first.
+    pub history: History,
+    /// Where `history` is saved after each run; not saved when unset.
+    pub history_path: Option<PathBuf>,
+    pub history_index: usize,
+    /// Outcome of each script's latest run this session, by path.
+    pub last_results: HashMap<String, RunResult>,
+    /// Script whose finished output is on show, outside of batches.
+    pub output_path: Option<String>,
+    /// Where each script's output was left, by path, with a hash of
+    /// the output it applies to.
+    pub output_scrolls: HashMap<String, (u64, usize)>,
+    pub sort_mode: SortMode,
+    pub help_scroll: usize,
+    /// Lines visible in the scrolling views, updated after each draw.
+    pub viewport_height: usize,
+    /// Columns available to text in the scrolling views.
+    pub viewport_width: usize,
+    /// Wrap long output lines instead of cutting them off.
+    pub wrap_output: bool,
+    /// Number the lines of the output and source views.
+    pub show_line_numbers: bool,
+    /// Shade every other script row in the list.
+    pub zebra_rows: bool,
+    /// Search typed after `/` in the output view.
+    pub output_search: Option<String>,
+    /// Whether keys are currently being typed into `output_search`.
+    pub searching_output: bool,
+    /// Rows of `output_rows()` containing the search, in order.
+    pub output_matches: Vec<usize>,
+    /// Extra environment variables for runs, from `.herring.env`.
+    pub env: Vec<(String, String)>,
+    /// Active tag filter, if any.
+    pub tag_filter: Option<TagFilter>,
+    /// Only list scripts whose last run this session failed.
+    pub failed_only: bool,
+    /// Directories the scripts were scanned from, for `rescan`.
+    pub scan_dirs: Vec<String>,
+    pub scan_options: ScanOptions,
+    /// Names or paths pinned by the config, reapplied on `rescan`.
+    pub pins: Vec<String>,
+    /// Run the next script interactively even without the marker.
+    pub force_interactive: bool,
+    /// Interactive run waiting for the event loop to release the terminal.
+    pub interactive_request: Option<InteractiveRun>,
+    /// Script waiting for the event loop to open it in the editor.
+    pub edit_request: Option<String>,
+    /// Scripts starred with `f`, reapplied on `rescan`.
+    pub favorites: Favorites,
+    /// Where `favorites` is saved after each toggle, if anywhere.
+    pub favorites_path: Option<PathBuf>,
+    /// Runs of each script across sessions, reapplied on `rescan`.
+    pub run_counts: RunCounts,
+    /// Where `run_counts` is saved after each run, if anywhere.
+    pub run_counts_path: Option<PathBuf>,
+    /// Show each script's run count after its name in the list.
+    pub show_run_counts: bool,
+    /// Entries the last scan had to skip.
+    pub scan_warnings: Vec<ScanWarning>,
+    /// Query and selection of the `Ctrl-P` palette, reset each time it
+    /// opens.
+    pub palette: Palette,
+    /// Where the list rows were last drawn, for mapping mouse clicks.
+    pub list_area: Rect,
+    /// Script and time of the last click, to spot double-clicks.
+    pub last_click: Option<(usize, Instant)>,
+    /// Row number being typed in the list, and when its last digit came.
+    pub jump_number: String,
+    pub jump_number_at: Option<Instant>,
+    /// Paths of scripts whose comments are still being read in the
+    /// background, with `ScanOptions::lazy_metadata`.
+    pub loading_metadata: HashSet<String>,
+    /// What the background reader has found, as it finds it.
+    pub metadata_updates: Option<Receiver<(String, ScriptMetadata)>>,
+    /// Something changed since the last draw.
+    pub dirty: bool,
+    /// Colors for every view, from `theme.toml`.
+    pub theme: Theme,
+    /// Paths of the scripts marked with `m` for a batch run.
+    pub marked: HashSet<String>,
+    /// Batch run in progress or just finished, shown in the output view.
+    pub batch: Option<BatchRun>,
+    pub batch_on_failure: OnFailure,
+    /// How runs show output that isn't valid UTF-8.
+    pub invalid_utf8: InvalidUtf8,
+    /// Whether runs get pipes or a pseudo-terminal.
+    pub capture: Capture,
+    /// What Enter does in the list.
+    pub default_action: input::DefaultAction,
+    /// Bindings from the config that override the default keys.
+    pub keymap: KeyMap,
+    /// Lines of each output stream kept per run; `None` keeps all.
+    pub max_output_lines: Option<usize>,
+    pub working_dir: WorkingDir,
+    /// Path and argument string of the last script run, for `.`.
+    pub last_run: Option<(String, String)>,
+    /// Shell-style exit code of the last run to finish, for
+    /// `--propagate-exit`.
+    pub last_exit_code: Option<i32>,
+    /// How the run in the output view is going, or `None` if the view
+    /// shows something else.
+    pub last_status: Option<RunStatus>,
+    /// Index into `SPINNER_FRAMES`, advanced by `poll_run`.
+    pub spinner_frame: usize,
 }
 
 impl App {
@@ -28,32 +401,603 @@ impl App {
             scripts,
             selected_index: 0,
             should_quit: false,
-            viewing_output: false,
+            mode: AppMode::List,
+            overlay: None,
             output_text: String::new(),
             output_scroll: 0,
-            showing_help: false,
+            follow_output: true,
+            root: PathBuf::new(),
+            show_absolute_paths: false,
+            compact: false,
+            title_template: None,
+            use_color: true,
+            ascii: false,
+            filter_query: String::new(),
+            filter_smart_case: false,
+            pending_args: String::new(),
+            running: None,
+            require_confirmation: false,
+            collapsed: HashSet::new(),
+            source_text: String::new(),
+            source_scroll: 0,
+            status_message: None,
+            keep_banner_in_logs: true,
+            timeout: None,
+            verbose_failures: false,
+            history: History::default(),
+            history_path: None,
+            history_index: 0,
+            last_results: HashMap::new(),
+            output_path: None,
+            output_scrolls: HashMap::new(),
+            sort_mode: SortMode::default(),
+            help_scroll: 0,
+            viewport_height: input::OUTPUT_VISIBLE_LINES,
+            viewport_width: input::OUTPUT_VISIBLE_COLUMNS,
+            wrap_output: false,
+            show_line_numbers: false,
+            zebra_rows: true,
+            output_search: None,
+            searching_output: false,
+            output_matches: Vec::new(),
+            env: Vec::new(),
+            tag_filter: None,
+            failed_only: false,
+            scan_dirs: Vec::new(),
+            scan_options: ScanOptions::default(),
+            pins: Vec::new(),
+            force_interactive: false,
+            interactive_request: None,
+            edit_request: None,
+            favorites: Favorites::default(),
+            favorites_path: None,
+            run_counts: RunCounts::default(),
+            run_counts_path: None,
+            show_run_counts: false,
+            scan_warnings: Vec::new(),
+            palette: Palette::default(),
+            list_area: Rect::default(),
+            last_click: None,
+            jump_number: String::new(),
+            jump_number_at: None,
+            loading_metadata: HashSet::new(),
+            metadata_updates: None,
+            dirty: true,
+            theme: Theme::default(),
+            marked: HashSet::new(),
+            batch: None,
+            batch_on_failure: OnFailure::default(),
+            invalid_utf8: InvalidUtf8::default(),
+            capture: Capture::default(),
+            default_action: input::DefaultAction::default(),
+            keymap: KeyMap::default(),
+            max_output_lines: Some(run::DEFAULT_MAX_OUTPUT_LINES),
+            working_dir: WorkingDir::default(),
+            last_run: None,
+            last_exit_code: None,
+            last_status: None,
+            spinner_frame: 0,
+        }
+    }
+
+    fn matches_filter(&self, script: &Script) -> bool {
+        let tagged = match &self.tag_filter {
+            None => true,
+            Some(TagFilter::Tag(tag)) => script.tags.contains(tag),
+            Some(TagFilter::Untagged) => script.tags.is_empty(),
+        };
+        if !tagged {
+            return false;
+        }
+        let failed = || self.last_results.get(&script.path).is_some_and(|r| r.status != RunStatus::Success);
+        if self.failed_only && !failed() {
+            return false;
+        }
+
+        if self.filter_query.is_empty() {
+            return true;
+        }
+
+        let case_sensitive =
+            self.filter_smart_case && self.filter_query.chars().any(char::is_uppercase);
+        let matches = |text: &str| {
+            if case_sensitive {
+                text.contains(&self.filter_query)
+            } else {
+                text.to_lowercase().contains(&self.filter_query.to_lowercase())
+            }
+        };
+        matches(&script.name) || script.description.as_deref().is_some_and(matches)
+    }
+
+    /// Indices into `scripts` that pass the current filter, in order.
+    pub fn filtered_indices(&self) -> Vec<usize> {
+        self.scripts
+            .iter()
+            .enumerate()
+            .filter(|(_, script)| self.matches_filter(script))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// List group a script is shown under.
+    pub fn group_of<'a>(&self, script: &'a Script) -> &'a str {
+        if script.pinned {
+            PINNED_GROUP
+        } else {
+            script.category.as_deref().unwrap_or(UNCATEGORIZED_GROUP)
+        }
+    }
+
+    /// Whether the list is shown under group headers. Only the
+    /// category sort groups; the others are one flat list.
+    pub fn grouped(&self) -> bool {
+        self.sort_mode == SortMode::Category
+    }
+
+    pub fn is_collapsed(&self, group: &str) -> bool {
+        self.grouped() && self.collapsed.contains(group)
+    }
+
+    /// Scans `scan_dirs` again, keeping the selection if the script is
+    /// still there. The outcome is reported in `status_message`.
+    pub fn rescan(&mut self) {
+        if self.scan_dirs.is_empty() {
+            return;
+        }
+
+        let report = match scan_directories_report(&self.scan_dirs, &self.scan_options) {
+            Ok(report) => report,
+            Err(e) => {
+                self.status_message = Some(format!("{} Rescan failed: {}", ui::glyph(self.ascii, "✗"), e));
+                return;
+            }
+        };
+        let mut scripts = report.scripts;
+        self.scan_warnings = report.warnings;
+        self.failed_only = false;
+        pin_scripts(&mut scripts, &self.pins);
+        mark_favorites(&mut scripts, &self.favorites);
+        mark_run_counts(&mut scripts, &self.run_counts);
+        sort_scripts(&mut scripts, self.sort_mode);
+
+        let selected = self.scripts.get(self.selected_index).map(|s| s.path.clone());
+        self.scripts = scripts;
+        self.selected_index = selected
+            .and_then(|path| self.scripts.iter().position(|s| s.path == path))
+            .unwrap_or(0);
+        self.reselect_within_filter();
+        self.start_metadata_loader();
+        self.status_message = Some(match self.scan_warnings.len() {
+            0 => format!("Rescanned: {} scripts", self.scripts.len()),
+            n => format!("Rescanned: {} scripts, {} warnings", self.scripts.len(), n),
+        });
+    }
+
+    /// Reads the scripts' comments on a background thread when the scan
+    /// left them out, replacing any reader already going.
+    pub fn start_metadata_loader(&mut self) {
+        self.loading_metadata.clear();
+        self.metadata_updates = None;
+        if !self.scan_options.lazy_metadata || self.scripts.is_empty() {
+            return;
+        }
+
+        let paths: Vec<String> = self.scripts.iter().map(|s| s.path.clone()).collect();
+        self.loading_metadata.extend(paths.iter().cloned());
+        self.metadata_updates = Some(load_metadata_in_background(paths));
+    }
+
+    /// Fills in whatever the background reader has found since the last
+    /// call. Returns whether anything changed, marking the app `dirty`
+    /// if so.
+    pub fn poll_metadata(&mut self) -> bool {
+        let Some(updates) = &self.metadata_updates else {
+            return false;
+        };
+
+        let mut received = Vec::new();
+        let done = loop {
+            match updates.try_recv() {
+                Ok(update) => received.push(update),
+                Err(TryRecvError::Empty) => break false,
+                Err(TryRecvError::Disconnected) => break true,
+            }
+        };
+        let mut pinned = false;
+        let changed = !received.is_empty();
+        for (path, metadata) in received {
+            pinned |= metadata.pinned;
+            self.apply_metadata(&path, metadata);
+        }
+        // A `@pin` comment moves its script to the top
+        if pinned {
+            self.resort();
+        }
+        if done {
+            self.metadata_updates = None;
+            self.loading_metadata.clear();
+        }
+
+        self.dirty |= changed;
+        changed
+    }
+
+    /// Reads `path`'s comments right away if the background reader
+    /// hasn't got to it yet, so its markers apply to the coming run.
+    pub fn ensure_metadata(&mut self, path: &str) {
+        if !self.loading_metadata.contains(path) {
+            return;
+        }
+        let metadata = extract_metadata(path).unwrap_or_default();
+        let pinned = metadata.pinned;
+        self.apply_metadata(path, metadata);
+        if pinned {
+            self.resort();
+        }
+    }
+
+    fn apply_metadata(&mut self, path: &str, metadata: ScriptMetadata) {
+        if !self.loading_metadata.remove(path) {
+            return;
+        }
+        let Some(script) = self.scripts.iter_mut().find(|s| s.path == path) else {
+            return;
+        };
+        // Anything already set came from `herring.toml`, which wins
+        if script.description.is_none() {
+            script.description = metadata.description;
+            script.description_full = metadata.description_full;
+        }
+        script.pinned |= metadata.pinned;
+        let manifest_tags = std::mem::replace(&mut script.tags, metadata.tags);
+        for tag in manifest_tags {
+            if !script.tags.contains(&tag) {
+                script.tags.push(tag);
+            }
+        }
+        script.interactive = metadata.interactive;
+        script.run_command = metadata.run_command;
+        if script.confirm_message.is_none() {
+            script.confirm_message = metadata.confirm_message;
+        }
+    }
+
+    /// Switches to the next sort mode, keeping the same script selected.
+    pub fn cycle_sort(&mut self) {
+        self.sort_mode = self.sort_mode.next();
+        self.resort();
+    }
+
+    /// Stars or unstars the selected script and saves the favorites.
+    /// The script moves within its category but stays selected.
+    pub fn toggle_favorite(&mut self) {
+        let Some(script) = self.scripts.get_mut(self.selected_index) else {
+            return;
+        };
+        script.favorite = self.favorites.toggle(&favorite_key(&script.path));
+        self.resort();
+
+        if let Some(path) = &self.favorites_path
+            && let Err(e) = self.favorites.save_to(path)
+        {
+            self.status_message = Some(format!("{} Could not save favorites: {}", ui::glyph(self.ascii, "✗"), e));
+        }
+    }
+
+    /// Re-sorts for `sort_mode`, keeping the same script selected.
+    fn resort(&mut self) {
+        let selected = self.scripts.get(self.selected_index).map(|s| s.path.clone());
+        sort_scripts(&mut self.scripts, self.sort_mode);
+
+        if let Some(path) = selected {
+            self.selected_index = self
+                .scripts
+                .iter()
+                .position(|s| s.path == path)
+                .unwrap_or(0);
+        }
+    }
+
+    /// Indices the selection can rest on: every visible script, plus the
+    /// first script of each collapsed group, which stands in for its
+    /// header.
+    pub fn navigable_indices(&self) -> Vec<usize> {
+        let mut stops = Vec::new();
+        let mut last_group: Option<&str> = None;
+
+        for i in self.filtered_indices() {
+            let group = self.group_of(&self.scripts[i]);
+            let starts_group = last_group != Some(group);
+            last_group = Some(group);
+
+            if starts_group || !self.is_collapsed(group) {
+                stops.push(i);
+            }
+        }
+
+        stops
+    }
+
+    /// The selected script, unless the filter or a collapsed group
+    /// hides it.
+    pub fn selected_script(&self) -> Option<&Script> {
+        self.scripts
+            .get(self.selected_index)
+            .filter(|script| self.matches_filter(script))
+            .filter(|script| !self.is_collapsed(self.group_of(script)))
+    }
+
+    /// Collapses or expands the selected script's group. Collapsing
+    /// moves the selection onto the group header.
+    pub fn toggle_collapse(&mut self) {
+        if !self.grouped() {
+            return;
+        }
+        let Some(script) = self.scripts.get(self.selected_index) else {
+            return;
+        };
+        let group = self.group_of(script).to_string();
+
+        if !self.collapsed.remove(&group) {
+            let first = self
+                .filtered_indices()
+                .into_iter()
+                .find(|&i| self.group_of(&self.scripts[i]) == group);
+            if let Some(first) = first {
+                self.selected_index = first;
+            }
+            self.collapsed.insert(group);
         }
     }
 
+    /// Selects the clicked script. Returns whether this click follows
+    /// one on the same script within `DOUBLE_CLICK_WINDOW`.
+    pub fn click_script(&mut self, index: usize, at: Instant) -> bool {
+        self.selected_index = index;
+        let double = self.last_click.is_some_and(|(last, time)| {
+            last == index && at.duration_since(time) <= DOUBLE_CLICK_WINDOW
+        });
+        // A double-click doesn't start the next one
+        self.last_click = if double { None } else { Some((index, at)) };
+        double
+    }
+
     pub fn next(&mut self) {
-        if self.selected_index < self.scripts.len().saturating_sub(1) {
-            self.selected_index += 1;
+        let visible = self.navigable_indices();
+        match visible.iter().position(|&i| i == self.selected_index) {
+            Some(pos) if pos + 1 < visible.len() => {
+                self.selected_index = visible[pos + 1];
+            }
+            Some(_) => {}
+            None => {
+                if let Some(&first) = visible.first() {
+                    self.selected_index = first;
+                }
+            }
+        }
+    }
+
+    /// Selects the next script in list order whose name starts with
+    /// `c`, ignoring case and wrapping around. The selection stays put
+    /// if there is none.
+    pub fn jump_to_letter(&mut self, c: char) {
+        let stops = self.navigable_indices();
+        let start = stops
+            .iter()
+            .position(|&i| i == self.selected_index)
+            .map_or(0, |pos| pos + 1);
+        let starts_with_c = |i: &&usize| {
+            self.scripts[**i]
+                .name
+                .chars()
+                .next()
+                .is_some_and(|first| first.to_lowercase().eq(c.to_lowercase()))
+        };
+
+        if let Some(&i) = stops[start..].iter().chain(&stops[..start]).find(starts_with_c) {
+            self.selected_index = i;
+        }
+    }
+
+    /// The scripts shown as list rows, in order; their 1-based
+    /// positions are the row numbers typed to jump.
+    pub fn numbered_indices(&self) -> Vec<usize> {
+        self.filtered_indices()
+            .into_iter()
+            .filter(|&i| !self.is_collapsed(self.group_of(&self.scripts[i])))
+            .collect()
+    }
+
+    /// Adds a digit to the row number being typed.
+    pub fn push_jump_digit(&mut self, c: char, now: Instant) {
+        self.jump_number.push(c);
+        self.jump_number_at = Some(now);
+    }
+
+    /// Selects the script numbered by the typed digits, clamped to the
+    /// rows there are, and clears them.
+    pub fn finish_number_jump(&mut self) {
+        let number: usize = self.jump_number.parse().unwrap_or(usize::MAX);
+        self.cancel_number_jump();
+
+        let rows = self.numbered_indices();
+        if let Some(&i) = rows.get(number.clamp(1, rows.len().max(1)) - 1) {
+            self.selected_index = i;
+        }
+    }
+
+    pub fn cancel_number_jump(&mut self) {
+        self.jump_number.clear();
+        self.jump_number_at = None;
+    }
+
+    /// Jumps once no digit has come for `NUMBER_JUMP_TIMEOUT`. Returns
+    /// whether it did, marking the app `dirty` if so.
+    pub fn expire_number_jump(&mut self, now: Instant) -> bool {
+        match self.jump_number_at {
+            Some(at) if now.duration_since(at) >= NUMBER_JUMP_TIMEOUT => {
+                self.finish_number_jump();
+                self.dirty = true;
+                true
+            }
+            _ => false,
         }
     }
 
     pub fn previous(&mut self) {
-        if self.selected_index > 0 {
-            self.selected_index -= 1;
+        let visible = self.navigable_indices();
+        match visible.iter().position(|&i| i == self.selected_index) {
+            Some(pos) if pos > 0 => {
+                self.selected_index = visible[pos - 1];
+            }
+            Some(_) => {}
+            None => {
+                if let Some(&first) = visible.first() {
+                    self.selected_index = first;
+                }
+            }
+        }
+    }
+
+    pub fn start_filter(&mut self) {
+        self.overlay = Some(Overlay::Filter);
+    }
+
+    /// Stops typing into the filter but keeps it applied.
+    pub fn confirm_filter(&mut self) {
+        self.close(Overlay::Filter);
+    }
+
+    pub fn clear_filter(&mut self) {
+        self.filter_query.clear();
+        self.close(Overlay::Filter);
+    }
+
+    /// Closes `overlay` if it's the one open.
+    fn close(&mut self, overlay: Overlay) {
+        if self.overlay == Some(overlay) {
+            self.overlay = None;
+        }
+    }
+
+    pub fn push_filter_char(&mut self, c: char) {
+        self.filter_query.push(c);
+        self.reselect_within_filter();
+    }
+
+    pub fn pop_filter_char(&mut self) {
+        self.filter_query.pop();
+        self.reselect_within_filter();
+    }
+
+    /// Every tag in use, sorted.
+    pub fn all_tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self
+            .scripts
+            .iter()
+            .flat_map(|s| s.tags.iter().cloned())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        tags.sort();
+        tags
+    }
+
+    /// Steps the tag filter: off, each tag in turn, untagged, then off.
+    pub fn cycle_tag_filter(&mut self) {
+        let tags = self.all_tags();
+        self.tag_filter = match self.tag_filter.take() {
+            None => tags.first().cloned().map(TagFilter::Tag),
+            Some(TagFilter::Tag(current)) => {
+                let next = tags.iter().position(|t| *t == current).map(|i| i + 1);
+                match next.and_then(|i| tags.get(i)) {
+                    Some(tag) => Some(TagFilter::Tag(tag.clone())),
+                    None => Some(TagFilter::Untagged),
+                }
+            }
+            Some(TagFilter::Untagged) => None,
+        };
+        self.reselect_within_filter();
+    }
+
+    /// Shows only the scripts whose last run failed, or everything again.
+    pub fn toggle_failed_only(&mut self) {
+        self.failed_only = !self.failed_only;
+        self.reselect_within_filter();
+    }
+
+    /// Moves the selection to the first match if the filter hid it.
+    fn reselect_within_filter(&mut self) {
+        let stops = self.navigable_indices();
+        if stops.contains(&self.selected_index) {
+            return;
+        }
+        if let Some(&first) = stops.first() {
+            self.selected_index = first;
+        }
+    }
+
+    /// Selects the last script run again and restores its arguments.
+    /// Returns false if there's nothing to re-run yet, the script is
+    /// gone after a rescan, or a run is still going.
+    pub fn select_last_run(&mut self) -> bool {
+        if self.running.is_some() || self.batch.is_some() {
+            return false;
         }
+        let Some((path, args)) = &self.last_run else {
+            return false;
+        };
+        let Some(index) = self.scripts.iter().position(|s| s.path == *path) else {
+            return false;
+        };
+
+        self.selected_index = index;
+        self.pending_args = args.clone();
+        true
     }
 
+    /// The selected script's absolute path, to remember it by.
+    pub fn selected_path(&self) -> Option<String> {
+        self.scripts
+            .get(self.selected_index)
+            .map(|script| favorite_key(&script.path))
+    }
+
+    /// Selects the script at the absolute path `path`, or the first
+    /// one if it's gone.
+    pub fn restore_selection(&mut self, path: &str) {
+        self.selected_index = self
+            .scripts
+            .iter()
+            .position(|script| favorite_key(&script.path) == path)
+            .unwrap_or(0);
+    }
+
+    /// Quits, or asks first while a script is still running.
     pub fn quit(&mut self) {
+        if self.running.is_some() {
+            self.overlay = Some(Overlay::QuitPrompt);
+        } else {
+            self.should_quit = true;
+        }
+    }
+
+    /// Kills any running script and quits.
+    pub fn confirm_quit(&mut self) {
+        self.close(Overlay::QuitPrompt);
+        self.cancel_run();
         self.should_quit = true;
     }
 
+    pub fn cancel_quit(&mut self) {
+        self.close(Overlay::QuitPrompt);
+    }
+
     pub fn scroll_output_up(&mut self) {
         if self.output_scroll > 0 {
             self.output_scroll -= 1;
+            self.follow_output = false;
         }
     }
 
@@ -61,108 +1005,2260 @@ impl App {
         if self.output_scroll < max_scroll {
             self.output_scroll += 1;
         }
+        if self.output_scroll >= max_scroll {
+            self.follow_output = true;
+        }
     }
 
-    pub fn show_help(&mut self) {
-        self.showing_help = true;
+    pub fn scroll_output_to_top(&mut self) {
+        self.output_scroll = 0;
+        self.follow_output = false;
     }
 
-    pub fn hide_help(&mut self) {
-        self.showing_help = false;
+    pub fn scroll_output_to_bottom(&mut self, max_scroll: usize) {
+        self.output_scroll = max_scroll;
+        self.follow_output = true;
     }
 
-    pub fn back_to_list(&mut self) {
-        self.viewing_output = false;
-        self.output_text.clear();
-        self.output_scroll = 0;
+    /// Scrolls up by `page` lines, stopping at the top.
+    pub fn page_output_up(&mut self, page: usize) {
+        self.output_scroll = self.output_scroll.saturating_sub(page);
+        self.follow_output = false;
     }
-}
 
-pub fn extract_description(path: &str) -> Result<Option<String>, io::Error> {
-    let file = fs::File::open(path)?;
-    let reader = io::BufReader::new(file);
+    /// Scrolls down by `page` lines, stopping at `max_scroll`.
+    pub fn page_output_down(&mut self, page: usize, max_scroll: usize) {
+        self.output_scroll = (self.output_scroll + page).min(max_scroll);
+        if self.output_scroll >= max_scroll {
+            self.follow_output = true;
+        }
+    }
 
-    for line_result in reader.lines() {
-        let line = line_result?;
-        let trimmed = line.trim();
+    /// Scrolls up by half of a `viewport` lines tall, like vim's Ctrl-U.
+    pub fn half_page_up(&mut self, viewport: usize) {
+        self.page_output_up((viewport / 2).max(1));
+    }
 
-        if trimmed.is_empty() || trimmed.starts_with("#!") {
-            continue;
-        }
+    /// Scrolls down by half of a `viewport` lines tall, stopping once
+    /// the last line is at its bottom.
+    pub fn half_page_down(&mut self, viewport: usize) {
+        let max_scroll = self.output_rows().len().saturating_sub(viewport);
+        self.page_output_down((viewport / 2).max(1), max_scroll);
+    }
 
-        let desc = if let Some(d) = trimmed.strip_prefix('#') {
-            Some(d)
-        } else if let Some(d) = trimmed.strip_prefix("//") {
-            Some(d)
-        } else if let Some(d) = trimmed.strip_prefix("--") {
-            Some(d)
-        } else {
-            None
-        };
+    /// Rows of output as laid out in the output view.
+    pub fn output_rows(&self) -> Vec<&str> {
+        display_rows(&self.output_text, self.output_width(), self.wrap_output)
+    }
 
-        if let Some(d) = desc {
-            let cleaned = d.trim().to_string();
-            if !cleaned.is_empty() {
-                return Ok(Some(cleaned));
-            }
-            continue;
+    /// Columns left for output text beside the line number gutter.
+    pub fn output_width(&self) -> usize {
+        if self.show_line_numbers {
+            self.viewport_width.saturating_sub(gutter_width(&self.output_text))
+        } else {
+            self.viewport_width
         }
+    }
+
+    /// Measures the views for a terminal of `size` and pulls the scroll
+    /// positions back into range for it.
+    pub fn resize(&mut self, size: Rect) {
+        self.viewport_height = ui::scroll_view_height(size);
+        self.viewport_width = ui::scroll_view_width(size);
+        self.list_area = ui::list_rows_area(size);
 
-        break;
+        self.relayout_output();
+        let source_lines = self.source_text.lines().count();
+        self.source_scroll = self
+            .source_scroll
+            .min(source_lines.saturating_sub(self.viewport_height));
+        let help_lines = input::help_lines().len();
+        self.help_scroll = self
+            .help_scroll
+            .min(help_lines.saturating_sub(self.viewport_height));
+        self.dirty = true;
     }
 
-    Ok(None)
-}
+    /// Largest useful `output_scroll` for the current output.
+    pub fn max_output_scroll(&self) -> usize {
+        self.output_rows().len().saturating_sub(self.viewport_height)
+    }
 
-pub fn scan_directory(directory: &str) -> Result<Vec<Script>, io::Error> {
-    let mut scripts = Vec::new();
-    scan_directory_recursive(directory, None, &mut scripts)?;
-    Ok(scripts)
-}
+    pub fn start_output_search(&mut self) {
+        self.searching_output = true;
+        self.output_search = Some(String::new());
+        self.output_matches.clear();
+    }
 
-fn scan_directory_recursive(
-    directory: &str,
-    category: Option<String>,
-    scripts: &mut Vec<Script>,
-) -> Result<(), io::Error> {
-    let entries = fs::read_dir(directory)?;
+    pub fn push_search_char(&mut self, c: char) {
+        if let Some(query) = self.output_search.as_mut() {
+            query.push(c);
+        }
+        self.update_output_matches();
+    }
 
-    for entry_result in entries {
-        let entry = entry_result?;
-        let path = entry.path();
+    pub fn pop_search_char(&mut self) {
+        if let Some(query) = self.output_search.as_mut() {
+            query.pop();
+        }
+        self.update_output_matches();
+    }
 
-        if path.is_dir() {
-            let subdir_name = path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("unknown")
-                .to_string();
+    /// Stops typing the search and jumps to the first match at or
+    /// below the current scroll position.
+    pub fn confirm_output_search(&mut self) {
+        self.searching_output = false;
+        if self.output_search.as_deref().is_some_and(str::is_empty) {
+            self.output_search = None;
+        }
+        let first = self
+            .output_matches
+            .iter()
+            .find(|&&row| row >= self.output_scroll)
+            .or(self.output_matches.first())
+            .copied();
+        if let Some(row) = first {
+            self.scroll_output_to_row(row);
+        }
+    }
 
-            let path_str = path.to_str().unwrap_or("").to_string();
+    pub fn cancel_output_search(&mut self) {
+        self.searching_output = false;
+        self.output_search = None;
+        self.output_matches.clear();
+    }
 
-            scan_directory_recursive(&path_str, Some(subdir_name), scripts)?;
-            continue;
+    /// Scrolls to the next match below the top row, wrapping around.
+    pub fn next_match(&mut self) {
+        let next = self
+            .output_matches
+            .iter()
+            .find(|&&row| row > self.output_scroll)
+            .or(self.output_matches.first())
+            .copied();
+        if let Some(row) = next {
+            self.scroll_output_to_row(row);
+        }
+    }
+
+    /// Scrolls to the previous match above the top row, wrapping around.
+    pub fn previous_match(&mut self) {
+        let previous = self
+            .output_matches
+            .iter()
+            .rev()
+            .find(|&&row| row < self.output_scroll)
+            .or(self.output_matches.last())
+            .copied();
+        if let Some(row) = previous {
+            self.scroll_output_to_row(row);
         }
+    }
 
-        let metadata = fs::metadata(&path)?;
-        let permissions = metadata.permissions();
+    fn scroll_output_to_row(&mut self, row: usize) {
+        self.output_scroll = row.min(self.max_output_scroll());
+        self.follow_output = false;
+    }
 
-        if permissions.mode() & 0o111 != 0 {
-            let name = path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("unknown")
-                .to_string();
+    /// Recomputes `output_matches` for the current output and search.
+    /// Matching ignores case.
+    pub fn update_output_matches(&mut self) {
+        let query = match &self.output_search {
+            Some(query) if !query.is_empty() => query.to_lowercase(),
+            _ => {
+                self.output_matches.clear();
+                return;
+            }
+        };
+
+        self.output_matches = self
+            .output_rows()
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| row.to_lowercase().contains(&query))
+            .map(|(i, _)| i)
+            .collect();
+    }
+
+    /// Switches output word wrap, keeping the scroll position in range.
+    pub fn toggle_wrap(&mut self) {
+        self.wrap_output = !self.wrap_output;
+        self.relayout_output();
+    }
+
+    /// Shows or hides line numbers. Wrapped output gives up columns to
+    /// the gutter, so its rows are laid out again.
+    pub fn toggle_line_numbers(&mut self) {
+        self.show_line_numbers = !self.show_line_numbers;
+        self.relayout_output();
+    }
 
-            let path_str = path.to_str().unwrap_or("").to_string();
+    /// Recomputes matches and the scroll range after the output rows
+    /// changed shape.
+    fn relayout_output(&mut self) {
+        self.update_output_matches();
+        let max_scroll = self.max_output_scroll();
+        if self.follow_output || self.output_scroll > max_scroll {
+            self.output_scroll = max_scroll;
+        }
+    }
+
+    /// Appends streamed output, keeping the view pinned to the bottom
+    /// while `follow_output` is set.
+    pub fn append_output(&mut self, chunk: &str) {
+        self.output_text.push_str(chunk);
+        self.update_output_matches();
+        self.follow_to_bottom();
+    }
+
+    fn follow_to_bottom(&mut self) {
+        if self.follow_output {
+            self.output_scroll = self.max_output_scroll();
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// Whether the output view is showing, running or not.
+    pub fn viewing_output(&self) -> bool {
+        matches!(self.mode, AppMode::Running | AppMode::Output)
+    }
+
+    /// Moves from watching a run to reading its result once it ends.
+    fn finish_running_mode(&mut self) {
+        if self.mode == AppMode::Running {
+            self.mode = AppMode::Output;
+        }
+    }
+
+    /// The spinner frame to show, while a script is running.
+    pub fn spinner(&self) -> Option<char> {
+        self.running
+            .as_ref()
+            .map(|_| SPINNER_FRAMES[self.spinner_frame % SPINNER_FRAMES.len()])
+    }
+
+    /// Spawns the selected script and switches to the output view.
+    /// Output is collected by `poll_run`. Interactive scripts are only
+    /// queued in `interactive_request`, since running them needs the
+    /// terminal.
+    pub fn start_run(&mut self) -> Result<(), io::Error> {
+        if let Some(path) = self.selected_script().map(|s| s.path.clone()) {
+            self.ensure_metadata(&path);
+        }
+        let Some(script) = self.selected_script() else {
+            return Ok(());
+        };
+
+        let args = split_args(&self.pending_args);
+        let dir = working_dir(script, self.working_dir);
+        if script.interactive || self.force_interactive {
+            let path = script.path.clone();
+            let interpreter = script.interpreter.clone();
+            let run_command = script.run_command.clone();
+            self.force_interactive = false;
+            self.interactive_request = Some(InteractiveRun {
+                path,
+                interpreter,
+                run_command,
+                args,
+                dir,
+            });
+            return Ok(());
+        }
+
+        let mut running = RunningScript::spawn(
+            &script.path,
+            script.interpreter.as_deref(),
+            script.run_command.as_deref(),
+            &args,
+            &self.env,
+            dir.as_deref(),
+            self.capture,
+        )?;
+        running.invalid_utf8 = self.invalid_utf8;
+        running.ascii = self.ascii;
+        running.limit_lines(self.max_output_lines);
+        self.remember_output_scroll();
+        self.status_message = None;
+
+        self.output_text = run::format_output(
+            &format!("Running script...\nPID: {}", running.id()),
+            &running.details(),
+            "",
+            "",
+        );
+        self.last_status = Some(RunStatus::Running);
+        self.output_scroll = 0;
+        self.follow_output = true;
+        self.mode = AppMode::Running;
+        self.running = Some(running);
+        Ok(())
+    }
+
+    /// Shows the command the selected script would run with, without
+    /// running it.
+    pub fn dry_run(&mut self) {
+        let Some(script) = self.selected_script() else {
+            return;
+        };
+
+        let preview = run::preview(
+            &script.path,
+            script.interpreter.as_deref(),
+            script.run_command.as_deref(),
+            &split_args(&self.pending_args),
+            &self.env,
+            working_dir(script, self.working_dir).as_deref(),
+        );
+        self.output_text = format!(
+            "{} Dry run, nothing was executed\nPress any key to return\n\n{}",
+            ui::glyph(self.ascii, "◌"),
+            preview
+        );
+        self.last_status = None;
+        self.status_message = None;
+        self.output_scroll = 0;
+        self.follow_output = false;
+        self.mode = AppMode::Output;
+    }
+
+    /// The selected script's command as a line to paste into a shell,
+    /// with the working directory, environment and pending arguments.
+    pub fn command_line(&self) -> Option<String> {
+        let script = self.selected_script()?;
+        Some(run::command_line(
+            &script.path,
+            script.interpreter.as_deref(),
+            script.run_command.as_deref(),
+            &split_args(&self.pending_args),
+            &self.env,
+            working_dir(script, self.working_dir).as_deref(),
+        ))
+    }
+
+    /// Copies `command_line` to the clipboard, reporting the result in
+    /// `status_message`.
+    pub fn copy_command(&mut self) {
+        let Some(line) = self.command_line() else {
+            return;
+        };
+
+        self.status_message = Some(match clipboard::copy(&line) {
+            Ok(()) => format!("Copied: {}", line),
+            Err(e) => format!("{} {}", ui::glyph(self.ascii, "✗"), e),
+        });
+    }
+
+    /// Kills the running script and shows what it printed so far.
+    pub fn cancel_run(&mut self) {
+        let Some(mut running) = self.running.take() else {
+            return;
+        };
+        self.finish_running_mode();
+
+        let (state, status) = match running.kill() {
+            Ok(status) => (status.to_string(), RunStatus::from_exit_status(status)),
+            Err(e) => (format!("unknown ({})", e), RunStatus::Failure(-1)),
+        };
+        let code = status.exit_code();
+        self.record_run(&running.path, status, running.elapsed());
+        let output = run::format_output(
+            &format!("{} Script terminated by user\nExit state: {}", ui::glyph(self.ascii, "✗"), state),
+            &running.details(),
+            &running.stdout.text(),
+            &running.stderr.text(),
+        );
+
+        // Cancelling a batch script cancels the rest of the batch too
+        if let Some(batch) = self.batch.as_mut() {
+            batch.skip_rest();
+            batch.record(&output, code);
+            self.show_batch_output(None);
+            return;
+        }
+        self.output_text = output;
+        self.update_output_matches();
+    }
+
+    /// How long the event loop should wait for input before polling
+    /// the running script again.
+    pub fn poll_interval(&self) -> Duration {
+        if self.running.is_some()
+            || !self.jump_number.is_empty()
+            || self.metadata_updates.is_some()
+        {
+            ACTIVE_POLL_INTERVAL
+        } else {
+            IDLE_POLL_INTERVAL
+        }
+    }
+
+    /// Picks up new output from the running script, finishing the run
+    /// once it has exited. Returns whether the output changed, marking
+    /// the app `dirty` if so.
+    pub fn poll_run(&mut self) -> bool {
+        let Some(running) = self.running.as_mut() else {
+            return false;
+        };
+
+        let dropped = running.stdout.dropped();
+        let mut received = running.drain();
+        // Lines dropped off the top move the rest up; keep what's on
+        // screen in place unless following the end
+        let shift = running.stdout.dropped() - dropped;
+        if !self.follow_output {
+            self.output_scroll = self.output_scroll.saturating_sub(shift);
+        }
+        let mut diagnostics = None;
+        let mut finished = match running.try_finish() {
+            Ok(Some(status)) => {
+                if self.verbose_failures && !status.success() {
+                    diagnostics = Some(running.diagnostics(status));
+                }
+                Some((run::result_banner(status, self.ascii), RunStatus::from_exit_status(status)))
+            }
+            Ok(None) => None,
+            Err(e) => Some((
+                format!("{} Script failed\nError: {}", ui::glyph(self.ascii, "✗"), e),
+                RunStatus::Failure(-1),
+            )),
+        };
+
+        if finished.is_none()
+            && let Some(timeout) = self.timeout
+            && running.elapsed() >= timeout
+        {
+            // Reaping can only fail if the child is already gone
+            let _ = running.kill();
+            received = true;
+            finished = Some((
+                format!(
+                    "{} Script timed out after {}s\nPID: {}",
+                    ui::glyph(self.ascii, "✗"),
+                    timeout.as_secs(),
+                    running.id()
+                ),
+                RunStatus::Failure(-1),
+            ));
+        }
+
+        // Keep redrawing while the spinner turns, even with no output
+        let frame = (running.elapsed().as_millis() / SPINNER_INTERVAL.as_millis()) as usize;
+        if frame != self.spinner_frame {
+            self.spinner_frame = frame;
+            self.dirty = true;
+        }
+
+        if !received && finished.is_none() {
+            return false;
+        }
+
+        let banner = match &finished {
+            Some((banner, _)) => banner.clone(),
+            None => format!("Running script...\nPID: {}", running.id()),
+        };
+        let mut output = run::format_output(
+            &banner,
+            &running.details(),
+            &running.stdout.text(),
+            &running.stderr.text(),
+        );
+        if let Some(diagnostics) = diagnostics {
+            output.push_str("\n\n");
+            output.push_str(&diagnostics);
+        }
+
+        let status = finished.map(|(_, status)| status);
+        let code = status.map(RunStatus::exit_code);
+        let mut finished_path = None;
+        if let Some(status) = status
+            && let Some(running) = self.running.take()
+        {
+            self.finish_running_mode();
+            self.record_run(&running.path, status, running.elapsed());
+            finished_path = Some(running.path);
+        }
+
+        if self.batch.is_none() {
+            self.output_text = output;
+            self.update_output_matches();
+            self.follow_to_bottom();
+            if let Some(path) = finished_path {
+                self.restore_output_scroll(&path);
+                self.output_path = Some(path);
+            }
+        } else if let Some(code) = code {
+            self.finish_batch_step(&output, code);
+        } else {
+            self.show_batch_output(Some(&output));
+        }
+        self.dirty = true;
+        true
+    }
+
+    pub fn toggle_mark(&mut self) {
+        let Some(path) = self.selected_script().map(|s| s.path.clone()) else {
+            return;
+        };
+        if !self.marked.remove(&path) {
+            self.marked.insert(path);
+        }
+    }
+
+    /// Runs every marked script one after another, in list order,
+    /// collecting their output into one log.
+    pub fn start_batch(&mut self) {
+        let queue: VecDeque<String> = self
+            .scripts
+            .iter()
+            .filter(|s| self.marked.contains(&s.path))
+            .map(|s| s.path.clone())
+            .collect();
+        self.run_batch(queue);
+    }
+
+    /// Runs every script the filter shows in the selected script's
+    /// category as a batch. Does nothing while the list isn't grouped
+    /// or the category is collapsed.
+    pub fn run_category(&mut self) {
+        let Some(group) = self.selected_script().map(|s| self.group_of(s).to_string()) else {
+            return;
+        };
+        if !self.grouped() {
+            return;
+        }
+        let queue: VecDeque<String> = self
+            .filtered_indices()
+            .into_iter()
+            .map(|i| &self.scripts[i])
+            .filter(|s| self.group_of(s) == group)
+            .map(|s| s.path.clone())
+            .collect();
+        self.run_batch(queue);
+    }
+
+    fn run_batch(&mut self, queue: VecDeque<String>) {
+        if self.running.is_some() || queue.is_empty() {
+            return;
+        }
+
+        self.batch = Some(BatchRun {
+            ascii: self.ascii,
+            ..BatchRun::new(queue)
+        });
+        self.status_message = None;
+        self.output_scroll = 0;
+        self.follow_output = true;
+        self.mode = AppMode::Output;
+        self.run_next_in_batch();
+    }
+
+    /// Starts the next queued batch script, or shows the summary once
+    /// the queue is empty. A script with a `herring-confirm:` marker
+    /// waits for `answer_batch_confirm` instead.
+    fn run_next_in_batch(&mut self) {
+        let Some(batch) = self.batch.as_mut() else {
+            return;
+        };
+        let Some(path) = batch.queue.pop_front() else {
+            self.show_batch_output(None);
+            return;
+        };
+        self.ensure_metadata(&path);
+        let Some(batch) = self.batch.as_mut() else {
+            return;
+        };
+
+        let script = self.scripts.iter().find(|s| s.path == path);
+        batch.current = script.map_or_else(|| path.clone(), |s| s.name.clone());
+        if script.is_some_and(|s| s.confirm_message.is_some()) {
+            batch.awaiting_confirm = Some(path);
+            self.mode = AppMode::Output;
+            self.show_batch_output(None);
+            return;
+        }
+        self.spawn_batch_script(path);
+    }
+
+    /// Answers a batch script's confirmation: runs it, or skips just
+    /// that script and goes on with the rest.
+    pub fn answer_batch_confirm(&mut self, run: bool) {
+        let Some(batch) = self.batch.as_mut() else {
+            return;
+        };
+        let Some(path) = batch.awaiting_confirm.take() else {
+            return;
+        };
+        if run {
+            self.spawn_batch_script(path);
+        } else {
+            batch.skipped += 1;
+            self.run_next_in_batch();
+        }
+    }
+
+    /// Whether the batch is waiting on a script's confirmation.
+    pub fn batch_awaiting_confirm(&self) -> bool {
+        self.batch.as_ref().is_some_and(|b| b.awaiting_confirm.is_some())
+    }
+
+    /// Starts one batch script. One that can't be started counts as a
+    /// failure.
+    fn spawn_batch_script(&mut self, path: String) {
+        let script = self.scripts.iter().find(|s| s.path == path);
+        let interpreter = script.and_then(|s| s.interpreter.as_deref());
+        let run_command = script.and_then(|s| s.run_command.as_deref());
+        let dir = script.and_then(|s| working_dir(s, self.working_dir));
+
+        let spawned = RunningScript::spawn(
+            &path,
+            interpreter,
+            run_command,
+            &[],
+            &self.env,
+            dir.as_deref(),
+            self.capture,
+        );
+        match spawned {
+            Ok(mut running) => {
+                running.invalid_utf8 = self.invalid_utf8;
+                running.limit_lines(self.max_output_lines);
+                let output = run::format_output(
+                    &format!("Running script...\nPID: {}", running.id()),
+                    &running.details(),
+                    "",
+                    "",
+                );
+                self.running = Some(running);
+                self.mode = AppMode::Running;
+                self.show_batch_output(Some(&output));
+            }
+            Err(e) => {
+                self.record_run(&path, RunStatus::Failure(-1), Duration::ZERO);
+                self.finish_batch_step(
+                    &format!("{} Error running script:\n{}", ui::glyph(self.ascii, "✗"), e),
+                    -1,
+                );
+            }
+        }
+    }
+
+    /// Logs a finished batch script and moves on, unless it failed and
+    /// `batch_on_failure` says to stop or ask.
+    fn finish_batch_step(&mut self, output: &str, exit_code: i32) {
+        let Some(batch) = self.batch.as_mut() else {
+            return;
+        };
+        batch.record(output, exit_code);
+
+        if exit_code != 0 && !batch.queue.is_empty() {
+            match self.batch_on_failure {
+                OnFailure::Continue => {}
+                OnFailure::Stop => batch.skip_rest(),
+                OnFailure::Ask => {
+                    batch.awaiting_decision = true;
+                    self.show_batch_output(None);
+                    return;
+                }
+            }
+        }
+        self.run_next_in_batch();
+    }
+
+    /// Answers the failure prompt: runs the rest of the batch, or
+    /// skips it and shows the summary.
+    pub fn resume_batch(&mut self, keep_going: bool) {
+        let Some(batch) = self.batch.as_mut() else {
+            return;
+        };
+        batch.awaiting_decision = false;
+        if !keep_going {
+            batch.skip_rest();
+        }
+        self.run_next_in_batch();
+    }
+
+    /// Whether the batch is waiting on the failure prompt.
+    pub fn batch_awaiting_decision(&self) -> bool {
+        self.batch.as_ref().is_some_and(|b| b.awaiting_decision)
+    }
+
+    /// Whether every script in the batch has run or been skipped.
+    pub fn batch_finished(&self) -> bool {
+        self.batch
+            .as_ref()
+            .is_some_and(|b| {
+                b.queue.is_empty()
+                    && !b.awaiting_decision
+                    && b.awaiting_confirm.is_none()
+                    && self.running.is_none()
+            })
+    }
+
+    /// Shows the batch log followed by the running script's `output`,
+    /// or by the summary once the batch is over.
+    fn show_batch_output(&mut self, output: Option<&str>) {
+        let finished = self.batch_finished();
+        let Some(batch) = &self.batch else {
+            return;
+        };
+
+        let mut text = batch.log.clone();
+        if let Some(output) = output {
+            text.push_str(&batch.header());
+            text.push_str(output);
+        } else if finished {
+            text.push_str(&batch.summary());
+        }
+        self.output_text = text;
+        self.update_output_matches();
+        self.follow_to_bottom();
+    }
+
+    /// Shows the outcome of an interactive run, whose output went
+    /// straight to the terminal.
+    pub fn finish_interactive(
+        &mut self,
+        run: &InteractiveRun,
+        result: Result<ExitStatus, io::Error>,
+        duration: Duration,
+    ) {
+        let (banner, status) = match result {
+            Ok(status) => (
+                run::result_banner(status, self.ascii),
+                RunStatus::from_exit_status(status),
+            ),
+            Err(e) => (
+                format!("{} Error running script:\n{}", ui::glyph(self.ascii, "✗"), e),
+                RunStatus::Failure(-1),
+            ),
+        };
+        self.remember_output_scroll();
+        let command_line = if run.args.is_empty() {
+            String::new()
+        } else {
+            format!("Command: {}\n", format_command(&run.path, &run.args))
+        };
+
+        self.output_text = run::format_output(
+            &banner,
+            &command_line,
+            "(interactive run; output went to the terminal)\n",
+            "",
+        );
+        self.output_scroll = 0;
+        self.follow_output = true;
+        self.mode = AppMode::Output;
+        self.dirty = true;
+        self.record_run(&run.path, status, duration);
+    }
+
+    /// Adds a finished run to the history and `last_results`, saving
+    /// the history if a file is configured. A failed save is not worth
+    /// interrupting the UI for.
+    fn record_run(&mut self, path: &str, status: RunStatus, duration: Duration) {
+        self.last_exit_code = Some(status.shell_exit_code());
+        self.last_status = Some(status);
+        self.history.record(path, status);
+        self.last_results
+            .insert(path.to_string(), RunResult { status, duration });
+        if let Some(history_path) = &self.history_path {
+            let _ = self.history.save_to(history_path);
+        }
+
+        // The list keeps its order until the next sort or rescan
+        let count = self.run_counts.increment(&favorite_key(path));
+        if let Some(script) = self.scripts.iter_mut().find(|s| s.path == path) {
+            script.run_count = count;
+        }
+        if let Some(run_counts_path) = &self.run_counts_path {
+            let _ = self.run_counts.save_to(run_counts_path);
+        }
+    }
+
+    pub fn show_warnings(&mut self) {
+        if !self.scan_warnings.is_empty() {
+            self.overlay = Some(Overlay::Warnings);
+        }
+    }
+
+    pub fn hide_warnings(&mut self) {
+        self.close(Overlay::Warnings);
+    }
+
+    pub fn show_info(&mut self) {
+        if self.selected_script().is_some() {
+            self.overlay = Some(Overlay::Info);
+        }
+    }
+
+    pub fn hide_info(&mut self) {
+        self.close(Overlay::Info);
+    }
+
+    pub fn open_palette(&mut self) {
+        self.palette = Palette::default();
+        self.overlay = Some(Overlay::Palette);
+    }
+
+    pub fn close_palette(&mut self) {
+        self.close(Overlay::Palette);
+    }
+
+    pub fn push_palette_char(&mut self, c: char) {
+        self.palette.query.push(c);
+        self.palette.selected = 0;
+    }
+
+    pub fn pop_palette_char(&mut self) {
+        self.palette.query.pop();
+        self.palette.selected = 0;
+    }
+
+    pub fn next_palette_match(&mut self) {
+        if self.palette.selected + 1 < self.palette_matches().len() {
+            self.palette.selected += 1;
+        }
+    }
+
+    pub fn previous_palette_match(&mut self) {
+        self.palette.selected = self.palette.selected.saturating_sub(1);
+    }
+
+    /// Scripts the palette lists, best first, ignoring the list's own
+    /// filters. With no query, the recently run scripts come first,
+    /// then the favorites.
+    pub fn palette_matches(&self) -> Vec<usize> {
+        if self.overlay != Some(Overlay::Palette) {
+            return Vec::new();
+        }
+        let palette = &self.palette;
+
+        let mut matches = Vec::new();
+        if palette.query.trim().is_empty() {
+            let recent = self
+                .history
+                .entries
+                .iter()
+                .filter_map(|entry| self.scripts.iter().position(|s| s.path == entry.path));
+            let favorites = (0..self.scripts.len()).filter(|&i| self.scripts[i].favorite);
+            for i in recent.chain(favorites) {
+                if !matches.contains(&i) {
+                    matches.push(i);
+                }
+            }
+        } else {
+            let mut scored: Vec<(i64, usize)> = self
+                .scripts
+                .iter()
+                .enumerate()
+                .filter_map(|(i, s)| Some((palette::score_script(&palette.query, s)?, i)))
+                .collect();
+            // Best score first, list order among equals
+            scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+            matches = scored.into_iter().map(|(_, i)| i).collect();
+        }
+
+        matches.truncate(palette::PALETTE_LIMIT);
+        matches
+    }
+
+    /// Closes the palette and selects its highlighted script, clearing
+    /// the filters and expanding the group that would hide it. Returns
+    /// whether there was a script to select.
+    pub fn palette_jump(&mut self) -> bool {
+        let chosen = self.palette_matches().get(self.palette.selected).copied();
+        self.close_palette();
+        let Some(index) = chosen else {
+            return false;
+        };
+
+        self.reveal(index);
+        true
+    }
+
+    /// Selects the script at `index`, clearing the filters and
+    /// expanding the group if they would hide it.
+    fn reveal(&mut self, index: usize) {
+        if !self.matches_filter(&self.scripts[index]) {
+            self.clear_filter();
+            self.tag_filter = None;
+            self.failed_only = false;
+        }
+        let group = self.group_of(&self.scripts[index]).to_string();
+        self.collapsed.remove(&group);
+        self.selected_index = index;
+    }
+
+    pub fn show_history(&mut self) {
+        self.overlay = Some(Overlay::History);
+        self.history_index = 0;
+    }
+
+    pub fn hide_history(&mut self) {
+        self.close(Overlay::History);
+    }
+
+    /// Empties the history, on disk too, and the last-run badges, which
+    /// also drops the failed-only filter.
+    pub fn clear_history(&mut self) {
+        self.history.entries.clear();
+        self.last_results.clear();
+        self.history_index = 0;
+        self.failed_only = false;
+        if let Some(history_path) = &self.history_path
+            && let Err(e) = self.history.save_to(history_path)
+        {
+            self.status_message = Some(format!(
+                "{} Could not clear history: {}",
+                ui::glyph(self.ascii, "✗"),
+                e
+            ));
+        }
+    }
+
+    pub fn next_history(&mut self) {
+        if self.history_index + 1 < self.history.entries.len() {
+            self.history_index += 1;
+        }
+    }
+
+    pub fn previous_history(&mut self) {
+        self.history_index = self.history_index.saturating_sub(1);
+    }
+
+    /// Leaves the history view with the chosen entry's script selected,
+    /// clearing the filters or expanding its group if they hide it.
+    /// Returns false if the script is no longer in the list.
+    pub fn select_history_entry(&mut self) -> bool {
+        let Some(entry) = self.history.entries.get(self.history_index) else {
+            return false;
+        };
+        let Some(index) = self.scripts.iter().position(|s| s.path == entry.path) else {
+            return false;
+        };
+
+        self.close(Overlay::History);
+        self.reveal(index);
+        true
+    }
+
+    /// Opens the selected script's source in the preview view.
+    pub fn view_source(&mut self) {
+        let Some(script) = self.selected_script() else {
+            return;
+        };
+
+        self.source_text = match read_source(&script.path) {
+            Ok(text) => text,
+            Err(e) => format!("{} Could not read {}:\n{}", ui::glyph(self.ascii, "✗"), script.path, e),
+        };
+        self.source_scroll = 0;
+        self.overlay = Some(Overlay::Source);
+    }
+
+    /// Queues the selected script to be opened in `$EDITOR`, which
+    /// needs the terminal.
+    pub fn edit_selected(&mut self) {
+        if let Some(script) = self.selected_script() {
+            self.edit_request = Some(script.path.clone());
+        }
+    }
+
+    /// Reports how the editor exited, rescanning after a clean exit so
+    /// changed descriptions show up.
+    pub fn finish_edit(&mut self, editor: &str, result: Result<ExitStatus, io::Error>) {
+        self.dirty = true;
+        match result {
+            Ok(status) if status.success() => self.rescan(),
+            Ok(status) => {
+                self.status_message = Some(format!("{} {} exited with {}", ui::glyph(self.ascii, "✗"), editor, status));
+            }
+            Err(e) => {
+                self.status_message = Some(format!("{} Could not start {}: {}", ui::glyph(self.ascii, "✗"), editor, e));
+            }
+        }
+    }
+
+    pub fn close_source(&mut self) {
+        self.close(Overlay::Source);
+        self.source_text.clear();
+        self.source_scroll = 0;
+    }
+
+    pub fn scroll_source_up(&mut self) {
+        self.source_scroll = self.source_scroll.saturating_sub(1);
+    }
+
+    pub fn scroll_source_down(&mut self, max_scroll: usize) {
+        if self.source_scroll < max_scroll {
+            self.source_scroll += 1;
+        }
+    }
+
+    pub fn show_help(&mut self) {
+        self.mode = AppMode::Help;
+        self.help_scroll = 0;
+    }
+
+    pub fn hide_help(&mut self) {
+        self.mode = AppMode::List;
+    }
+
+    pub fn scroll_help_up(&mut self) {
+        self.help_scroll = self.help_scroll.saturating_sub(1);
+    }
+
+    pub fn scroll_help_down(&mut self, max_scroll: usize) {
+        if self.help_scroll < max_scroll {
+            self.help_scroll += 1;
+        }
+    }
+
+    pub fn start_args(&mut self) {
+        self.overlay = Some(Overlay::Args);
+    }
+
+    /// Leaves the `a` prompt keeping what was typed, for the run.
+    pub fn finish_args(&mut self) {
+        self.close(Overlay::Args);
+    }
+
+    pub fn push_args_char(&mut self, c: char) {
+        self.pending_args.push(c);
+    }
+
+    pub fn pop_args_char(&mut self) {
+        self.pending_args.pop();
+    }
+
+    pub fn cancel_args(&mut self) {
+        self.close(Overlay::Args);
+        self.pending_args.clear();
+    }
+
+    /// Whether running the selected script needs a y/n answer first:
+    /// always when `require_confirmation` is set, otherwise only for
+    /// scripts with a `herring-confirm:` marker.
+    pub fn needs_confirmation(&self) -> bool {
+        self.selected_script()
+            .is_some_and(|script| self.require_confirmation || script.confirm_message.is_some())
+    }
+
+    /// Asks for confirmation before the next run.
+    pub fn request_confirm(&mut self) {
+        self.overlay = Some(Overlay::Confirm);
+    }
+
+    /// Accepts the confirmation; the caller then runs the script.
+    pub fn confirm_run(&mut self) {
+        self.close(Overlay::Confirm);
+    }
+
+    pub fn cancel_confirm(&mut self) {
+        self.force_interactive = false;
+        self.close(Overlay::Confirm);
+        self.pending_args.clear();
+    }
+
+    pub fn toggle_absolute_paths(&mut self) {
+        self.show_absolute_paths = !self.show_absolute_paths;
+    }
+
+    pub fn toggle_compact(&mut self) {
+        self.compact = !self.compact;
+    }
+
+    /// Path of `script` as currently shown, absolute or relative to `root`.
+    /// Without a single root (several directories were scanned) the
+    /// scanned path is already absolute.
+    pub fn display_path(&self, script: &Script) -> String {
+        if self.show_absolute_paths && self.root.as_os_str().is_empty() {
+            script.path.clone()
+        } else if self.show_absolute_paths {
+            self.root.join(&script.relative_path).display().to_string()
+        } else {
+            script.relative_path.clone()
+        }
+    }
+
+    /// Title for the list view, expanded from `title_template`.
+    pub fn title(&self) -> String {
+        let categories: HashSet<&str> =
+            self.scripts.iter().filter_map(|s| s.category.as_deref()).collect();
+
+        self.title_template
+            .as_deref()
+            .unwrap_or(DEFAULT_TITLE_TEMPLATE)
+            .replace("{count}", &self.scripts.len().to_string())
+            .replace("{categories}", &categories.len().to_string())
+            .replace("{dir}", &self.root.display().to_string())
+    }
+
+    /// Scrolls back to where `path`'s output was left last time, if it
+    /// came out the same.
+    fn restore_output_scroll(&mut self, path: &str) {
+        if let Some(&(hash, scroll)) = self.output_scrolls.get(path)
+            && hash == output_hash(&self.output_text)
+        {
+            self.output_scroll = scroll.min(self.max_output_scroll());
+            self.follow_output = false;
+        }
+    }
+
+    /// Keeps where the finished output on show was scrolled to, for
+    /// the next time its script prints the same.
+    fn remember_output_scroll(&mut self) {
+        if let Some(path) = self.output_path.take() {
+            let saved = (output_hash(&self.output_text), self.output_scroll);
+            self.output_scrolls.insert(path, saved);
+        }
+    }
+
+    pub fn back_to_list(&mut self) {
+        self.remember_output_scroll();
+        self.mode = AppMode::List;
+        // A script that passed this time drops out of the failed list
+        if self.failed_only {
+            self.reselect_within_filter();
+        }
+        self.output_text.clear();
+        self.last_status = None;
+        self.output_scroll = 0;
+        self.follow_output = true;
+        self.status_message = None;
+        self.batch = None;
+        self.cancel_output_search();
+    }
+
+    /// Writes the current output to a timestamped log in `dir` and
+    /// reports the result in `status_message`. A batch's log is named
+    /// for the batch and kept whole, as it starts with no banner.
+    pub fn save_output(&mut self, dir: &Path) {
+        let epoch = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let (name, text) = if self.batch.is_some() {
+            (batch_log_name(epoch), self.output_text.as_str())
+        } else {
+            // Named for the script that printed it, wherever the
+            // selection has moved since
+            let last_run = self.last_run.as_ref().map(|(path, _)| path.as_str());
+            let Some(path) = self.output_path.as_deref().or(last_run) else {
+                return;
+            };
+            let name = match self.scripts.iter().find(|s| s.path == path) {
+                Some(script) => script.name.clone(),
+                None => Path::new(path)
+                    .file_name()
+                    .map_or_else(|| path.to_string(), |name| name.to_string_lossy().into_owned()),
+            };
+            let text = if self.keep_banner_in_logs {
+                &self.output_text
+            } else {
+                run::strip_banner(&self.output_text)
+            };
+            (output_log_name(&name, epoch), text)
+        };
+        let path = dir.join(name);
+
+        self.status_message = Some(match fs::write(&path, text) {
+            Ok(()) => format!("Saved to {}", path.display()),
+            Err(e) => format!(
+                "{} Could not save {}: {}",
+                ui::glyph(self.ascii, "✗"),
+                path.display(),
+                e
+            ),
+        });
+    }
+}
+
+/// Identifies an output, to tell whether a rerun printed the same.
+fn output_hash(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Rows `text` takes up in a view `width` columns wide: one per line,
+/// or with `wrap`, each line cut into pieces of at most `width`
+/// characters.
+pub fn display_rows(text: &str, width: usize, wrap: bool) -> Vec<&str> {
+    numbered_rows(text, width, wrap)
+        .into_iter()
+        .map(|(_, row)| row)
+        .collect()
+}
+
+/// `display_rows`, each row paired with the index of the line of
+/// `text` it belongs to.
+pub fn numbered_rows(text: &str, width: usize, wrap: bool) -> Vec<(usize, &str)> {
+    if !wrap || width == 0 {
+        return text.lines().enumerate().collect();
+    }
+
+    let mut rows = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let mut rest = line;
+        loop {
+            let split = rest
+                .char_indices()
+                .nth(width)
+                .map(|(i, _)| i)
+                .unwrap_or(rest.len());
+            let (row, tail) = rest.split_at(split);
+            rows.push((number, row));
+            if tail.is_empty() {
+                break;
+            }
+            rest = tail;
+        }
+    }
+    rows
+}
+
+/// Columns taken by line numbers for `text`: enough digits for its
+/// last line, plus a space.
+pub fn gutter_width(text: &str) -> usize {
+    text.lines().count().max(1).to_string().len() + 1
+}
+
+/// File name for saved output, e.g. `herring-output-deploy.sh-1700000000.log`.
+/// Anything but letters, digits, `.`, `-` and `_` in the script name
+/// becomes `_`.
+pub fn output_log_name(script_name: &str, epoch: u64) -> String {
+    let safe: String = script_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("herring-output-{}-{}.log", safe, epoch)
+}
+
+/// File name for a saved batch log, e.g. `herring-batch-1700000000.log`.
+pub fn batch_log_name(epoch: u64) -> String {
+    format!("herring-batch-{}.log", epoch)
+}
+
+/// Largest source file read in full for the preview; anything past
+/// this is cut off with a notice.
+pub const SOURCE_PREVIEW_LIMIT: usize = 1024 * 1024;
+/// Bytes checked for NUL when deciding a file is binary.
+const BINARY_SNIFF_BYTES: usize = 8 * 1024;
+
+/// Reads a script for the source preview, capped at
+/// `SOURCE_PREVIEW_LIMIT`. Binary files are replaced by a short notice.
+pub fn read_source(path: &str) -> Result<String, io::Error> {
+    let size = fs::metadata(path)?.len();
+    let mut bytes = Vec::new();
+    fs::File::open(path)?
+        .take(SOURCE_PREVIEW_LIMIT as u64)
+        .read_to_end(&mut bytes)?;
+
+    let sniff = &bytes[..bytes.len().min(BINARY_SNIFF_BYTES)];
+    if sniff.contains(&0) {
+        return Ok(format!("(binary file, {} bytes; not shown)", size));
+    }
+
+    let mut text = String::from_utf8_lossy(&bytes).into_owned();
+    if size > SOURCE_PREVIEW_LIMIT as u64 {
+        text.push_str(&format!(
+            "\n--- truncated: showing the first {} of {} bytes ---",
+            SOURCE_PREVIEW_LIMIT, size
+        ));
+    }
+    Ok(text)
+}
+
+/// Splits an argument string on whitespace. Single and double quotes
+/// group words, and a backslash escapes the next character outside of
+/// single quotes.
+pub fn split_args(input: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('\''), c) => current.push(c),
+            (_, '\\') => {
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+                in_word = true;
+            }
+            (Some(_), c) => current.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    args.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if in_word {
+        args.push(current);
+    }
+
+    args
+}
+
+/// Directory `script` runs in under `mode`; `None` keeps herring's own.
+pub fn working_dir(script: &Script, mode: WorkingDir) -> Option<PathBuf> {
+    let dir = match mode {
+        WorkingDir::Script => Path::new(&script.path).parent()?,
+        WorkingDir::Root => Path::new(script.path.strip_suffix(&script.relative_path)?),
+        WorkingDir::Launch => return None,
+    };
+    // The parent of a bare file name is empty
+    let dir = if dir.as_os_str().is_empty() { Path::new(".") } else { dir };
+    std::path::absolute(dir).ok()
+}
+
+/// Renders a command line that can be pasted back into a shell.
+pub fn format_command(program: &str, args: &[String]) -> String {
+    let mut parts = vec![shell_quote(program)];
+    parts.extend(args.iter().map(|a| shell_quote(a)));
+    parts.join(" ")
+}
+
+/// Quotes `word` for a POSIX shell, leaving plain words alone.
+pub fn shell_quote(word: &str) -> String {
+    let safe = !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:,@%+".contains(c));
+
+    if safe {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', "'\\''"))
+    }
+}
+
+/// Details parsed from a script's leading comment block.
+#[derive(Debug, Default, PartialEq)]
+pub struct ScriptMetadata {
+    pub description: Option<String>,
+    /// The description with the comment lines right under it, when
+    /// there are any.
+    pub description_full: Option<String>,
+    /// Set by a `@pin` directive.
+    pub pinned: bool,
+    pub tags: Vec<String>,
+    /// Set by a `herring-interactive` marker.
+    pub interactive: bool,
+    /// From a `herring-run:` marker.
+    pub run_command: Option<String>,
+    /// From a `herring-confirm:` marker.
+    pub confirm_message: Option<String>,
+}
+
+/// Line comment prefixes recognised in script headers: shell and
+/// Python, C-like, SQL and Lua, Lisp and ini, TeX and Erlang, batch.
+pub const COMMENT_PREFIXES: &[&str] = &["#", "//", "--", ";", "%", "REM"];
+
+/// The comment prefixes of languages known by their extension, so a
+/// `//` path in a shell script or a `--flag` line in a Lisp file isn't
+/// mistaken for a comment.
+pub const EXTENSION_COMMENT_PREFIXES: &[(&[&str], &[&str])] = &[
+    (
+        &["sh", "bash", "zsh", "ksh", "fish", "py", "rb", "pl", "r", "ps1", "tcl", "awk", "nu"],
+        &["#"],
+    ),
+    (
+        &["js", "mjs", "cjs", "ts", "rs", "go", "c", "h", "cpp", "java", "kt", "kts", "swift", "scala", "dart", "groovy"],
+        &["//"],
+    ),
+    (&["php"], &["//", "#"]),
+    (&["sql", "lua", "hs"], &["--"]),
+    (&["el", "lisp", "clj", "scm", "rkt", "ini"], &[";"]),
+    (&["tex", "erl", "m"], &["%"]),
+    (&["bat", "cmd"], &["REM"]),
+];
+
+/// Comment prefixes for a script at `path`: those of its language if
+/// the extension is known, otherwise all of `COMMENT_PREFIXES`.
+pub fn comment_prefixes(path: &Path) -> &'static [&'static str] {
+    let Some(extension) = path.extension().and_then(|e| e.to_str()) else {
+        return COMMENT_PREFIXES;
+    };
+    EXTENSION_COMMENT_PREFIXES
+        .iter()
+        .find(|(extensions, _)| extensions.iter().any(|e| e.eq_ignore_ascii_case(extension)))
+        .map_or(COMMENT_PREFIXES, |&(_, prefixes)| prefixes)
+}
+
+/// Strips one of `prefixes`, returning it and the trimmed comment text.
+/// A prefix that is a word, like `REM`, must be followed by a space or
+/// the end of the line.
+fn comment_text<'a>(line: &'a str, prefixes: &[&'static str]) -> Option<(&'static str, &'a str)> {
+    prefixes.iter().find_map(|&prefix| {
+        let text = line.strip_prefix(prefix)?;
+        let is_word = prefix.ends_with(|c: char| c.is_alphanumeric());
+        if is_word && !text.is_empty() && !text.starts_with(char::is_whitespace) {
+            return None;
+        }
+        Some((prefix, text.trim()))
+    })
+}
+
+/// How far into a file `herring-` markers are looked for.
+const MARKER_SCAN_LINES: usize = 20;
+
+/// Explicit description marker, e.g. `# herring-desc: Reset the db`.
+const DESCRIPTION_MARKER: &str = "herring-desc:";
+
+/// Tag marker, e.g. `# herring-tags: deploy, db`.
+const TAGS_MARKER: &str = "herring-tags:";
+
+/// Marks a script that needs the real terminal, e.g. to read stdin.
+const INTERACTIVE_MARKER: &str = "herring-interactive";
+
+/// Run command marker, e.g. `# herring-run: /opt/venv/bin/python3 {}`.
+const RUN_MARKER: &str = "herring-run:";
+
+/// Confirmation marker, e.g. `# herring-confirm: Drops the prod db`.
+const CONFIRM_MARKER: &str = "herring-confirm:";
+
+/// Shared prefix of all markers; such comments are never descriptions.
+const MARKER_PREFIX: &str = "herring-";
+
+/// One comment line of a script's leading block.
+struct Comment {
+    line_number: usize,
+    prefix: &'static str,
+    text: String,
+}
+
+/// Comments found at the top of a script.
+struct Header {
+    /// The leading comment block, up to the first line of code.
+    leading: Vec<Comment>,
+    /// Every comment within the first `MARKER_SCAN_LINES` lines.
+    early: Vec<String>,
+}
+
+/// Whether the start of a file looks like a compiled program rather
+/// than text: a NUL byte, or bytes that aren't UTF-8. A character cut
+/// off by the end of `start` doesn't count.
+fn looks_binary(start: &[u8]) -> bool {
+    start.contains(&0)
+        || std::str::from_utf8(start).is_err_and(|e| e.error_len().is_some())
+}
+
+/// Reads a script's comments, skipping blank lines and any shebang.
+/// Binary files have none.
+fn read_header(path: &str) -> Result<Header, io::Error> {
+    let file = fs::File::open(path)?;
+    let mut reader = io::BufReader::new(file);
+    let mut header = Header {
+        leading: Vec::new(),
+        early: Vec::new(),
+    };
+
+    let start = reader.fill_buf()?;
+    if looks_binary(&start[..start.len().min(BINARY_SNIFF_BYTES)]) {
+        return Ok(header);
+    }
+    let prefixes = comment_prefixes(Path::new(path));
+    let mut in_leading_block = true;
+
+    for (line_number, line_result) in reader.lines().enumerate() {
+        let in_marker_range = line_number < MARKER_SCAN_LINES;
+        if !in_leading_block && !in_marker_range {
+            break;
+        }
+
+        let line = line_result?;
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() || trimmed.starts_with("#!") {
+            continue;
+        }
+
+        match comment_text(trimmed, prefixes) {
+            Some((prefix, text)) => {
+                if in_leading_block {
+                    header.leading.push(Comment {
+                        line_number,
+                        prefix,
+                        text: text.to_string(),
+                    });
+                }
+                if in_marker_range {
+                    header.early.push(text.to_string());
+                }
+            }
+            None => in_leading_block = false,
+        }
+    }
+
+    Ok(header)
+}
+
+/// Runs `extract_metadata` over `paths` on a new thread, sending each
+/// result as it is found. The thread stops early once the receiver is
+/// dropped.
+pub fn load_metadata_in_background(paths: Vec<String>) -> Receiver<(String, ScriptMetadata)> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for path in paths {
+            let metadata = extract_metadata(&path).unwrap_or_default();
+            if sender.send((path, metadata)).is_err() {
+                return;
+            }
+        }
+    });
+    receiver
+}
+
+pub fn extract_metadata(path: &str) -> Result<ScriptMetadata, io::Error> {
+    let header = read_header(path)?;
+    let mut metadata = ScriptMetadata::default();
+
+    for (i, comment) in header.leading.iter().enumerate() {
+        if let Some(directive) = comment.text.strip_prefix('@') {
+            if directive.trim() == "pin" {
+                metadata.pinned = true;
+            }
+            continue;
+        }
+
+        if metadata.description.is_none()
+            && !comment.text.is_empty()
+            && !comment.text.starts_with(MARKER_PREFIX)
+        {
+            metadata.description = Some(comment.text.clone());
+            metadata.description_full = comment_block(&header.leading[i..]);
+        }
+    }
+
+    // An explicit marker beats the first-comment guess
+    let marker = marker_value(&header.early, DESCRIPTION_MARKER);
+    if marker.is_some() {
+        metadata.description = marker;
+        metadata.description_full = None;
+    }
+
+    metadata.interactive = header
+        .early
+        .iter()
+        .any(|comment| comment == INTERACTIVE_MARKER);
+
+    if let Some(tags) = header
+        .early
+        .iter()
+        .find_map(|comment| comment.strip_prefix(TAGS_MARKER))
+    {
+        metadata.tags = parse_tags(tags);
+    }
+
+    metadata.run_command = marker_value(&header.early, RUN_MARKER);
+    metadata.confirm_message = marker_value(&header.early, CONFIRM_MARKER);
+
+    Ok(metadata)
+}
+
+/// The text after the first `marker` in `comments`, unless it's blank.
+fn marker_value(comments: &[String], marker: &str) -> Option<String> {
+    comments.iter().find_map(|comment| {
+        comment
+            .strip_prefix(marker)
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+    })
+}
+
+/// The text of `comments[0]` and the lines directly under it with the
+/// same prefix, stopping at a blank line, directive or marker. `None`
+/// if the block is a single line.
+fn comment_block(comments: &[Comment]) -> Option<String> {
+    let first = comments.first()?;
+    let mut lines = vec![first.text.as_str()];
+
+    for pair in comments.windows(2) {
+        let (previous, comment) = (&pair[0], &pair[1]);
+        if comment.line_number != previous.line_number + 1
+            || comment.prefix != first.prefix
+            || comment.text.starts_with('@')
+            || comment.text.starts_with(MARKER_PREFIX)
+        {
+            break;
+        }
+        lines.push(&comment.text);
+    }
+
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    (lines.len() > 1).then(|| lines.join("\n"))
+}
+
+/// Splits a tag list on commas and whitespace.
+pub fn parse_tags(list: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in list.split(|c: char| c == ',' || c.is_whitespace()) {
+        if !tag.is_empty() && !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+pub fn extract_description(path: &str) -> Result<Option<String>, io::Error> {
+    Ok(extract_metadata(path)?.description)
+}
+
+/// Marks scripts whose name or relative path appears in `pins`.
+pub fn pin_scripts(scripts: &mut [Script], pins: &[String]) {
+    for script in scripts {
+        if pins.iter().any(|p| *p == script.name || *p == script.relative_path) {
+            script.pinned = true;
+        }
+    }
+}
+
+/// Scripts whose name or relative path is `name`. A relative path
+/// picks one of several scripts sharing a name.
+pub fn find_scripts<'a>(scripts: &'a [Script], name: &str) -> Vec<&'a Script> {
+    let by_path: Vec<&Script> = scripts.iter().filter(|s| s.relative_path == name).collect();
+    if !by_path.is_empty() {
+        return by_path;
+    }
+    scripts.iter().filter(|s| s.name == name).collect()
+}
+
+/// Favorites and run counts are keyed by absolute path so they
+/// survive scanning the same directory through a different relative
+/// path.
+fn favorite_key(path: &str) -> String {
+    fs::canonicalize(path)
+        .ok()
+        .and_then(|p| p.to_str().map(String::from))
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// Marks scripts whose absolute path is in `favorites`.
+pub fn mark_favorites(scripts: &mut [Script], favorites: &Favorites) {
+    if favorites.paths.is_empty() {
+        return;
+    }
+    for script in scripts {
+        script.favorite = favorites.contains(&favorite_key(&script.path));
+    }
+}
+
+/// Gives scripts their run count from `run_counts`, by absolute path.
+pub fn mark_run_counts(scripts: &mut [Script], run_counts: &RunCounts) {
+    if run_counts.counts.is_empty() {
+        return;
+    }
+    for script in scripts {
+        script.run_count = run_counts.get(&favorite_key(&script.path));
+    }
+}
+
+/// Sorts scripts into their categories: uncategorized first, then
+/// categories alphabetically. Order within a category is kept.
+pub fn group_by_category(scripts: &mut [Script]) {
+    scripts.sort_by(|a, b| a.category.cmp(&b.category));
+}
+
+/// Order of the script list, cycled with `s`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortMode {
+    /// Grouped under category headers, by name within each.
+    #[default]
+    Category,
+    /// One flat list, alphabetical.
+    Name,
+    /// One flat list, most recently modified first.
+    Modified,
+    /// One flat list, most often run first.
+    MostUsed,
+}
+
+impl SortMode {
+    pub fn next(self) -> SortMode {
+        match self {
+            SortMode::Category => SortMode::Name,
+            SortMode::Name => SortMode::Modified,
+            SortMode::Modified => SortMode::MostUsed,
+            SortMode::MostUsed => SortMode::Category,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortMode::Category => "category",
+            SortMode::Name => "name",
+            SortMode::Modified => "modified",
+            SortMode::MostUsed => "most used",
+        }
+    }
+}
+
+/// Sorts `scripts` for `mode`, keeping pinned scripts first and
+/// favorites first within each category (or the whole flat list).
+pub fn sort_scripts(scripts: &mut [Script], mode: SortMode) {
+    match mode {
+        SortMode::Category => {
+            scripts.sort_by(|a, b| b.favorite.cmp(&a.favorite).then_with(|| a.name.cmp(&b.name)));
+            group_by_category(scripts);
+        }
+        SortMode::Name => scripts.sort_by(|a, b| {
+            b.favorite
+                .cmp(&a.favorite)
+                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+                .then_with(|| a.relative_path.cmp(&b.relative_path))
+        }),
+        // Newest first; scripts without a time go last
+        SortMode::Modified => scripts.sort_by_key(|s| (!s.favorite, Reverse(s.modified))),
+        SortMode::MostUsed => scripts.sort_by(|a, b| {
+            b.favorite
+                .cmp(&a.favorite)
+                .then_with(|| b.run_count.cmp(&a.run_count))
+                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+        }),
+    }
+    order_pinned_first(scripts);
+}
+
+/// Moves pinned scripts to the front, sorted by name among themselves.
+/// Unpinned scripts keep their relative order.
+pub fn order_pinned_first(scripts: &mut [Script]) {
+    scripts.sort_by(|a, b| match (a.pinned, b.pinned) {
+        (true, true) => a.name.cmp(&b.name),
+        (true, false) => std::cmp::Ordering::Less,
+        (false, true) => std::cmp::Ordering::Greater,
+        (false, false) => std::cmp::Ordering::Equal,
+    });
+}
+
+/// Options controlling which files `scan_directory_with` picks up.
+#[derive(Debug, Default, Clone)]
+pub struct ScanOptions {
+    /// Only include scripts modified within this long ago.
+    pub modified_within: Option<Duration>,
+    /// Only include scripts last modified longer ago than this.
+    pub older_than: Option<Duration>,
+    /// Files and directories to skip, in addition to any patterns in
+    /// the root's `.herringignore`.
+    pub ignore: IgnorePatterns,
+    /// Descend into symlinked directories. Symlinked files are always
+    /// listed.
+    pub follow_symlinks: bool,
+    /// Also list files without execute permission that start with a
+    /// `#!` line; they're run through that interpreter.
+    pub include_shebang: bool,
+    /// Descend at most this many directories below the root; `Some(0)`
+    /// scans only the root itself. `None` means no limit.
+    pub max_depth: Option<usize>,
+    /// Only list files with one of these extensions, given without the
+    /// dot. Empty means any extension.
+    pub extensions: Vec<String>,
+    /// Never list files with these extensions.
+    pub excluded_extensions: Vec<String>,
+    /// Whether files without an extension are listed. `None` lists them
+    /// unless `extensions` is set.
+    pub extensionless: Option<bool>,
+    /// Leave descriptions and markers out of the scan, for
+    /// `App::start_metadata_loader` to fill in afterwards.
+    pub lazy_metadata: bool,
+    /// List a script reachable by several paths, say through a
+    /// symlink, only once, under the shortest of them.
+    pub dedupe: bool,
+}
+
+impl ScanOptions {
+    fn matches_extension(&self, path: &Path) -> bool {
+        let Some(extension) = path.extension().and_then(|e| e.to_str()) else {
+            return self.extensionless.unwrap_or(self.extensions.is_empty());
+        };
+        let listed = |list: &[String]| list.iter().any(|e| e.eq_ignore_ascii_case(extension));
+
+        (self.extensions.is_empty() || listed(&self.extensions))
+            && !listed(&self.excluded_extensions)
+    }
+
+    fn matches_age(&self, metadata: &fs::Metadata) -> bool {
+        if self.modified_within.is_none() && self.older_than.is_none() {
+            return true;
+        }
+
+        let age = match metadata.modified() {
+            Ok(modified) => SystemTime::now()
+                .duration_since(modified)
+                .unwrap_or(Duration::ZERO),
+            Err(_) => return false,
+        };
+
+        let too_old = self.modified_within.is_some_and(|within| age > within);
+        let too_new = self.older_than.is_some_and(|older| age <= older);
+
+        !too_old && !too_new
+    }
+}
+
+/// Splits a comma-separated list like `sh,.py, rb` into extensions
+/// without their dots.
+pub fn parse_extensions(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(|e| e.trim().trim_start_matches('.'))
+        .filter(|e| !e.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Parses a duration like `30s`, `15m`, `12h`, `7d` or `2w`.
+/// A bare number is taken as seconds.
+pub fn parse_duration(input: &str) -> Option<Duration> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(split);
+
+    let value: u64 = number.parse().ok()?;
+    let multiplier = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 60 * 60 * 24,
+        "w" => 60 * 60 * 24 * 7,
+        _ => return None,
+    };
+
+    value.checked_mul(multiplier).map(Duration::from_secs)
+}
+
+pub fn scan_directory(directory: &str) -> Result<Vec<Script>, ScanError> {
+    scan_directory_with(directory, &ScanOptions::default())
+}
+
+pub fn scan_directory_with(
+    directory: &str,
+    options: &ScanOptions,
+) -> Result<Vec<Script>, ScanError> {
+    Ok(scan_directory_report(directory, options)?.scripts)
+}
+
+/// Why a directory or file couldn't be scanned.
+#[derive(Debug)]
+pub enum ScanError {
+    /// Reading `path` was refused.
+    PermissionDenied { path: PathBuf },
+    /// `path` can't be listed or run by name since it isn't valid UTF-8.
+    InvalidUtf8Path { path: PathBuf },
+    Io { path: PathBuf, source: io::Error },
+}
+
+impl ScanError {
+    fn io(path: &Path, source: io::Error) -> ScanError {
+        let path = path.to_path_buf();
+        if source.kind() == io::ErrorKind::PermissionDenied {
+            ScanError::PermissionDenied { path }
+        } else {
+            ScanError::Io { path, source }
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        match self {
+            ScanError::PermissionDenied { path }
+            | ScanError::InvalidUtf8Path { path }
+            | ScanError::Io { path, .. } => path,
+        }
+    }
+
+    /// What went wrong, without the path.
+    fn reason(&self) -> String {
+        match self {
+            ScanError::PermissionDenied { .. } => "Permission denied".to_string(),
+            ScanError::InvalidUtf8Path { .. } => "Name is not valid UTF-8".to_string(),
+            ScanError::Io { source, .. } => source.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.path().display(), self.reason())
+    }
+}
+
+impl std::error::Error for ScanError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ScanError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// A file or directory that had to be skipped during a scan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanWarning {
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ScanWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Scripts found by a scan, plus whatever couldn't be read along the
+/// way.
+#[derive(Debug, Default)]
+pub struct ScanReport {
+    pub scripts: Vec<Script>,
+    pub warnings: Vec<ScanWarning>,
+}
+
+impl ScanReport {
+    fn warn(&mut self, error: ScanError) {
+        self.warnings.push(ScanWarning {
+            path: error.path().display().to_string(),
+            message: error.reason(),
+        });
+    }
+}
+
+/// Like `scan_directory_with`, but unreadable entries below the root
+/// are reported as warnings instead of failing the whole scan. Only an
+/// unreadable root is an error.
+pub fn scan_directory_report(
+    directory: &str,
+    options: &ScanOptions,
+) -> Result<ScanReport, ScanError> {
+    let mut report = ScanReport::default();
+    let root = Path::new(directory);
+
+    let mut options = options.clone();
+    options.ignore.extend(&IgnorePatterns::load(root));
+
+    let mut visited = HashSet::new();
+    visited.insert(fs::canonicalize(root).map_err(|e| ScanError::io(root, e))?);
+
+    scan_directory_recursive(root, directory, None, 0, &options, &mut visited, &mut report)?;
+    // read_dir order depends on the filesystem
+    report.scripts.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+    if options.dedupe {
+        dedupe_scripts(&mut report.scripts);
+    }
+
+    let manifest_path = root.join(MANIFEST_FILE).display().to_string();
+    match Manifest::load(root) {
+        Ok(manifest) => {
+            for key in manifest.apply(&mut report.scripts) {
+                report.warnings.push(ScanWarning {
+                    path: manifest_path.clone(),
+                    message: format!("No script named {:?}", key),
+                });
+            }
+        }
+        Err(message) => report.warnings.push(ScanWarning {
+            path: manifest_path,
+            message,
+        }),
+    }
+    Ok(report)
+}
+
+/// Drops scripts whose file is also listed under a shorter path,
+/// keeping the list's order otherwise.
+fn dedupe_scripts(scripts: &mut Vec<Script>) {
+    let canonical: Vec<PathBuf> = scripts
+        .iter()
+        .map(|s| fs::canonicalize(&s.path).unwrap_or_else(|_| PathBuf::from(&s.path)))
+        .collect();
+
+    let mut keep: HashMap<&PathBuf, usize> = HashMap::new();
+    for (index, target) in canonical.iter().enumerate() {
+        let shortest = keep.entry(target).or_insert(index);
+        if scripts[index].relative_path.len() < scripts[*shortest].relative_path.len() {
+            *shortest = index;
+        }
+    }
+
+    let kept: HashSet<usize> = keep.into_values().collect();
+    let mut index = 0;
+    scripts.retain(|_| {
+        index += 1;
+        kept.contains(&(index - 1))
+    });
+}
+
+pub fn scan_directories(directories: &[String]) -> Result<Vec<Script>, ScanError> {
+    scan_directories_with(directories, &ScanOptions::default())
+}
+
+/// Scans several directories into one list. With more than one
+/// directory, each script's category is prefixed by the name of the
+/// directory it came from, and a script reachable from two of them
+/// (say, through a symlinked directory) is only listed once.
+pub fn scan_directories_with(
+    directories: &[String],
+    options: &ScanOptions,
+) -> Result<Vec<Script>, ScanError> {
+    Ok(scan_directories_report(directories, options)?.scripts)
+}
+
+/// `scan_directories_with`, collecting warnings from every directory.
+pub fn scan_directories_report(
+    directories: &[String],
+    options: &ScanOptions,
+) -> Result<ScanReport, ScanError> {
+    if let [directory] = directories {
+        return scan_directory_report(directory, options);
+    }
+
+    let mut scripts = Vec::new();
+    let mut warnings = Vec::new();
+    let mut seen = HashSet::new();
+
+    for directory in directories {
+        let root = fs::canonicalize(directory)
+            .map_err(|e| ScanError::io(Path::new(directory), e))?;
+        let source = root
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+        let Some(root_str) = root.to_str().map(String::from) else {
+            return Err(ScanError::InvalidUtf8Path { path: root });
+        };
+
+        let report = scan_directory_report(&root_str, options)?;
+        warnings.extend(report.warnings);
+
+        for mut script in report.scripts {
+            let canonical = fs::canonicalize(&script.path)
+                .unwrap_or_else(|_| PathBuf::from(&script.path));
+            if !seen.insert(canonical) {
+                continue;
+            }
+
+            script.category = Some(match script.category {
+                Some(category) => format!("{}/{}", source, category),
+                None => source.clone(),
+            });
+            scripts.push(script);
+        }
+    }
+
+    Ok(ScanReport { scripts, warnings })
+}
+
+/// Whether a file can be run: any execute bit set.
+#[cfg(unix)]
+fn is_executable(_path: &Path, metadata: &fs::Metadata) -> bool {
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+/// Extensions treated as runnable where there are no execute bits.
+#[cfg(windows)]
+const EXECUTABLE_EXTENSIONS: &[&str] = &["exe", "bat", "cmd", "ps1"];
+
+/// Whether a file can be run, judged by its extension.
+#[cfg(windows)]
+fn is_executable(path: &Path, _metadata: &fs::Metadata) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            EXECUTABLE_EXTENSIONS
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known))
+        })
+}
+
+/// Longest shebang line looked at.
+const SHEBANG_LIMIT: u64 = 256;
+
+/// The command after a file's leading `#!`, if it has one.
+fn read_shebang(path: &Path) -> Option<String> {
+    let file = fs::File::open(path).ok()?;
+    let mut line = String::new();
+    io::BufReader::new(file.take(SHEBANG_LIMIT))
+        .read_line(&mut line)
+        .ok()?;
+
+    let command = line.strip_prefix("#!")?.trim();
+    (!command.is_empty()).then(|| command.to_string())
+}
+
+/// Files herring reads its own settings from, never listed as scripts
+/// even when executable.
+const HERRING_FILES: &[&str] = &[LOCAL_CONFIG_FILE, ENV_FILE, IGNORE_FILE, MANIFEST_FILE];
+
+fn scan_directory_recursive(
+    root: &Path,
+    directory: &str,
+    category: Option<String>,
+    depth: usize,
+    options: &ScanOptions,
+    visited: &mut HashSet<PathBuf>,
+    report: &mut ScanReport,
+) -> Result<(), ScanError> {
+    let entries = fs::read_dir(directory).map_err(|e| ScanError::io(Path::new(directory), e))?;
+
+    for entry_result in entries {
+        let entry = match entry_result {
+            Ok(entry) => entry,
+            Err(e) => {
+                report.warn(ScanError::io(Path::new(directory), e));
+                continue;
+            }
+        };
+        let path = entry.path();
+        let is_symlink = match entry.file_type() {
+            Ok(file_type) => file_type.is_symlink(),
+            Err(e) => {
+                report.warn(ScanError::io(&path, e));
+                continue;
+            }
+        };
+        let is_dir = path.is_dir();
+
+        if options.ignore.is_ignored(path.strip_prefix(root).unwrap_or(&path), is_dir) {
+            continue;
+        }
+        let Some(path_str) = path.to_str().map(String::from) else {
+            report.warn(ScanError::InvalidUtf8Path { path });
+            continue;
+        };
+
+        if is_dir {
+            if is_symlink && !options.follow_symlinks {
+                continue;
+            }
+            if options.max_depth.is_some_and(|max| depth >= max) {
+                continue;
+            }
+            // A directory reached twice, e.g. through a link back up
+            // the tree, is only scanned the first time
+            let Ok(canonical) = fs::canonicalize(&path) else {
+                continue;
+            };
+            if !visited.insert(canonical) {
+                continue;
+            }
+
+            let subdir_name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("unknown");
+            // The category is the whole path below the root, e.g. `db/migrations`
+            let subdir_category = match &category {
+                Some(parent) => format!("{}/{}", parent, subdir_name),
+                None => subdir_name.to_string(),
+            };
+
+            // A subdirectory we can't read is skipped, not fatal
+            if let Err(e) = scan_directory_recursive(
+                root,
+                &path_str,
+                Some(subdir_category),
+                depth + 1,
+                options,
+                visited,
+                report,
+            ) {
+                report.warn(e);
+            }
+            continue;
+        }
+
+        if path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| HERRING_FILES.contains(&n))
+        {
+            continue;
+        }
+
+        // Broken symlinks have no target to inspect, and pipes or
+        // devices can't be run
+        let Ok(metadata) = fs::metadata(&path) else {
+            continue;
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+
+        let interpreter = if is_executable(&path, &metadata) {
+            None
+        } else if options.include_shebang {
+            match read_shebang(&path) {
+                Some(shebang) => Some(shebang),
+                None => continue,
+            }
+        } else {
+            continue;
+        };
+
+        if options.matches_age(&metadata) && options.matches_extension(&path) {
+            let modified = metadata.modified().ok();
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("unknown")
+                .to_string();
+
+            let relative_path = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .to_str()
+                .unwrap_or("")
+                .to_string();
 
-            let description = extract_description(&path_str).unwrap_or(None);
+            let metadata = if options.lazy_metadata {
+                ScriptMetadata::default()
+            } else {
+                extract_metadata(&path_str).unwrap_or_default()
+            };
 
-            scripts.push(Script {
+            report.scripts.push(Script {
                 path: path_str,
+                relative_path,
                 name,
-                description,
+                description: metadata.description,
+                description_full: metadata.description_full,
                 category: category.clone(),
+                pinned: metadata.pinned,
+                favorite: false,
+                run_count: 0,
+                tags: metadata.tags,
+                interactive: metadata.interactive,
+                interpreter,
+                run_command: metadata.run_command,
+                confirm_message: metadata.confirm_message,
+                modified,
             });
         }
     }
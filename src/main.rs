@@ -1,10 +1,14 @@
 use std::env;
+use std::fs;
 use std::io;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{Duration, Instant};
 
-use ratatui::{backend::CrosstermBackend, Terminal};
+use ratatui::{backend::CrosstermBackend, layout::Rect, Terminal};
 use crossterm::{
-    event::{self, Event, KeyCode},
+    cursor,
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event},
     terminal::{
         disable_raw_mode,
         enable_raw_mode,
@@ -14,167 +18,563 @@ use crossterm::{
     execute,
 };
 
-use rusty_herring::{App, scan_directory, ui};
+use rusty_herring::config::Config;
+use rusty_herring::favorites::Favorites;
+use rusty_herring::history::History;
+use rusty_herring::input::{handle_key, handle_mouse};
+use rusty_herring::keymap::KeyMap;
+use rusty_herring::run::{self, Capture, ProcessRunner};
+use rusty_herring::run_counts::RunCounts;
+use rusty_herring::selection::Selections;
+use rusty_herring::theme::Theme;
+use rusty_herring::{
+    App, AppMode, InteractiveRun, Overlay, ScanOptions, SortMode, find_scripts, mark_favorites,
+    mark_run_counts, parse_duration, parse_extensions, pin_scripts, scan_directories_report,
+    sort_scripts, ui, working_dir,
+};
 
 struct TerminalGuard;
 
 impl Drop for TerminalGuard {
     fn drop(&mut self) {
         let _ = disable_raw_mode();
-        let _ = execute!(io::stdout(), LeaveAlternateScreen);
+        let _ = execute!(io::stdout(), DisableMouseCapture, LeaveAlternateScreen);
     }
 }
 
-fn run_selected_script(
-    app: &mut App,
+/// Runs the TUI until the user quits, returning the exit code of the
+/// last script run, or 0 if none was.
+fn run_app(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
-) -> Result<(), io::Error> {
-    let script = &app.scripts[app.selected_index];
+    app: &mut App,
+) -> Result<i32, io::Error> {
+    app.resize(terminal.size()?);
+    loop {
+        if app.dirty {
+            app.dirty = false;
+            draw(terminal, app)?;
+        }
+        
+        if event::poll(app.poll_interval())? {
+            match event::read()? {
+                Event::Key(key) => handle_key(app, key, &mut ProcessRunner),
+                Event::Mouse(mouse) => handle_mouse(app, mouse, &mut ProcessRunner),
+                Event::Resize(width, height) => app.resize(Rect::new(0, 0, width, height)),
+                _ => {}
+            }
+        }
 
-    app.output_text = "Running script...\n\nPlease wait...".to_string();
-    app.viewing_output = true;
+        app.poll_run();
+        app.poll_metadata();
+        app.expire_number_jump(Instant::now());
+
+        // The terminal may have been resized while the child had it
+        if let Some(request) = app.interactive_request.take() {
+            run_interactive(terminal, app, &request)?;
+            app.resize(terminal.size()?);
+        }
 
+        if let Some(path) = app.edit_request.take() {
+            edit_script(terminal, app, &path)?;
+            app.resize(terminal.size()?);
+        }
+        
+        if app.should_quit {
+            break;
+        }
+    }
+    
+    Ok(app.last_exit_code.unwrap_or(0))
+}
+
+fn draw(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &App,
+) -> Result<(), io::Error> {
     terminal.draw(|f| {
-        ui::render_output_view(f, app);
+        match app.mode {
+            AppMode::Help => ui::render_help_view(f, app),
+            AppMode::Running | AppMode::Output => {
+                ui::render_output_view(f, app);
+                if app.batch_awaiting_decision() {
+                    ui::render_batch_prompt(f, app);
+                } else if app.batch_awaiting_confirm() {
+                    ui::render_batch_confirm(f, app);
+                }
+            }
+            AppMode::List => match app.overlay {
+                Some(Overlay::Source) => ui::render_source_view(f, app),
+                Some(Overlay::History) => ui::render_history_view(f, app),
+                Some(Overlay::Warnings) => ui::render_warnings_view(f, app),
+                Some(Overlay::Palette) => {
+                    ui::render_list_view(f, app);
+                    ui::render_palette(f, app);
+                }
+                Some(Overlay::Info) => {
+                    ui::render_list_view(f, app);
+                    ui::render_info_popup(f, app);
+                }
+                Some(Overlay::Confirm) => {
+                    ui::render_list_view(f, app);
+                    ui::render_confirm_view(f, app);
+                }
+                _ => ui::render_list_view(f, app),
+            },
+        }
+        if app.overlay == Some(Overlay::QuitPrompt) {
+            ui::render_quit_prompt(f, app);
+        }
     })?;
+    Ok(())
+}
 
-    let output = Command::new(&script.path).output()?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    let code = output.status.code().unwrap_or(-1);
-
-    app.output_text = if code == 0 {
-        format!(
-            "✓ Script completed successfully\n\
-             Exit code: 0\n\n\
-             === OUTPUT ===\n{}\n\n\
-             === ERRORS ===\n{}",
-            if stdout.is_empty() { "(no output)" } else { stdout.as_ref() },
-            if stderr.is_empty() { "(none)" } else { stderr.as_ref() }
-        )
-    } else {
-        format!(
-            "✗ Script failed\n\
-             Exit code: {}\n\n\
-             === OUTPUT ===\n{}\n\n\
-             === ERRORS ===\n{}",
-            code,
-            if stdout.is_empty() { "(no output)" } else { stdout.as_ref() },
-            if stderr.is_empty() { "(none)" } else { stderr.as_ref() }
-        )
-    };
+/// Leaves the TUI so a child process can use the terminal.
+/// `TerminalGuard` still restores the terminal if the caller bails out.
+fn suspend() -> Result<(), io::Error> {
+    disable_raw_mode()?;
+    execute!(io::stdout(), DisableMouseCapture, LeaveAlternateScreen, cursor::Show)
+}
+
+/// Takes the terminal back after `suspend`.
+fn resume(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<(), io::Error> {
+    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+    enable_raw_mode()?;
+    terminal.clear()
+}
 
+/// Hands the terminal to an interactive script, then takes it back.
+fn run_interactive(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    request: &InteractiveRun,
+) -> Result<(), io::Error> {
+    suspend()?;
+
+    let started = Instant::now();
+    let result = run::run_interactive(
+        &request.path,
+        request.interpreter.as_deref(),
+        request.run_command.as_deref(),
+        &request.args,
+        &app.env,
+        request.dir.as_deref(),
+    );
+    let duration = started.elapsed();
+
+    println!("\nPress Enter to return to the script list");
+    let mut line = String::new();
+    io::stdin().read_line(&mut line)?;
+
+    resume(terminal)?;
+    app.finish_interactive(request, result, duration);
     Ok(())
 }
-fn run_app(
+
+/// Opens a script in `$EDITOR`, then takes the terminal back.
+fn edit_script(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
-    mut app: App,
+    app: &mut App,
+    path: &str,
 ) -> Result<(), io::Error> {
-    loop {
-        terminal.draw(|f| {
-            if app.showing_help {
-                ui::render_help_view(f);
-            } else if app.viewing_output {
-                ui::render_output_view(f, &app);
-            } else {
-                ui::render_list_view(f, &app);
+    let editor = run::editor();
+    suspend()?;
+    let result = run::open_in_editor(&editor, path);
+    resume(terminal)?;
+    app.finish_edit(&editor, result);
+    Ok(())
+}
+
+/// Runs the script matching `name` with the terminal's stdio, for
+/// `--run`. Returns the exit code to leave with.
+fn run_headless(
+    scripts: &[rusty_herring::Script],
+    name: &str,
+    config: &Config,
+    directory: &str,
+    directories: &[String],
+) -> i32 {
+    let script = match find_scripts(scripts, name).as_slice() {
+        [script] => *script,
+        [] => {
+            eprintln!("No script named {} in {}", name, directories.join(", "));
+            return 1;
+        }
+        matches => {
+            eprintln!("{} is ambiguous; it matches:", name);
+            for script in matches {
+                eprintln!("  {}", script.relative_path);
             }
-        })?;
-        
-        if event::poll(
-            std::time::Duration::from_millis(100)
-        )? {
-            if let Event::Key(key) = event::read()? {
-                if app.showing_help {
-                    app.hide_help();
-                } else if app.viewing_output {
-                    let lines: Vec<&str> = app.output_text
-                        .lines()
-                        .collect();
-                    let total = lines.len();
-                    let visible = 20;
-                    let max = total.saturating_sub(visible);
-                    
-                    match key.code {
-                        KeyCode::Up | KeyCode::Char('k') => {
-                            app.scroll_output_up();
-                        }
-                        KeyCode::Down | KeyCode::Char('j') => {
-                            app.scroll_output_down(max);
-                        }
-                        _ => {
-                            app.back_to_list();
-                        }
-                    }
+            return 1;
+        }
+    };
+
+    let env = config.run_env(Path::new(directory));
+    let dir = working_dir(script, config.working_dir.unwrap_or_default());
+    match run::run_interactive(
+        &script.path,
+        script.interpreter.as_deref(),
+        script.run_command.as_deref(),
+        &[],
+        &env,
+        dir.as_deref(),
+    ) {
+        Ok(status) => run::shell_exit_code(status),
+        Err(e) => {
+            eprintln!("Cannot run {}: {}", script.path, e);
+            1
+        }
+    }
+}
+
+struct Cli {
+    directories: Vec<String>,
+    options: ScanOptions,
+    timeout: Option<Duration>,
+    /// Show diagnostics under the output of failed runs.
+    verbose: bool,
+    /// Print the scripts as JSON instead of starting the TUI.
+    json: bool,
+    /// Print how many scripts were found instead of starting the TUI.
+    count: bool,
+    /// Run the script with this name or relative path, without the TUI.
+    run: Option<String>,
+    /// Exit with the last run's exit code when the TUI quits.
+    propagate_exit: bool,
+    /// No colors, and plain text in place of symbols.
+    plain: bool,
+    /// Forget every script's run count before starting.
+    reset_run_counts: bool,
+    /// Run scripts in a pseudo-terminal.
+    pty: bool,
+    help: bool,
+    version: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<Cli, String> {
+    let mut cli = Cli {
+        directories: Vec::new(),
+        options: ScanOptions::default(),
+        timeout: None,
+        verbose: false,
+        json: false,
+        count: false,
+        run: None,
+        propagate_exit: false,
+        plain: false,
+        reset_run_counts: false,
+        pty: false,
+        help: false,
+        version: false,
+    };
+    let mut iter = args.iter().skip(1);
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--modified-within" | "--older-than" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| format!("{} requires a duration", arg))?;
+                let duration = parse_duration(value)
+                    .ok_or_else(|| format!("Invalid duration: {}", value))?;
+                if arg == "--modified-within" {
+                    cli.options.modified_within = Some(duration);
                 } else {
-                    match key.code {
-                        KeyCode::Char('?') => {
-                            app.show_help();
-                        }
-                        KeyCode::Char('q') | KeyCode::Esc => {
-                            app.quit();
-                        }
-                        KeyCode::Down | KeyCode::Char('j') => {
-                            app.next();
-                        }
-                        KeyCode::Up | KeyCode::Char('k') => {
-                            app.previous();
-                        }
-                        KeyCode::Enter => {
-                            if let Err(e) = run_selected_script(&mut app, terminal) {
-                                app.output_text = format!(
-                                    "✗ Error running script:\n{}",
-                                    e
-                                );
-                                app.viewing_output = true;
-                            }
-                        }
-                        _ => {}
-                    }
+                    cli.options.older_than = Some(duration);
                 }
             }
-        }
-        
-        if app.should_quit {
-            break;
+            "--follow-symlinks" => cli.options.follow_symlinks = true,
+            "--dedupe" => cli.options.dedupe = true,
+            "--shebang" => cli.options.include_shebang = true,
+            "--max-depth" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| format!("{} requires a number", arg))?;
+                let depth = value
+                    .parse()
+                    .map_err(|_| format!("Invalid depth: {}", value))?;
+                cli.options.max_depth = Some(depth);
+            }
+            "--ext" | "--exclude-ext" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| format!("{} requires a list of extensions", arg))?;
+                if arg == "--ext" {
+                    cli.options.extensions = parse_extensions(value);
+                } else {
+                    cli.options.excluded_extensions = parse_extensions(value);
+                }
+            }
+            "--extensionless" => cli.options.extensionless = Some(true),
+            "--no-extensionless" => cli.options.extensionless = Some(false),
+            "--verbose" | "-v" => cli.verbose = true,
+            "--propagate-exit" => cli.propagate_exit = true,
+            "--no-color" | "--ascii" => cli.plain = true,
+            "--reset-run-counts" => cli.reset_run_counts = true,
+            "--pty" => cli.pty = true,
+            "--json" => cli.json = true,
+            "--count" => cli.count = true,
+            "--run" => {
+                let name = iter
+                    .next()
+                    .ok_or_else(|| format!("{} requires a script name", arg))?;
+                cli.run = Some(name.clone());
+            }
+            "--help" | "-h" => cli.help = true,
+            "--version" | "-V" => cli.version = true,
+            "--timeout" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| format!("{} requires a duration", arg))?;
+                let duration = parse_duration(value)
+                    .ok_or_else(|| format!("Invalid duration: {}", value))?;
+                cli.timeout = Some(duration);
+            }
+            _ if arg.starts_with('-') && arg != "-" => {
+                return Err(format!("Unknown option: {}", arg));
+            }
+            _ => cli.directories.push(arg.clone()),
         }
     }
-    
-    Ok(())
-}
 
+    Ok(cli)
+}
 
 fn main() -> Result<(), io::Error> {
     let args: Vec<String> = env::args().collect();
-    
-    if args.len() < 2 {
-        println!("Usage: {} <directory>", args[0]);
+    let usage = format!(
+        "Usage: {} [options] [directory]...\n\
+         \n\
+         Without a directory, `scan_dir` from the config file is scanned.\n\
+         \n\
+         Options:\n\
+         \x20 --modified-within <dur>  Only scripts modified within <dur>\n\
+         \x20 --older-than <dur>       Only scripts last modified before <dur> ago\n\
+         \x20 --timeout <dur>          Kill runs that take longer than <dur>\n\
+         \x20 -v, --verbose            Show the executable, directory and environment of failed runs\n\
+         \x20 --follow-symlinks        Descend into symlinked directories\n\
+         \x20 --dedupe                 List a script reached through several paths once\n\
+         \x20 --shebang                Include non-executable files with a #! line\n\
+         \x20 --max-depth <n>          Descend at most <n> directories below each root\n\
+         \x20 --ext <list>             Only files with these extensions, e.g. sh,py\n\
+         \x20 --exclude-ext <list>     Skip files with these extensions\n\
+         \x20 --[no-]extensionless     List files without an extension (default: unless --ext)\n\
+         \x20 --json                   Print the scripts as JSON and exit\n\
+         \x20 --count                  Print the number of scripts found and exit\n\
+         \x20 --no-color, --ascii      No colors; [OK]/[FAIL] and other text in place of symbols\n\
+         \x20                          (also when NO_COLOR is set)\n\
+         \x20 --propagate-exit         Exit with the exit code of the last script run in the TUI\n\
+         \x20 --reset-run-counts       Forget how many times each script has been run\n\
+         \x20 --pty                    Run scripts in a pseudo-terminal so they see a TTY; errors\n\
+         \x20                          are mixed into the output (needs the pty feature)\n\
+         \x20 --run <name>             Run the script with this name or relative path and exit\n\
+         \x20                          with its exit code, without the TUI\n\
+         \x20 -h, --help               Print this help and exit\n\
+         \x20 -V, --version            Print the version and exit",
+        args[0]
+    );
+
+    let mut cli = match parse_args(&args) {
+        Ok(cli) => cli,
+        Err(e) => {
+            // Exit code 2 for usage errors, like most command-line tools
+            eprintln!("{}\n\n{}", e, usage);
+            process::exit(2);
+        }
+    };
+    if cli.help {
+        println!("{}", usage);
         return Ok(());
     }
-    
-    let directory = &args[1];
-    let scripts = scan_directory(directory)?;
+    if cli.version {
+        println!("rusty-herring {}", env!("CARGO_PKG_VERSION"));
+        return Ok(());
+    }
+
+    let global = Config::load();
+    if cli.directories.is_empty()
+        && let Some(directory) = global.scan_dir()
+    {
+        cli.directories.push(directory);
+    }
+    let Some(directory) = cli.directories.first().cloned() else {
+        eprintln!("No directory given and no scan_dir in the config\n\n{}", usage);
+        process::exit(2);
+    };
+    let config = global.merge(Config::load_local(Path::new(&directory)));
+    // Flags given on the command line win over the config
+    if cli.options.max_depth.is_none() {
+        cli.options.max_depth = config.max_depth;
+    }
+    cli.options.follow_symlinks |= config.follow_symlinks.unwrap_or(false);
+    cli.options.dedupe |= config.dedupe.unwrap_or(false);
+    if cli.options.extensions.is_empty() {
+        cli.options.extensions = config.extensions.clone().unwrap_or_default();
+    }
+    if cli.options.excluded_extensions.is_empty() {
+        cli.options.excluded_extensions = config.exclude_extensions.clone().unwrap_or_default();
+    }
+    if cli.options.extensionless.is_none() {
+        cli.options.extensionless = config.extensionless;
+    }
+    // Only the TUI can show the list before every file has been read
+    cli.options.lazy_metadata = !(cli.json || cli.count || cli.run.is_some());
+    let report = match scan_directories_report(&cli.directories, &cli.options) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("Cannot scan {}", e);
+            process::exit(1);
+        }
+    };
+    let mut scripts = report.scripts;
+    pin_scripts(&mut scripts, &config.pinned);
+    let favorites_path = Favorites::default_path();
+    let favorites = match &favorites_path {
+        Some(path) => Favorites::load_from(path).unwrap_or_else(|e| {
+            eprintln!("Warning: ignoring favorites {}: {}", path.display(), e);
+            Favorites::default()
+        }),
+        None => Favorites::default(),
+    };
+    mark_favorites(&mut scripts, &favorites);
+    let run_counts_path = RunCounts::default_path();
+    let run_counts = match &run_counts_path {
+        Some(path) if cli.reset_run_counts => {
+            if let Err(e) = RunCounts::default().save_to(path) {
+                eprintln!("Warning: could not reset run counts {}: {}", path.display(), e);
+            }
+            RunCounts::default()
+        }
+        Some(path) => RunCounts::load_from(path).unwrap_or_else(|e| {
+            eprintln!("Warning: ignoring run counts {}: {}", path.display(), e);
+            RunCounts::default()
+        }),
+        None => RunCounts::default(),
+    };
+    mark_run_counts(&mut scripts, &run_counts);
+    sort_scripts(&mut scripts, SortMode::default());
+
+    if cli.json || cli.count || cli.run.is_some() || scripts.is_empty() {
+        for warning in &report.warnings {
+            eprintln!("Warning: skipped {}", warning);
+        }
+    }
+
+    if cli.json {
+        let json = serde_json::to_string_pretty(&scripts).map_err(io::Error::other)?;
+        println!("{}", json);
+        return Ok(());
+    }
+    if cli.count {
+        println!("{}", scripts.len());
+        return Ok(());
+    }
+    if let Some(name) = &cli.run {
+        let code = run_headless(&scripts, name, &config, &directory, &cli.directories);
+        process::exit(code);
+    }
     
     if scripts.is_empty() {
         println!(
             "No executable scripts in {}",
-            directory
+            cli.directories.join(", ")
         );
         return Ok(());
     }
     
+    let mut app = App::new(scripts);
+    app.env = config.run_env(Path::new(&directory));
+    app.scan_dirs = cli.directories.clone();
+    app.scan_options = cli.options;
+    app.start_metadata_loader();
+    app.pins = config.pinned;
+    app.scan_warnings = report.warnings;
+    app.favorites = favorites;
+    app.favorites_path = favorites_path;
+    app.run_counts = run_counts;
+    app.run_counts_path = run_counts_path;
+    if cli.directories.len() == 1 {
+        app.root = fs::canonicalize(&directory)
+            .unwrap_or_else(|_| PathBuf::from(&directory));
+    }
+    app.title_template = config.title_template;
+    app.ascii = cli.plain || ui::plain_requested(std::env::var("NO_COLOR").ok().as_deref());
+    app.use_color = !app.ascii && ui::detect_color_support();
+    let theme = Theme::load();
+    app.theme = theme.with(&config.theme).unwrap_or_else(|e| {
+        eprintln!("Warning: ignoring [theme] in the config: {}", e);
+        theme
+    });
+    app.default_action = config.default_action.unwrap_or_default();
+    app.collapsed.extend(config.collapsed_categories);
+    app.require_confirmation = config.require_confirmation.unwrap_or(false);
+    app.batch_on_failure = config.batch_on_failure.unwrap_or_default();
+    app.invalid_utf8 = config.invalid_utf8.unwrap_or_default();
+    app.working_dir = config.working_dir.unwrap_or_default();
+    app.zebra_rows = config.zebra_rows.unwrap_or(true);
+    app.filter_smart_case = config.filter_smart_case.unwrap_or(false);
+    app.show_run_counts = config.show_run_counts.unwrap_or(false);
+    if cli.pty || config.pty.unwrap_or(false) {
+        app.capture = Capture::Pty;
+    }
+    let (keymap, warnings) = KeyMap::from_config(&config.keybindings);
+    for warning in warnings {
+        eprintln!("Warning: ignoring keybinding: {}", warning);
+    }
+    app.keymap = keymap;
+    if let Some(limit) = config.max_output_lines {
+        app.max_output_lines = (limit > 0).then_some(limit);
+    }
+    app.history_path = History::default_path();
+    if let Some(path) = &app.history_path {
+        app.history = History::load_from(path).unwrap_or_else(|e| {
+            eprintln!("Warning: ignoring history {}: {}", path.display(), e);
+            History::default()
+        });
+    }
+    app.verbose_failures = cli.verbose;
+    app.timeout = cli.timeout.or_else(|| {
+        let value = config.timeout.as_deref()?;
+        let duration = parse_duration(value);
+        if duration.is_none() {
+            eprintln!("Warning: ignoring invalid timeout {:?}", value);
+        }
+        duration
+    }).filter(|d| !d.is_zero());
+
+    // Start on the script selected when this directory was last used
+    let selections_path = Selections::default_path();
+    let mut selections = match &selections_path {
+        Some(path) => Selections::load_from(path).unwrap_or_default(),
+        None => Selections::default(),
+    };
+    let selection_key = Selections::key(&cli.directories);
+    if let Some(path) = selections.get(&selection_key) {
+        app.restore_selection(path);
+    }
+
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     
-    let _guard = TerminalGuard;
+    let guard = TerminalGuard;
     
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
     
-    let app = App::new(scripts);
-    run_app(&mut terminal, app)?;
+    let exit_code = run_app(&mut terminal, &mut app)?;
+
+    // Losing the remembered selection isn't worth an error on exit
+    if let (Some(path), Some(script)) = (&selections_path, app.selected_path()) {
+        selections.set(selection_key, script);
+        let _ = selections.save_to(path);
+    }
+
+    // `process::exit` skips destructors, so restore the terminal first
+    if cli.propagate_exit && exit_code != 0 {
+        drop(terminal);
+        drop(guard);
+        process::exit(exit_code);
+    }
     
     Ok(())
 }
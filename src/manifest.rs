@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::config::read_if_exists;
+use crate::{Script, find_scripts};
+
+/// Metadata for scripts that can't carry their own, read from the scan
+/// root.
+pub const MANIFEST_FILE: &str = "herring.toml";
+
+/// What `herring.toml` says about one script. Everything is optional;
+/// whatever is given wins over the script's own comments, except tags,
+/// which are added to its own.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ManifestEntry {
+    pub description: Option<String>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    /// Like a `herring-confirm:` marker.
+    pub confirm: Option<String>,
+}
+
+/// `herring.toml`: entries keyed by a script's relative path or name,
+/// like `[scripts."db/reset.sh"]` or `[scripts.deploy]`.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Manifest {
+    pub scripts: BTreeMap<String, ManifestEntry>,
+}
+
+impl Manifest {
+    pub fn parse(text: &str) -> Result<Manifest, String> {
+        toml::from_str(text).map_err(|e| e.message().to_string())
+    }
+
+    /// Reads `herring.toml` from `root`. A missing file is an empty
+    /// manifest.
+    pub fn load(root: &Path) -> Result<Manifest, String> {
+        match read_if_exists(&root.join(MANIFEST_FILE)) {
+            Ok(Some(text)) => Manifest::parse(&text),
+            Ok(None) => Ok(Manifest::default()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    /// Applies each entry to the scripts it names, returning the keys
+    /// that matched none.
+    pub fn apply(&self, scripts: &mut [Script]) -> Vec<String> {
+        let mut unmatched = Vec::new();
+
+        for (key, entry) in &self.scripts {
+            let paths: Vec<String> = find_scripts(scripts, key)
+                .into_iter()
+                .map(|s| s.path.clone())
+                .collect();
+            if paths.is_empty() {
+                unmatched.push(key.clone());
+                continue;
+            }
+            for script in scripts.iter_mut().filter(|s| paths.contains(&s.path)) {
+                entry.apply_to(script);
+            }
+        }
+
+        unmatched
+    }
+}
+
+impl ManifestEntry {
+    fn apply_to(&self, script: &mut Script) {
+        if let Some(description) = &self.description {
+            script.description = Some(description.clone());
+            script.description_full = None;
+        }
+        // An empty category puts the script in the root
+        if let Some(category) = &self.category {
+            script.category = Some(category.clone()).filter(|c| !c.is_empty());
+        }
+        for tag in &self.tags {
+            if !script.tags.contains(tag) {
+                script.tags.push(tag.clone());
+            }
+        }
+        if let Some(confirm) = &self.confirm {
+            script.confirm_message = Some(confirm.clone());
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use crate::Script;
+
+/// Most matches the palette lists at once.
+pub const PALETTE_LIMIT: usize = 10;
+
+/// Extra score for a match in a script's name, category or tags over
+/// one found only in its description.
+const NAME_BONUS: i64 = 20;
+const CATEGORY_BONUS: i64 = 10;
+
+/// The overlay opened with `Ctrl-P`: a query searched across every
+/// script and the highlighted one of its matches.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Palette {
+    pub query: String,
+    /// Position in the ranked matches.
+    pub selected: usize,
+}
+
+/// How well `query` matches `text`, ignoring case and spaces in the
+/// query: its characters must all appear in `text` in order. Runs of
+/// adjacent characters and characters starting a word score higher,
+/// gaps lower. `None` if a character is missing.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+
+    for c in query.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase) {
+        let found = next + text[next..].iter().position(|&t| t == c)?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == found) {
+            score += 5;
+        } else if previous.is_some() {
+            score -= (found - next).min(5) as i64;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(found);
+        next = found + 1;
+    }
+
+    Some(score)
+}
+
+/// A script's best score for `query` across its name, category, tags
+/// and description, favoring the name.
+pub fn score_script(query: &str, script: &Script) -> Option<i64> {
+    let fields = [
+        Some((script.name.as_str(), NAME_BONUS)),
+        script.category.as_deref().map(|c| (c, CATEGORY_BONUS)),
+        script.description.as_deref().map(|d| (d, 0)),
+    ];
+    let tags = script.tags.iter().map(|t| Some((t.as_str(), CATEGORY_BONUS)));
+
+    fields
+        .into_iter()
+        .chain(tags)
+        .flatten()
+        .filter_map(|(text, bonus)| Some(fuzzy_score(query, text)? + bonus))
+        .max()
+}
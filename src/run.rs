@@ -0,0 +1,875 @@
+use std::collections::VecDeque;
+use std::convert;
+use std::env;
+use std::ffi::OsStr;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read};
+#[cfg(unix)]
+use std::os::unix::process::ExitStatusExt;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::{App, ui};
+use crate::{format_command, is_executable, shell_quote, split_args};
+use crate::input::Runner;
+
+/// Upper bound on lines taken from the channel per `drain`, so a script
+/// printing faster than we render can't stall the event loop.
+const MAX_LINES_PER_DRAIN: usize = 10_000;
+
+/// Lines of each stream kept by default; older ones are dropped.
+pub const DEFAULT_MAX_OUTPUT_LINES: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// How output lines that aren't valid UTF-8 are shown, set by
+/// `invalid_utf8` in the config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InvalidUtf8 {
+    /// Replace invalid sequences with `�`.
+    #[default]
+    Lossy,
+    /// Show the line as a hex dump.
+    Hex,
+    /// Leave the line out and count its bytes.
+    Suppress,
+}
+
+/// How a run's output is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Capture {
+    /// Separate pipes for stdout and stderr; the script sees no
+    /// terminal.
+    #[default]
+    Piped,
+    /// One pseudo-terminal for both streams, so `isatty` checks pass
+    /// and scripts print colors and progress as they would in a shell.
+    /// Needs the `pty` feature; piped otherwise.
+    Pty,
+}
+
+/// Where scripts run, set by `working_dir` in the config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkingDir {
+    /// The directory holding the script.
+    #[default]
+    Script,
+    /// The scanned directory the script was found under.
+    Root,
+    /// Wherever herring was started.
+    Launch,
+}
+
+/// One output stream, keeping at most `limit` of its newest lines.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct OutputBuffer {
+    lines: VecDeque<String>,
+    /// `None` keeps everything.
+    pub limit: Option<usize>,
+    dropped: usize,
+}
+
+impl OutputBuffer {
+    /// Appends `text`, continuing the last line if it had no newline
+    /// yet, then drops the oldest lines past `limit`.
+    pub fn push_str(&mut self, text: &str) {
+        for piece in text.split_inclusive('\n') {
+            match self.lines.back_mut() {
+                Some(last) if !last.ends_with('\n') => last.push_str(piece),
+                _ => self.lines.push_back(piece.to_string()),
+            }
+        }
+        if let Some(limit) = self.limit {
+            while self.lines.len() > limit {
+                self.lines.pop_front();
+                self.dropped += 1;
+            }
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.lines.iter().map(String::as_str).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Lines dropped so far to stay within `limit`.
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+}
+
+/// A spawned script whose output is read on background threads.
+pub struct RunningScript {
+    child: Child,
+    receiver: Receiver<(Stream, Vec<u8>)>,
+    /// Both reader threads have hit EOF.
+    eof: bool,
+    /// How the output is actually being read.
+    pub capture: Capture,
+    /// A pseudo-terminal was asked for but this build has none.
+    pty_unavailable: bool,
+    started: Instant,
+    /// The script being run.
+    pub path: String,
+    pub command_line: String,
+    /// The program actually started, looked up on `PATH` if needed.
+    pub program: PathBuf,
+    /// Directory the script runs in.
+    pub dir: PathBuf,
+    /// Variables added on top of the inherited environment.
+    pub env: Vec<(String, String)>,
+    pub stdout: OutputBuffer,
+    pub stderr: OutputBuffer,
+    pub invalid_utf8: InvalidUtf8,
+    /// Plain-text marks on the notes, for `App::ascii`.
+    pub ascii: bool,
+    /// Invalid UTF-8 sequences seen so far, however they were shown.
+    pub invalid_sequences: usize,
+    /// Bytes left out under `InvalidUtf8::Suppress`.
+    pub suppressed_bytes: usize,
+    /// Bytes read from each stream, for hex dump offsets.
+    stdout_offset: usize,
+    stderr_offset: usize,
+}
+
+impl RunningScript {
+    /// Starts `path` with `args` in `dir`, adding `env` on top of the
+    /// inherited environment. A script with a `run_command` or an
+    /// `interpreter` is run through it instead of being executed
+    /// directly. Output is read as `capture` says.
+    pub fn spawn(
+        path: &str,
+        interpreter: Option<&str>,
+        run_command: Option<&str>,
+        args: &[String],
+        env: &[(String, String)],
+        dir: Option<&Path>,
+        capture: Capture,
+    ) -> Result<RunningScript, io::Error> {
+        check_available(path, interpreter.or(run_command))?;
+        let mut command = script_command(path, interpreter, run_command, dir);
+        let program = resolve_program(command.get_program(), env);
+        let dir = match command.get_current_dir() {
+            Some(dir) => dir.to_path_buf(),
+            None => env::current_dir()?,
+        };
+        command
+            .args(args)
+            .envs(env.iter().map(|(k, v)| (k, v)))
+            .stdin(Stdio::null());
+
+        let pty_unavailable = capture == Capture::Pty && !pty::AVAILABLE;
+        let capture = if pty_unavailable { Capture::Piped } else { capture };
+        let (sender, receiver) = mpsc::channel();
+        let child = match capture {
+            Capture::Pty => pty::spawn(command, sender)?,
+            Capture::Piped => {
+                let mut child = command
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .spawn()?;
+                if let Some(stdout) = child.stdout.take() {
+                    forward_lines(stdout, Stream::Stdout, sender.clone(), convert::identity);
+                }
+                if let Some(stderr) = child.stderr.take() {
+                    forward_lines(stderr, Stream::Stderr, sender, convert::identity);
+                }
+                child
+            }
+        };
+
+        let command_line = if args.is_empty() {
+            String::new()
+        } else {
+            format!("Command: {}\n", format_command(path, args))
+        };
+
+        Ok(RunningScript {
+            child,
+            receiver,
+            eof: false,
+            capture,
+            pty_unavailable,
+            started: Instant::now(),
+            path: path.to_string(),
+            command_line,
+            program,
+            dir,
+            env: env.to_vec(),
+            stdout: OutputBuffer::default(),
+            stderr: OutputBuffer::default(),
+            invalid_utf8: InvalidUtf8::default(),
+            ascii: false,
+            invalid_sequences: 0,
+            suppressed_bytes: 0,
+            stdout_offset: 0,
+            stderr_offset: 0,
+        })
+    }
+
+    pub fn id(&self) -> u32 {
+        self.child.id()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Moves any lines the reader threads have produced into `stdout`
+    /// and `stderr`. Returns whether anything arrived.
+    pub fn drain(&mut self) -> bool {
+        let mut received = false;
+
+        for _ in 0..MAX_LINES_PER_DRAIN {
+            match self.receiver.try_recv() {
+                Ok((stream, line)) => self.push_line(stream, &line),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.eof = true;
+                    break;
+                }
+            }
+            received = true;
+        }
+
+        received
+    }
+
+    /// Decodes one line of output onto its stream, handling invalid
+    /// UTF-8 as `invalid_utf8` says.
+    fn push_line(&mut self, stream: Stream, bytes: &[u8]) {
+        let (text, offset) = match stream {
+            Stream::Stdout => (&mut self.stdout, &mut self.stdout_offset),
+            Stream::Stderr => (&mut self.stderr, &mut self.stderr_offset),
+        };
+        let start = *offset;
+        *offset += bytes.len();
+
+        if let Ok(line) = std::str::from_utf8(bytes) {
+            text.push_str(line);
+            return;
+        }
+
+        self.invalid_sequences += bytes
+            .utf8_chunks()
+            .filter(|chunk| !chunk.invalid().is_empty())
+            .count();
+        match self.invalid_utf8 {
+            InvalidUtf8::Lossy => text.push_str(&String::from_utf8_lossy(bytes)),
+            InvalidUtf8::Hex => text.push_str(&hex_dump(bytes, start)),
+            InvalidUtf8::Suppress => self.suppressed_bytes += bytes.len(),
+        }
+    }
+
+    /// Keeps at most `limit` lines of each stream.
+    pub fn limit_lines(&mut self, limit: Option<usize>) {
+        self.stdout.limit = limit;
+        self.stderr.limit = limit;
+    }
+
+    /// Lines shown between the banner and the output: the command
+    /// line and any notes about the capture, invalid UTF-8 or dropped
+    /// lines.
+    pub fn details(&self) -> String {
+        format!(
+            "{}{}{}{}",
+            self.command_line,
+            self.capture_note(),
+            self.encoding_note(),
+            self.truncation_note()
+        )
+    }
+
+    /// A line saying how output read through a pseudo-terminal differs
+    /// from piped output, or why it wasn't. Nothing for plain pipes.
+    pub fn capture_note(&self) -> String {
+        match self.capture {
+            Capture::Pty => format!(
+                "{} Ran in a pseudo-terminal: errors are mixed into the output \
+                 and terminal escapes are stripped\n",
+                ui::glyph(self.ascii, "◌")
+            ),
+            Capture::Piped if self.pty_unavailable => format!(
+                "{} Built without the pty feature: ran with piped output, \
+                 so the script saw no terminal\n",
+                ui::glyph(self.ascii, "⚠")
+            ),
+            Capture::Piped => String::new(),
+        }
+    }
+
+    /// What the script ran as and with, for working out why it failed:
+    /// the program, directory, how it exited and the environment
+    /// variables most likely to matter.
+    pub fn diagnostics(&self, status: ExitStatus) -> String {
+        let mut text = format!(
+            "=== DIAGNOSTICS ===\nExecutable: {}\nWorking directory: {}\n{}\n",
+            self.program.display(),
+            self.dir.display(),
+            exit_line(status)
+        );
+
+        let overrides = self
+            .env
+            .iter()
+            .map(|(key, _)| key.as_str())
+            .filter(|key| !DIAGNOSTIC_VARS.contains(key));
+        for key in DIAGNOSTIC_VARS.iter().copied().chain(overrides) {
+            if let Some(value) = child_var(key, &self.env) {
+                text.push_str(&format!("{}={}\n", key, value));
+            }
+        }
+        text
+    }
+
+    /// A line saying output was cut to the newest lines, if it was.
+    pub fn truncation_note(&self) -> String {
+        match self.stdout.limit {
+            Some(limit) if self.stdout.dropped() + self.stderr.dropped() > 0 => format!(
+                "{} Output truncated, showing the last {} lines of each stream\n",
+                ui::glyph(self.ascii, "⚠"),
+                limit
+            ),
+            _ => String::new(),
+        }
+    }
+
+    /// A line explaining how invalid UTF-8 was handled, or nothing if
+    /// the output was all valid.
+    pub fn encoding_note(&self) -> String {
+        if self.invalid_sequences == 0 {
+            return String::new();
+        }
+        let warn = ui::glyph(self.ascii, "⚠");
+        match self.invalid_utf8 {
+            InvalidUtf8::Lossy => format!(
+                "{} {} invalid UTF-8 sequences replaced with �\n",
+                warn, self.invalid_sequences
+            ),
+            InvalidUtf8::Hex => format!("{} Lines with invalid UTF-8 are shown as hex\n", warn),
+            InvalidUtf8::Suppress => format!(
+                "{} Binary output suppressed ({} bytes)\n",
+                warn, self.suppressed_bytes
+            ),
+        }
+    }
+
+    /// The exit status once the process has exited and all of its
+    /// output has been drained.
+    pub fn try_finish(&mut self) -> Result<Option<ExitStatus>, io::Error> {
+        if !self.eof {
+            return Ok(None);
+        }
+        self.child.try_wait()
+    }
+
+    /// Kills the process and reaps it. A process that already exited
+    /// is simply reaped.
+    pub fn kill(&mut self) -> Result<ExitStatus, io::Error> {
+        // Fails only if the process is already gone; wait() still reaps it
+        let _ = self.child.kill();
+        let status = self.child.wait()?;
+        self.drain();
+        Ok(status)
+    }
+}
+
+/// `bytes` as `hexdump -C` style rows of 16, with offsets counted
+/// from `start`.
+pub fn hex_dump(bytes: &[u8], start: usize) -> String {
+    bytes
+        .chunks(16)
+        .enumerate()
+        .map(|(i, row)| {
+            let hex: Vec<String> = row.iter().map(|b| format!("{:02x}", b)).collect();
+            let ascii: String = row
+                .iter()
+                .map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' })
+                .collect();
+            format!("{:08x}  {:<47}  |{}|\n", start + i * 16, hex.join(" "), ascii)
+        })
+        .collect()
+}
+
+/// Sends each line `source` produces, passed through `clean`, until
+/// it runs out or the receiver goes away.
+fn forward_lines<R>(
+    source: R,
+    stream: Stream,
+    sender: Sender<(Stream, Vec<u8>)>,
+    clean: fn(Vec<u8>) -> Vec<u8>,
+) where
+    R: Read + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut reader = BufReader::new(source);
+        let mut buffer = Vec::new();
+
+        loop {
+            buffer.clear();
+            match reader.read_until(b'\n', &mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    let line = clean(std::mem::take(&mut buffer));
+                    if sender.send((stream, line)).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// `line` as read from a pseudo-terminal, made fit for the output
+/// view: the `\r` before each newline dropped, only what follows the
+/// last other `\r` kept (as a progress bar redrawing itself would
+/// leave it) and escape sequences such as colors removed.
+pub fn terminal_line(line: &[u8]) -> Vec<u8> {
+    let (body, newline) = match line.strip_suffix(b"\n") {
+        Some(body) => (body.strip_suffix(b"\r").unwrap_or(body), true),
+        None => (line, false),
+    };
+    let body = match body.iter().rposition(|&b| b == b'\r') {
+        Some(i) => &body[i + 1..],
+        None => body,
+    };
+
+    let mut text = Vec::with_capacity(body.len() + 1);
+    let mut bytes = body.iter().copied().peekable();
+    while let Some(b) = bytes.next() {
+        if b != 0x1b {
+            text.push(b);
+            continue;
+        }
+        match bytes.next() {
+            // CSI: parameters, then one final byte from `@` to `~`
+            Some(b'[') => while bytes.next().is_some_and(|b| !(0x40..=0x7e).contains(&b)) {},
+            // OSC: ended by BEL or `ESC \`
+            Some(b']') => {
+                while let Some(b) = bytes.next() {
+                    if b == 0x07 || (b == 0x1b && bytes.next_if_eq(&b'\\').is_some()) {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    if newline {
+        text.push(b'\n');
+    }
+    text
+}
+
+#[cfg(all(feature = "pty", unix))]
+mod pty {
+    use std::fs::File;
+    use std::io;
+    use std::os::fd::{FromRawFd, OwnedFd};
+    use std::process::{Child, Command, Stdio};
+    use std::ptr;
+    use std::sync::mpsc::Sender;
+
+    use super::{Stream, forward_lines, terminal_line};
+
+    pub const AVAILABLE: bool = true;
+
+    /// Starts `command` with a new pseudo-terminal as its stdout and
+    /// stderr, sending what it prints as `Stream::Stdout` lines.
+    pub fn spawn(mut command: Command, sender: Sender<(Stream, Vec<u8>)>) -> io::Result<Child> {
+        let (master, slave) = open(window_size())?;
+        command
+            .stdout(Stdio::from(slave.try_clone()?))
+            .stderr(Stdio::from(slave));
+        let child = command.spawn()?;
+        // Our copies of the terminal end must be closed, or reading the
+        // master never sees the script finish
+        drop(command);
+
+        forward_lines(File::from(master), Stream::Stdout, sender, |line| terminal_line(&line));
+        Ok(child)
+    }
+
+    /// The size of herring's own terminal, so the script lays out its
+    /// output for it, or 80x24 if there is none.
+    fn window_size() -> libc::winsize {
+        let (cols, rows) = crossterm::terminal::size().unwrap_or((80, 24));
+        libc::winsize {
+            ws_row: rows,
+            ws_col: cols,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        }
+    }
+
+    fn open(mut size: libc::winsize) -> io::Result<(OwnedFd, OwnedFd)> {
+        let mut master = -1;
+        let mut slave = -1;
+        // SAFETY: openpty writes two descriptors on success and only
+        // reads `size`; the pointers are mutable only on some platforms
+        let result = unsafe {
+            libc::openpty(&mut master, &mut slave, ptr::null_mut(), ptr::null_mut(), &raw mut size)
+        };
+        if result != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: both were just opened and nothing else owns them
+        Ok(unsafe { (OwnedFd::from_raw_fd(master), OwnedFd::from_raw_fd(slave)) })
+    }
+}
+
+/// Built without the `pty` feature: runs fall back to pipes.
+#[cfg(not(all(feature = "pty", unix)))]
+mod pty {
+    use std::io;
+    use std::process::{Child, Command};
+    use std::sync::mpsc::Sender;
+
+    use super::Stream;
+
+    pub const AVAILABLE: bool = false;
+
+    pub fn spawn(_command: Command, _sender: Sender<(Stream, Vec<u8>)>) -> io::Result<Child> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "built without the pty feature"))
+    }
+}
+
+/// Variables always shown by `RunningScript::diagnostics`, alongside
+/// any set from `.herring.env`.
+const DIAGNOSTIC_VARS: &[&str] = &["PATH", "HOME", "USER", "SHELL", "LANG"];
+
+/// The value `key` has in a child started with `env` on top of ours.
+fn child_var(key: &str, env: &[(String, String)]) -> Option<String> {
+    match env.iter().rev().find(|(k, _)| k == key) {
+        Some((_, value)) => Some(value.clone()),
+        None => env::var(key).ok(),
+    }
+}
+
+/// Where `program` is found: itself if it names a path, otherwise the
+/// first match on the child's `PATH`.
+fn resolve_program(program: &OsStr, env: &[(String, String)]) -> PathBuf {
+    let program = Path::new(program);
+    if program.components().count() > 1 {
+        return program.to_path_buf();
+    }
+    child_var("PATH", env)
+        .and_then(|path| {
+            env::split_paths(&path)
+                .map(|dir| dir.join(program))
+                .find(|candidate| candidate.is_file())
+        })
+        .unwrap_or_else(|| program.to_path_buf())
+}
+
+/// `Exit code: N`, or the signal that ended the process.
+fn exit_line(status: ExitStatus) -> String {
+    match killed_by(status) {
+        Some(signal) => format!("Signal: {}", signal_label(signal)),
+        None => format!("Exit code: {}", status.code().unwrap_or(-1)),
+    }
+}
+
+/// The signal that ended the process, if one did.
+#[cfg(unix)]
+pub fn killed_by(status: ExitStatus) -> Option<i32> {
+    status.signal()
+}
+
+/// Processes only end by signal on Unix.
+#[cfg(not(unix))]
+pub fn killed_by(_status: ExitStatus) -> Option<i32> {
+    None
+}
+
+/// The code a shell would report for `status`: the exit code, or
+/// 128 plus the signal for a process killed by one.
+pub fn shell_exit_code(status: ExitStatus) -> i32 {
+    match killed_by(status) {
+        Some(signal) => 128 + signal,
+        None => status.code().unwrap_or(1),
+    }
+}
+
+/// The usual name of a signal number, for those that are the same on
+/// Linux, macOS and the BSDs.
+pub fn signal_name(signal: i32) -> Option<&'static str> {
+    Some(match signal {
+        1 => "SIGHUP",
+        2 => "SIGINT",
+        3 => "SIGQUIT",
+        4 => "SIGILL",
+        5 => "SIGTRAP",
+        6 => "SIGABRT",
+        8 => "SIGFPE",
+        9 => "SIGKILL",
+        11 => "SIGSEGV",
+        13 => "SIGPIPE",
+        14 => "SIGALRM",
+        15 => "SIGTERM",
+        _ => return None,
+    })
+}
+
+/// `11 (SIGSEGV)`, or just the number for signals without a known name.
+fn signal_label(signal: i32) -> String {
+    match signal_name(signal) {
+        Some(name) => format!("{} ({})", signal, name),
+        None => signal.to_string(),
+    }
+}
+
+/// Checks that a script found by the scan can still be run: it must
+/// still be a regular file, and executable unless it goes through a
+/// `launcher` such as an interpreter.
+fn check_available(path: &str, launcher: Option<&str>) -> Result<(), io::Error> {
+    let runnable = fs::metadata(path).is_ok_and(|metadata| {
+        metadata.is_file() && (launcher.is_some() || is_executable(Path::new(path), &metadata))
+    });
+    if runnable {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Script no longer available: {}", path),
+        ))
+    }
+}
+
+/// Builds the command for a script, going through its `run_command`
+/// or shebang `interpreter` when it has one and running in `dir` if
+/// given.
+fn script_command(
+    path: &str,
+    interpreter: Option<&str>,
+    run_command: Option<&str>,
+    dir: Option<&Path>,
+) -> Command {
+    // A relative path would otherwise be looked up from `dir`
+    let path = match dir {
+        Some(_) => std::path::absolute(path).unwrap_or_else(|_| PathBuf::from(path)),
+        None => PathBuf::from(path),
+    };
+
+    if let Some(template) = run_command
+        && let Some(mut command) = template_command(template, &path)
+    {
+        if let Some(dir) = dir {
+            command.current_dir(dir);
+        }
+        return command;
+    }
+
+    let mut command = match interpreter.map(str::trim) {
+        None => Command::new(&path),
+        Some(line) => {
+            // Like the kernel, pass everything after the interpreter as
+            // one argument
+            let (program, arg) = match line.split_once(char::is_whitespace) {
+                Some((program, arg)) => (program, Some(arg.trim())),
+                None => (line, None),
+            };
+            let mut command = Command::new(program);
+            command.args(arg).arg(&path);
+            command
+        }
+    };
+    if let Some(dir) = dir {
+        command.current_dir(dir);
+    }
+    command
+}
+
+/// Builds a `herring-run:` command: `template` split into words, with
+/// `{}` in each replaced by `path`, or `path` added at the end if no
+/// word has `{}`. `None` if the template is empty.
+fn template_command(template: &str, path: &Path) -> Option<Command> {
+    let path = path.to_string_lossy();
+    let mut words = split_args(template);
+    if !words.iter().any(|word| word.contains("{}")) {
+        words.push("{}".to_string());
+    }
+    let mut words = words.iter().map(|word| word.replace("{}", &path));
+
+    let mut command = Command::new(words.next()?);
+    command.args(words);
+    Some(command)
+}
+
+/// Runs `path` attached to the terminal and waits for it. The caller
+/// must have released the terminal first.
+pub fn run_interactive(
+    path: &str,
+    interpreter: Option<&str>,
+    run_command: Option<&str>,
+    args: &[String],
+    env: &[(String, String)],
+    dir: Option<&Path>,
+) -> Result<ExitStatus, io::Error> {
+    check_available(path, interpreter.or(run_command))?;
+    script_command(path, interpreter, run_command, dir)
+        .args(args)
+        .envs(env.iter().map(|(k, v)| (k, v)))
+        .status()
+}
+
+/// Editor used when `$EDITOR` isn't set.
+pub const DEFAULT_EDITOR: &str = "vi";
+
+/// The editor command from `$EDITOR`, or `vi`.
+pub fn editor() -> String {
+    env::var("EDITOR")
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string())
+}
+
+/// Opens `path` in `editor` with the terminal's stdio, waiting for it
+/// to exit. The editor command may carry its own arguments, e.g.
+/// `code --wait`.
+pub fn open_in_editor(editor: &str, path: &str) -> Result<ExitStatus, io::Error> {
+    let words = split_args(editor);
+    let (program, args) = words
+        .split_first()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "empty editor command"))?;
+    Command::new(program).args(args).arg(path).status()
+}
+
+/// Describes what `spawn` would run for `path`, without running it.
+pub fn preview(
+    path: &str,
+    interpreter: Option<&str>,
+    run_command: Option<&str>,
+    args: &[String],
+    env: &[(String, String)],
+    dir: Option<&Path>,
+) -> String {
+    let mut command = script_command(path, interpreter, run_command, dir);
+    command.args(args);
+
+    let (program, argv) = command_words(&command);
+    let dir = match command.get_current_dir() {
+        Some(dir) => dir.display().to_string(),
+        None => env::current_dir()
+            .map(|d| d.display().to_string())
+            .unwrap_or_else(|e| format!("unknown ({})", e)),
+    };
+
+    let mut text = format!(
+        "Command: {}\nScript: {}\nWorking directory: {}\nEnvironment:",
+        format_command(&program, &argv),
+        path,
+        dir
+    );
+    if env.is_empty() {
+        text.push_str(" inherited, no overrides\n");
+    } else {
+        text.push('\n');
+        for (key, value) in env {
+            text.push_str(&format!("  {}={}\n", key, value));
+        }
+    }
+    text
+}
+
+/// What `spawn` would run for `path` as one shell line: a `cd` into
+/// `dir` if given, the `env` overrides, then the command itself.
+pub fn command_line(
+    path: &str,
+    interpreter: Option<&str>,
+    run_command: Option<&str>,
+    args: &[String],
+    env: &[(String, String)],
+    dir: Option<&Path>,
+) -> String {
+    let mut command = script_command(path, interpreter, run_command, dir);
+    command.args(args);
+
+    let mut line = String::new();
+    if let Some(dir) = command.get_current_dir() {
+        line.push_str(&format!("cd {} && ", shell_quote(&dir.to_string_lossy())));
+    }
+    for (key, value) in env {
+        line.push_str(&format!("{}={} ", key, shell_quote(value)));
+    }
+    let (program, argv) = command_words(&command);
+    line.push_str(&format_command(&program, &argv));
+    line
+}
+
+/// A command's program and arguments as text.
+fn command_words(command: &Command) -> (String, Vec<String>) {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let argv = command
+        .get_args()
+        .map(|a| a.to_string_lossy().into_owned())
+        .collect();
+    (program, argv)
+}
+
+/// Runs scripts as child processes via `App::start_run`.
+pub struct ProcessRunner;
+
+impl Runner for ProcessRunner {
+    fn run_selected(&mut self, app: &mut App) -> Result<(), io::Error> {
+        app.start_run()
+    }
+}
+
+/// Lays out the output view text: a two-line banner, the command line
+/// if arguments were given, then the captured output and errors.
+pub fn format_output(
+    banner: &str,
+    command_line: &str,
+    stdout: &str,
+    stderr: &str,
+) -> String {
+    format!(
+        "{}\n\
+         {}\n\
+         === OUTPUT ===\n{}\n\n\
+         === ERRORS ===\n{}",
+        banner,
+        command_line,
+        if stdout.is_empty() { "(no output)" } else { stdout },
+        if stderr.is_empty() { "(none)" } else { stderr }
+    )
+}
+
+/// `output` without the two-line banner `format_output` puts first.
+pub fn strip_banner(output: &str) -> &str {
+    output.splitn(3, '\n').nth(2).unwrap_or("")
+}
+
+/// Banner for a finished run, marked `[OK]`/`[FAIL]` when `ascii`.
+pub fn result_banner(status: ExitStatus, ascii: bool) -> String {
+    let fail = ui::glyph(ascii, "✗");
+    if let Some(signal) = killed_by(status) {
+        return format!(
+            "{} Script killed by signal {}\nExit code: none",
+            fail,
+            signal_label(signal)
+        );
+    }
+    let code = status.code().unwrap_or(-1);
+
+    if code == 0 {
+        format!("{} Script completed successfully\nExit code: 0", ui::glyph(ascii, "✓"))
+    } else {
+        format!("{} Script failed\nExit code: {}", fail, code)
+    }
+}
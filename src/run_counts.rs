@@ -0,0 +1,62 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::config::{cache_dir, read_if_exists};
+
+/// How many times each script has been run, across sessions, keyed by
+/// absolute path.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RunCounts {
+    pub counts: BTreeMap<String, u64>,
+}
+
+impl RunCounts {
+    /// Location of the run counts file, if a home directory is known.
+    pub fn default_path() -> Option<PathBuf> {
+        Some(cache_dir()?.join("run-counts"))
+    }
+
+    /// Reads a file of `count<TAB>path` lines. A missing file means no
+    /// runs yet; malformed lines are skipped.
+    pub fn load_from(path: &Path) -> Result<RunCounts, io::Error> {
+        let Some(contents) = read_if_exists(path)? else {
+            return Ok(RunCounts::default());
+        };
+
+        let counts = contents
+            .lines()
+            .filter_map(|line| {
+                let (count, path) = line.split_once('\t')?;
+                Some((path.to_string(), count.parse().ok()?))
+            })
+            .collect();
+
+        Ok(RunCounts { counts })
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), io::Error> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let contents: String = self
+            .counts
+            .iter()
+            .map(|(path, count)| format!("{}\t{}\n", count, path))
+            .collect();
+        fs::write(path, contents)
+    }
+
+    pub fn get(&self, path: &str) -> u64 {
+        self.counts.get(path).copied().unwrap_or(0)
+    }
+
+    /// Counts one more run of `path`, returning the new total.
+    pub fn increment(&mut self, path: &str) -> u64 {
+        let count = self.counts.entry(path.to_string()).or_insert(0);
+        *count += 1;
+        *count
+    }
+}
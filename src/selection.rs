@@ -0,0 +1,71 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::config::{cache_dir, read_if_exists};
+
+/// The script last selected in each scanned directory, so the next
+/// session starts there.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Selections {
+    /// Absolute script path, keyed by the scanned directories.
+    pub entries: BTreeMap<String, String>,
+}
+
+impl Selections {
+    /// Location of the selections file, if a home directory is known.
+    pub fn default_path() -> Option<PathBuf> {
+        Some(cache_dir()?.join("selection"))
+    }
+
+    /// The key for a set of scanned directories: their absolute paths.
+    pub fn key(directories: &[String]) -> String {
+        directories
+            .iter()
+            .map(|dir| {
+                fs::canonicalize(dir)
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_else(|_| dir.clone())
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Reads `directories<TAB>script` lines. A missing file means
+    /// nothing is remembered; malformed lines are skipped.
+    pub fn load_from(path: &Path) -> Result<Selections, io::Error> {
+        let Some(contents) = read_if_exists(path)? else {
+            return Ok(Selections::default());
+        };
+
+        let entries = contents
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .map(|(key, script)| (key.to_string(), script.to_string()))
+            .collect();
+
+        Ok(Selections { entries })
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), io::Error> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let contents: String = self
+            .entries
+            .iter()
+            .map(|(key, script)| format!("{}\t{}\n", key, script))
+            .collect();
+        fs::write(path, contents)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(String::as_str)
+    }
+
+    pub fn set(&mut self, key: String, script: String) {
+        self.entries.insert(key, script);
+    }
+}
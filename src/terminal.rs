@@ -1,26 +0,0 @@
-use std::io;
-use crossterm::{
-    execute,
-    terminal::{
-        disable_raw_mode,
-        enable_raw_mode,
-        EnterAlternateScreen,
-        LeaveAlternateScreen,
-    },
-};
-
-pub struct TerminalGuard;
-
-impl Drop for TerminalGuard {
-    fn drop(&mut self) {
-        let _ = disable_raw_mode();
-        let _ = execute!(io::stdout(), LeaveAlternateScreen);
-    }
-}
-
-pub fn setup() -> Result<TerminalGuard, io::Error> {
-    // Returns the guard. This is a common Rust pattern: a setup function that returns a cleanup guard.
-    enable_raw_mode()?;
-    execute!(io::stdout(), EnterAlternateScreen)?;
-    Ok(TerminalGuard)
-}
@@ -0,0 +1,136 @@
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use ratatui::style::Color;
+use serde::Deserialize;
+
+use crate::config::{config_dir, read_if_exists};
+
+/// Colors used by the views, read from
+/// `~/.config/rusty-herring/theme.toml` and then the config's `[theme]`
+/// table.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    pub border: Color,
+    /// The selected script or history entry.
+    pub selection: Color,
+    pub success: Color,
+    pub error: Color,
+    /// Script names, output and help text.
+    pub text: Color,
+    /// Footers and hints.
+    pub muted: Color,
+    /// Category headers.
+    pub header: Color,
+    /// Help and prompt borders, running output and warnings.
+    pub accent: Color,
+    /// Background of every other list row.
+    pub stripe: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme {
+            border: Color::Cyan,
+            selection: Color::Yellow,
+            success: Color::Green,
+            error: Color::Red,
+            text: Color::White,
+            muted: Color::Gray,
+            header: Color::Magenta,
+            accent: Color::Yellow,
+            stripe: Color::Indexed(235),
+        }
+    }
+}
+
+/// Colors as written in `theme.toml` or a config's `[theme]` table:
+/// every color is optional and given as a name (`"light-blue"`), an
+/// index (`"208"`) or hex (`"#rrggbb"`).
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeColors {
+    pub border: Option<String>,
+    pub selection: Option<String>,
+    pub success: Option<String>,
+    pub error: Option<String>,
+    pub text: Option<String>,
+    pub muted: Option<String>,
+    pub header: Option<String>,
+    pub accent: Option<String>,
+    pub stripe: Option<String>,
+}
+
+impl ThemeColors {
+    /// `self`, with the colors it leaves out taken from `base`.
+    pub fn or(self, base: ThemeColors) -> ThemeColors {
+        ThemeColors {
+            border: self.border.or(base.border),
+            selection: self.selection.or(base.selection),
+            success: self.success.or(base.success),
+            error: self.error.or(base.error),
+            text: self.text.or(base.text),
+            muted: self.muted.or(base.muted),
+            header: self.header.or(base.header),
+            accent: self.accent.or(base.accent),
+            stripe: self.stripe.or(base.stripe),
+        }
+    }
+}
+
+impl Theme {
+    /// Location of the theme file, if a home directory is known.
+    pub fn default_path() -> Option<PathBuf> {
+        Some(config_dir()?.join("theme.toml"))
+    }
+
+    /// Parses a theme; colors it leaves out keep their defaults.
+    pub fn parse(text: &str) -> Result<Theme, String> {
+        let colors: ThemeColors = toml::from_str(text).map_err(|e| e.message().to_string())?;
+        Theme::default().with(&colors)
+    }
+
+    /// `self` with the colors `colors` gives replaced.
+    pub fn with(mut self, colors: &ThemeColors) -> Result<Theme, String> {
+        for (value, slot) in [
+            (&colors.border, &mut self.border),
+            (&colors.selection, &mut self.selection),
+            (&colors.success, &mut self.success),
+            (&colors.error, &mut self.error),
+            (&colors.text, &mut self.text),
+            (&colors.muted, &mut self.muted),
+            (&colors.header, &mut self.header),
+            (&colors.accent, &mut self.accent),
+            (&colors.stripe, &mut self.stripe),
+        ] {
+            if let Some(value) = value {
+                *slot = Color::from_str(value.trim())
+                    .map_err(|_| format!("unknown color {:?}", value))?;
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Reads a theme file. A missing file yields the defaults.
+    pub fn load_from(path: &Path) -> Result<Theme, String> {
+        match read_if_exists(path) {
+            Ok(Some(text)) => Theme::parse(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+            Ok(None) => Ok(Theme::default()),
+            Err(e) => Err(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    /// Loads the user's theme, warning on stderr and falling back to
+    /// the defaults if it can't be used.
+    pub fn load() -> Theme {
+        let Some(path) = Theme::default_path() else {
+            return Theme::default();
+        };
+
+        Theme::load_from(&path).unwrap_or_else(|e| {
+            eprintln!("Warning: ignoring theme {}", e);
+            Theme::default()
+        })
+    }
+}
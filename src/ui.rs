@@ -1,53 +1,409 @@
 use ratatui::{
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    text::{Line, Span},
+    widgets::{
+        Block, Borders, Clear, List, ListItem, Paragraph, Scrollbar, ScrollbarOrientation,
+        ScrollbarState, Wrap,
+    },
 };
-use crate::App;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
 
-pub fn render_list_view(
-    f: &mut ratatui::Frame,
-    app: &App,
-) {
-    let size = f.size();
+use crate::{App, Overlay, RunStatus, TagFilter, UNCATEGORIZED_GROUP, gutter_width, numbered_rows};
+use crate::input::{
+    HISTORY_HINTS, LIST_HINTS, OUTPUT_HINTS, RUNNING_HINTS, SCROLL_HINTS, help_lines, hint_bar,
+};
 
-    let chunks = Layout::default()
+/// Whether colors should be used, given the `NO_COLOR` and `TERM`
+/// environment values. See https://no-color.org.
+pub fn color_supported(no_color: Option<&str>, term: Option<&str>) -> bool {
+    if plain_requested(no_color) {
+        return false;
+    }
+    term != Some("dumb")
+}
+
+/// Whether `NO_COLOR` asks for plain output, symbols included: set and
+/// not empty.
+pub fn plain_requested(no_color: Option<&str>) -> bool {
+    no_color.is_some_and(|v| !v.is_empty())
+}
+
+/// Reads `NO_COLOR` and `TERM` from the environment.
+pub fn detect_color_support() -> bool {
+    let no_color = std::env::var("NO_COLOR").ok();
+    let term = std::env::var("TERM").ok();
+    color_supported(no_color.as_deref(), term.as_deref())
+}
+
+/// Symbols drawn by the views and their plain-text stand-ins for
+/// `App::ascii`.
+const ASCII_GLYPHS: &[(&str, &str)] = &[
+    ("✓", "[OK]"),
+    ("✗", "[FAIL]"),
+    ("⚠", "[WARN]"),
+    ("◌", "[i]"),
+    ("▶", ">"),
+    ("▸", "+"),
+    ("▾", "-"),
+    ("📌", "[pin]"),
+    ("★", "*"),
+    ("×", "x"),
+    ("━", "="),
+    ("─", "-"),
+    ("—", "-"),
+    ("…", "..."),
+];
+
+/// `text` with every symbol in `ASCII_GLYPHS` spelled out.
+pub fn ascii_glyphs(text: &str) -> String {
+    ASCII_GLYPHS
+        .iter()
+        .fold(text.to_string(), |text, (glyph, plain)| text.replace(glyph, plain))
+}
+
+/// `symbol`, or its stand-in from `ASCII_GLYPHS` when `ascii` is set.
+/// For marks built into banners and messages, which keeps the text
+/// around them, such as a script's own output, as it is.
+pub fn glyph(ascii: bool, symbol: &'static str) -> &'static str {
+    match ASCII_GLYPHS.iter().find(|(glyph, _)| *glyph == symbol) {
+        Some((_, plain)) if ascii => plain,
+        _ => symbol,
+    }
+}
+
+/// `text` as the views should draw it: spelled out in ASCII mode,
+/// untouched otherwise.
+fn glyphs<'a>(app: &App, text: &'a str) -> Cow<'a, str> {
+    if app.ascii && !text.is_ascii() {
+        Cow::Owned(ascii_glyphs(text))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// `glyphs` for text the caller has built and no longer needs.
+fn ascii_or(app: &App, text: String) -> String {
+    match glyphs(app, &text) {
+        Cow::Owned(plain) => plain,
+        Cow::Borrowed(_) => text,
+    }
+}
+
+/// Name of the selected script for view headings, or a placeholder if
+/// a rescan left nothing to select.
+fn selected_name(app: &App) -> &str {
+    app.scripts
+        .get(app.selected_index)
+        .map_or("(no script)", |script| script.name.as_str())
+}
+
+/// Foreground-colored style, or an unstyled one when colors are off.
+fn fg(use_color: bool, color: Color) -> Style {
+    if use_color {
+        Style::default().fg(color)
+    } else {
+        Style::default()
+    }
+}
+
+/// Title, list and footer areas of the list view.
+fn list_layout(size: Rect) -> std::rc::Rc<[Rect]> {
+    Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3),
             Constraint::Min(0),
-            Constraint::Length(3),
+            Constraint::Length(4),
         ])
-        .split(size);
+        .split(size)
+}
 
-    let title = Paragraph::new(
-        format!("Script Runner - {} scripts", app.scripts.len())
-    )
+/// Where the list view draws its rows (inside the border), so the
+/// event loop can map mouse clicks back to scripts.
+pub fn list_rows_area(size: Rect) -> Rect {
+    let list = list_layout(size)[1];
+    Rect {
+        x: list.x + 1,
+        y: list.y + 1,
+        width: list.width.saturating_sub(2),
+        height: list.height.saturating_sub(2),
+    }
+}
+
+/// Width inside the list border from which the list is drawn in two
+/// columns.
+pub const TWO_COLUMN_MIN_WIDTH: u16 = 140;
+
+/// Stands in for a description the background reader hasn't got to.
+pub const LOADING_DESCRIPTION: &str = "(loading…)";
+
+/// How many columns the list is drawn in, given the width inside its
+/// border.
+pub fn list_column_count(width: u16) -> usize {
+    if width >= TWO_COLUMN_MIN_WIDTH { 2 } else { 1 }
+}
+
+/// A list row placed in its column, `top` rows below the border.
+struct PlacedItem<'a> {
+    item: ListItem<'a>,
+    index: Option<usize>,
+    column: usize,
+    top: usize,
+}
+
+/// The list rows split over `columns` columns, newspaper style: the
+/// first column is filled to about its share of the rows, then the
+/// next.
+fn place_items(app: &App, columns: usize) -> Vec<PlacedItem<'_>> {
+    let items = list_items(app, columns);
+    let total: usize = items.iter().map(|(item, _)| item.height()).sum();
+    let per_column = total.div_ceil(columns.max(1));
+
+    let mut placed = Vec::with_capacity(items.len());
+    let (mut column, mut top) = (0, 0);
+    for (item, index) in items {
+        if column + 1 < columns && top >= per_column {
+            column += 1;
+            top = 0;
+        }
+        let height = item.height();
+        placed.push(PlacedItem { item, index, column, top });
+        top += height;
+    }
+    placed
+}
+
+/// The script drawn at `x`, `y` within the list rows area, as laid out
+/// for `app.list_area`. A collapsed group's header counts as its first
+/// script; other headers and empty space are `None`.
+pub fn script_at(app: &App, x: u16, y: u16) -> Option<usize> {
+    let columns = list_column_count(app.list_area.width);
+    let column = (x as usize * columns / app.list_area.width.max(1) as usize).min(columns - 1);
+    let row = y as usize;
+
+    place_items(app, columns)
+        .into_iter()
+        .find(|p| p.column == column && p.top <= row && row < p.top + p.item.height())
+        .and_then(|p| p.index)
+}
+
+/// The script in the column left or right of the selection, on about
+/// the same row. `None` in a single column or at the edge.
+pub fn script_beside(app: &App, right: bool) -> Option<usize> {
+    let columns = list_column_count(app.list_area.width);
+    let placed = place_items(app, columns);
+    let current = placed.iter().find(|p| p.index == Some(app.selected_index))?;
+    let column = if right {
+        Some(current.column + 1).filter(|&c| c < columns)?
+    } else {
+        current.column.checked_sub(1)?
+    };
+
+    let targets: Vec<&PlacedItem> = placed
+        .iter()
+        .filter(|p| p.column == column && p.index.is_some())
+        .collect();
+    targets
+        .iter()
+        .rev()
+        .find(|p| p.top <= current.top)
+        .or(targets.first())
+        .and_then(|p| p.index)
+}
+
+pub fn render_list_view(
+    f: &mut ratatui::Frame,
+    app: &App,
+) {
+    let size = f.size();
+
+    let chunks = list_layout(size);
+
+    let mut title_line = vec![Span::raw(app.title())];
+    if !app.scan_warnings.is_empty() {
+        title_line.push(Span::styled(
+            ascii_or(app, format!("  ⚠ {} skipped (W)", app.scan_warnings.len())),
+            fg(app.use_color, app.theme.accent),
+        ));
+    }
+    let title = Paragraph::new(Line::from(title_line))
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .title("Scripts")
-                .border_style(Style::default().fg(Color::Cyan))
+                .border_style(fg(app.use_color, app.theme.border))
         );
     f.render_widget(title, chunks[0]);
 
-    let mut items: Vec<ListItem> = Vec::new();
-    let mut current_category: Option<&Option<String>> = None;
+    let mut list_title = if app.overlay == Some(Overlay::Filter) || !app.filter_query.is_empty() {
+        format!("Available Scripts /{}", app.filter_query)
+    } else {
+        "Available Scripts".to_string()
+    };
+    match &app.tag_filter {
+        Some(TagFilter::Tag(tag)) => list_title.push_str(&format!(" [tag: {}]", tag)),
+        Some(TagFilter::Untagged) => list_title.push_str(" [untagged]"),
+        None => {}
+    }
+    if app.failed_only {
+        list_title.push_str(" [failed]");
+    }
+    if !app.grouped() {
+        list_title.push_str(&format!(" (by {})", app.sort_mode.label()));
+    }
 
-    for (i, script) in app.scripts.iter().enumerate() {
-        // Add category header if category changed
-        if current_category != Some(&script.category) {
-            current_category = Some(&script.category);
-            let header = match &script.category {
-                Some(cat) => format!("── {} ──", cat),
-                None => "── Uncategorized ──".to_string(),
-            };
-            items.push(
-                ListItem::new(header)
-                    .style(Style::default()
-                        .fg(Color::Magenta)
-                        .add_modifier(Modifier::BOLD))
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(list_title)
+        .border_style(fg(app.use_color, app.theme.border));
+    let inner = block.inner(chunks[1]);
+    f.render_widget(block, chunks[1]);
+
+    let columns = list_column_count(inner.width);
+    let areas = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Ratio(1, columns as u32); columns])
+        .split(inner);
+    let mut column_items: Vec<Vec<ListItem>> = vec![Vec::new(); columns];
+    for placed in place_items(app, columns) {
+        column_items[placed.column].push(placed.item);
+    }
+    for (items, area) in column_items.into_iter().zip(areas.iter()) {
+        f.render_widget(List::new(items), *area);
+    }
+
+    let inner_width = chunks[2].width.saturating_sub(2) as usize;
+    let selected_path = match &app.status_message {
+        Some(message) => message.clone(),
+        None => app
+            .selected_script()
+            .map(|script| {
+                let category = format!(
+                    "[{}] ",
+                    script.category.as_deref().unwrap_or(UNCATEGORIZED_GROUP)
+                );
+                let room = inner_width.saturating_sub(category.chars().count());
+                let path = truncate_left(&app.display_path(script), room);
+                let mut line = format!("{}{}", category, path);
+                if app.compact
+                    && let Some(description) = &script.description
+                {
+                    let room = inner_width.saturating_sub(line.chars().count() + 3);
+                    if room > 0 {
+                        line.push_str(" — ");
+                        line.push_str(&truncate_right(description, room));
+                    }
+                }
+                line
+            })
+            .unwrap_or_default(),
+    };
+
+    let footer_text = if app.overlay == Some(Overlay::Args) {
+        format!(
+            "Args: {}_\nEnter: Run with args | Esc: Cancel",
+            app.pending_args
+        )
+    } else if !app.jump_number.is_empty() {
+        format!(
+            "Go to row: {}_\nEnter: Jump now | other keys: Cancel",
+            app.jump_number
+        )
+    } else {
+        format!("{}\n{}", selected_path, hint_bar(LIST_HINTS, &app.keymap))
+    };
+
+    let footer = Paragraph::new(ascii_or(app, footer_text))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(fg(app.use_color, app.theme.border))
+        )
+        .style(fg(app.use_color, app.theme.muted));
+    f.render_widget(footer, chunks[2]);
+}
+
+/// `text` cut to `width` columns, ending in `…` if anything was cut.
+fn truncate_right(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let head: String = text.chars().take(width - 1).collect();
+    format!("{}…", head)
+}
+
+/// `text` cut to `width` columns from the left, so its end (say, a
+/// file name) stays visible.
+fn truncate_left(text: &str, width: usize) -> String {
+    let len = text.chars().count();
+    if len <= width {
+        return text.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let tail: String = text.chars().skip(len - (width - 1)).collect();
+    format!("…{}", tail)
+}
+
+/// The list view's rows, each with the script it selects. With more
+/// than one column there's no room for a selected script's whole
+/// description, so only its first line is shown.
+fn list_items(app: &App, columns: usize) -> Vec<(ListItem<'_>, Option<usize>)> {
+    let mut items = Vec::new();
+    let mut current_group: Option<&str> = None;
+    let mut script_rows = 0;
+
+    let indices = app.filtered_indices();
+    let number_width = app.numbered_indices().len().to_string().len();
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    if app.grouped() {
+        for &i in &indices {
+            *counts.entry(app.group_of(&app.scripts[i])).or_default() += 1;
+        }
+    }
+
+    for i in indices {
+        let script = &app.scripts[i];
+        let group = app.group_of(script);
+        let collapsed = app.is_collapsed(group);
+
+        // Add group header if group changed
+        if app.grouped() && current_group != Some(group) {
+            current_group = Some(group);
+
+            // A collapsed group's header stands in for its first script
+            let selected = collapsed && i == app.selected_index;
+            let marker = if collapsed { "▸" } else { "▾" };
+            let header = format!(
+                "{}── {} {} ({}) ──",
+                if selected { "▶ " } else { "" },
+                marker,
+                group,
+                counts.get(group).copied().unwrap_or(0)
             );
+            let style = if selected {
+                fg(app.use_color, app.theme.selection)
+            } else {
+                fg(app.use_color, app.theme.header)
+            };
+            items.push((
+                ListItem::new(ascii_or(app, header))
+                    .style(style.add_modifier(Modifier::BOLD)),
+                collapsed.then_some(i),
+            ));
+        }
+
+        if collapsed {
+            continue;
         }
 
         let prefix = if i == app.selected_index {
@@ -56,44 +412,461 @@ pub fn render_list_view(
             " "
         };
 
-        let name = format!("{} {}", prefix, script.name);
-
-        let lines = if let Some(d) = &script.description {
-            vec![name, format!("    {}", d)]
+        let marker = match (script.pinned, script.favorite) {
+            (true, true) => "📌 ★ ",
+            (true, false) => "📌 ",
+            (false, true) => "★ ",
+            (false, false) => "",
+        };
+        let checkbox = if app.marked.is_empty() {
+            ""
+        } else if app.marked.contains(&script.path) {
+            "[x] "
         } else {
-            vec![name]
+            "[ ] "
+        };
+        let name = format!(
+            "{} {:<width$} {}{}{}",
+            prefix,
+            script_rows + 1,
+            checkbox,
+            marker,
+            script.name,
+            width = number_width
+        );
+
+        let mut first_line = vec![Span::raw(ascii_or(app, name))];
+        if app.show_run_counts && script.run_count > 0 {
+            first_line.push(Span::styled(
+                ascii_or(app, format!(" ×{}", script.run_count)),
+                fg(app.use_color, app.theme.muted),
+            ));
+        }
+        if let Some(result) = app.last_results.get(&script.path) {
+            let color = if result.status == RunStatus::Success {
+                app.theme.success
+            } else {
+                app.theme.error
+            };
+            first_line.push(Span::styled(
+                ascii_or(app, format!(" {}", result.badge())),
+                fg(app.use_color, color),
+            ));
+        }
+
+        let mut lines = vec![Line::from(first_line)];
+        // The selected script shows its whole comment block
+        let description = match &script.description_full {
+            Some(full) if i == app.selected_index && columns == 1 => Some(full),
+            _ => script.description.as_ref(),
         };
+        // A compact list shows the description in the footer instead
+        if !app.compact {
+            if let Some(d) = description {
+                for line in d.lines() {
+                    lines.push(Line::raw(format!("    {}", line)));
+                }
+            } else if app.loading_metadata.contains(&script.path) {
+                lines.push(Line::raw(format!("    {}", LOADING_DESCRIPTION)));
+            }
+        }
 
         let style = if i == app.selected_index {
-            Style::default()
-                .fg(Color::Yellow)
+            fg(app.use_color, app.theme.selection)
                 .add_modifier(Modifier::BOLD)
+        } else if app.zebra_rows && app.use_color && script_rows % 2 == 1 {
+            fg(app.use_color, app.theme.text).bg(app.theme.stripe)
         } else {
-            Style::default().fg(Color::White)
+            fg(app.use_color, app.theme.text)
         };
+        script_rows += 1;
 
-        items.push(ListItem::new(lines.join("\n")).style(style));
+        items.push((ListItem::new(lines).style(style), Some(i)));
     }
 
-    let list = List::new(items)
+    items
+}
+
+/// Draws a scrollbar over the right border of `area`, with a thumb
+/// sized to the `visible` share of `total` rows.
+fn render_scrollbar(
+    f: &mut ratatui::Frame,
+    app: &App,
+    area: Rect,
+    start: usize,
+    total: usize,
+    visible: usize,
+    color: Color,
+) {
+    // One position per possible top row, so the thumb reaches the
+    // bottom when the last row is on screen
+    let mut state = ScrollbarState::new(total - visible + 1)
+        .position(start)
+        .viewport_content_length(visible);
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(None)
+        .end_symbol(None)
+        .style(fg(app.use_color, color));
+    f.render_stateful_widget(
+        scrollbar,
+        area.inner(&Margin { vertical: 1, horizontal: 0 }),
+        &mut state,
+    );
+}
+
+/// A `width` x `height` rect centered in `area`, clamped to fit.
+pub fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// Rows `text` takes when wrapped to `width` columns.
+fn wrapped_rows(text: &str, width: usize) -> usize {
+    // Word wrapping can take a row more than the characters need
+    text.lines()
+        .map(|line| match line.chars().count() {
+            len if len > width => len.div_ceil(width) + 1,
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Yes/no prompt drawn over the list before a run.
+pub fn render_confirm_view(
+    f: &mut ratatui::Frame,
+    app: &App,
+) {
+    let script = app.selected_script();
+    let name = script.map(|script| script.name.as_str()).unwrap_or("");
+
+    // The script's own warning comes first
+    let mut text = match script.and_then(|script| script.confirm_message.as_deref()) {
+        Some(message) => format!("⚠ {}\n\nRun {}?", message, name),
+        None => format!("Run {}?", name),
+    };
+    if !app.pending_args.is_empty() {
+        text.push_str(&format!("\nArgs: {}", app.pending_args));
+    }
+    text.push_str("\n\ny: Run | any other key: Cancel");
+
+    let width: u16 = 50;
+    let rows = wrapped_rows(&text, (width - 2) as usize);
+    let area = centered_rect(width, rows as u16 + 2, f.size());
+    let prompt = Paragraph::new(ascii_or(app, text))
+        .wrap(Wrap { trim: false })
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Available Scripts")
-                .border_style(Style::default().fg(Color::Cyan))
+                .title("Confirm")
+                .border_style(fg(app.use_color, app.theme.accent))
+        )
+        .style(fg(app.use_color, app.theme.text));
+
+    f.render_widget(Clear, area);
+    f.render_widget(prompt, area);
+}
+
+/// Prompt drawn over the output when a batch script fails.
+pub fn render_batch_prompt(
+    f: &mut ratatui::Frame,
+    app: &App,
+) {
+    let Some(batch) = &app.batch else {
+        return;
+    };
+
+    let text = format!(
+        "{} failed.\nRun the remaining {}?\n\ny: Continue | any other key: Stop",
+        batch.current,
+        batch.queue.len()
+    );
+
+    let area = centered_rect(50, 6, f.size());
+    let prompt = Paragraph::new(ascii_or(app, text))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Batch")
+                .border_style(fg(app.use_color, app.theme.error))
+        )
+        .style(fg(app.use_color, app.theme.text));
+
+    f.render_widget(Clear, area);
+    f.render_widget(prompt, area);
+}
+
+/// Prompt drawn over the output before a batch script with a
+/// `herring-confirm:` marker runs.
+pub fn render_batch_confirm(
+    f: &mut ratatui::Frame,
+    app: &App,
+) {
+    let Some(path) = app.batch.as_ref().and_then(|b| b.awaiting_confirm.as_deref()) else {
+        return;
+    };
+    let script = app.scripts.iter().find(|s| s.path == path);
+    let name = script.map_or(path, |s| s.name.as_str());
+    let message = script.and_then(|s| s.confirm_message.as_deref()).unwrap_or("");
+
+    let text = format!(
+        "⚠ {}\n\nRun {}?\n\ny: Run | any other key: Skip it",
+        message, name
+    );
+    let width: u16 = 50;
+    let rows = wrapped_rows(&text, (width - 2) as usize);
+    let area = centered_rect(width, rows as u16 + 2, f.size());
+    let prompt = Paragraph::new(ascii_or(app, text))
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Batch")
+                .border_style(fg(app.use_color, app.theme.accent))
+        )
+        .style(fg(app.use_color, app.theme.text));
+
+    f.render_widget(Clear, area);
+    f.render_widget(prompt, area);
+}
+
+/// The `Ctrl-P` palette drawn over the list: the query, then the
+/// ranked matches with the highlighted one marked.
+pub fn render_palette(
+    f: &mut ratatui::Frame,
+    app: &App,
+) {
+    let palette = &app.palette;
+    let matches = app.palette_matches();
+
+    let width: u16 = 70;
+    let inner_width = (width - 2) as usize;
+    let mut lines = vec![
+        Line::raw(format!("> {}_", palette.query)),
+        Line::raw(""),
+    ];
+    if matches.is_empty() {
+        let empty = if palette.query.trim().is_empty() {
+            "No recent or favorite scripts yet; type to search"
+        } else {
+            "No matches"
+        };
+        lines.push(Line::styled(empty, fg(app.use_color, app.theme.muted)));
+    }
+    for (row, &i) in matches.iter().enumerate() {
+        let script = &app.scripts[i];
+        let selected = row == palette.selected;
+        let mut text = format!(
+            "{} {}",
+            if selected { "▶" } else { " " },
+            script.name
         );
-    f.render_widget(list, chunks[1]);
+        if let Some(category) = &script.category {
+            text.push_str(&format!(" ({})", category));
+        }
+        if let Some(description) = &script.description {
+            text.push_str(&format!(" — {}", description));
+        }
+        let style = if selected {
+            fg(app.use_color, app.theme.selection).add_modifier(Modifier::BOLD)
+        } else {
+            fg(app.use_color, app.theme.text)
+        };
+        lines.push(Line::styled(ascii_or(app, truncate_right(&text, inner_width)), style));
+    }
 
-    let footer = Paragraph::new(
-        "↑/↓: Navigate | Enter: Run | ?: Help | q: Quit"
-    )
+    let area = centered_rect(width, lines.len() as u16 + 2, f.size());
+    let popup = Paragraph::new(lines)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan))
+                .title("Scripts (Enter: Select | Alt-Enter: Run | Esc: Close)")
+                .border_style(fg(app.use_color, app.theme.accent))
         )
-        .style(Style::default().fg(Color::Gray));
-    f.render_widget(footer, chunks[2]);
+        .style(fg(app.use_color, app.theme.text));
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+/// Prompt drawn over any view when quitting with a script running.
+pub fn render_quit_prompt(
+    f: &mut ratatui::Frame,
+    app: &App,
+) {
+    let name = app
+        .running
+        .as_ref()
+        .and_then(|r| Path::new(&r.path).file_name())
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let text = format!(
+        "{} is still running.\nKill it and quit?\n\ny: Kill and quit | any other key: Cancel",
+        name
+    );
+
+    let area = centered_rect(50, 6, f.size());
+    let prompt = Paragraph::new(ascii_or(app, text))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Quit")
+                .border_style(fg(app.use_color, app.theme.error))
+        )
+        .style(fg(app.use_color, app.theme.text));
+
+    f.render_widget(Clear, area);
+    f.render_widget(prompt, area);
+}
+
+/// A file size like `512 B`, `1.5 KiB` or `3.0 MiB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Permission bits like `rwxr-xr-x`.
+#[cfg(unix)]
+fn format_permissions(metadata: &fs::Metadata) -> String {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = metadata.permissions().mode();
+    (0..9)
+        .map(|bit| match mode & (0o400 >> bit) {
+            0 => '-',
+            _ => ['r', 'w', 'x'][bit % 3],
+        })
+        .collect()
+}
+
+#[cfg(not(unix))]
+fn format_permissions(metadata: &fs::Metadata) -> String {
+    if metadata.permissions().readonly() { "read-only" } else { "writable" }.to_string()
+}
+
+/// The selected script's file details and full description, drawn
+/// over the list with `i`.
+pub fn render_info_popup(
+    f: &mut ratatui::Frame,
+    app: &App,
+) {
+    let Some(script) = app.selected_script() else {
+        return;
+    };
+
+    let mut text = format!("Path:        {}\n", script.path);
+    text.push_str(&format!(
+        "Category:    {}\n",
+        script.category.as_deref().unwrap_or(UNCATEGORIZED_GROUP)
+    ));
+    if !script.tags.is_empty() {
+        text.push_str(&format!("Tags:        {}\n", script.tags.join(", ")));
+    }
+    text.push_str(&format!("Runs:        {}\n", script.run_count));
+    match fs::metadata(&script.path) {
+        Ok(metadata) => {
+            text.push_str(&format!("Size:        {}\n", format_size(metadata.len())));
+            if let Ok(modified) = metadata.modified() {
+                text.push_str(&format!(
+                    "Modified:    {} ago\n",
+                    format_age(modified, SystemTime::now())
+                ));
+            }
+            text.push_str(&format!("Permissions: {}\n", format_permissions(&metadata)));
+        }
+        Err(e) => text.push_str(&format!("⚠ {}\n", e)),
+    }
+    if let Some(description) = script.description_full.as_ref().or(script.description.as_ref()) {
+        text.push_str(&format!("\n{}\n", description));
+    }
+    text.push_str("\nPress any key to close");
+
+    let width: u16 = 70.min(f.size().width);
+    let rows = wrapped_rows(&text, width.saturating_sub(2).max(1) as usize);
+    let area = centered_rect(width, rows as u16 + 2, f.size());
+    let popup = Paragraph::new(ascii_or(app, text))
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(script.name.as_str())
+                .border_style(fg(app.use_color, app.theme.accent))
+        )
+        .style(fg(app.use_color, app.theme.text));
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+/// Lines of text that fit in the scrolling views (output, source and
+/// help): the frame minus the title and footer boxes and the content
+/// block's borders.
+pub fn scroll_view_height(area: Rect) -> usize {
+    area.height.saturating_sub(3 + 3 + 2) as usize
+}
+
+/// Gutter for line `index` (counting from 0), dimmed and right-aligned
+/// in `width` columns; `None` leaves it blank.
+fn line_number(app: &App, index: Option<usize>, width: usize) -> Span<'static> {
+    let label = match index {
+        Some(index) => format!("{:>1$} ", index + 1, width.saturating_sub(1)),
+        None => " ".repeat(width),
+    };
+    Span::styled(label, fg(app.use_color, app.theme.muted).add_modifier(Modifier::DIM))
+}
+
+/// `row` with every case-insensitive occurrence of `query` styled
+/// with `highlight`.
+fn highlight_matches<'a>(row: &'a str, query: &str, highlight: Style) -> Line<'a> {
+    if query.is_empty() {
+        return Line::raw(row);
+    }
+
+    let lower_row = row.to_lowercase();
+    let lower_query = query.to_lowercase();
+    // Lowercasing can change byte lengths outside ASCII; offsets into
+    // `lower_row` are then no good for `row`, so mark the whole row
+    if lower_row.len() != row.len() {
+        return if lower_row.contains(&lower_query) {
+            Line::styled(row, highlight)
+        } else {
+            Line::raw(row)
+        };
+    }
+
+    let mut spans = Vec::new();
+    let mut last = 0;
+    for (start, matched) in lower_row.match_indices(&lower_query) {
+        let end = start + matched.len();
+        if !row.is_char_boundary(start) || !row.is_char_boundary(end) {
+            continue;
+        }
+        spans.push(Span::raw(&row[last..start]));
+        spans.push(Span::styled(&row[start..end], highlight));
+        last = end;
+    }
+    spans.push(Span::raw(&row[last..]));
+    Line::from(spans)
+}
+
+/// Columns of text that fit in the scrolling views, inside the
+/// content block's borders.
+pub fn scroll_view_width(area: Rect) -> usize {
+    area.width.saturating_sub(2) as usize
 }
 
 pub fn render_output_view(
@@ -111,49 +884,192 @@ pub fn render_output_view(
         ])
         .split(size);
     
-    let is_success = app.output_text.starts_with("✓");
-    let color = if is_success {
-        Color::Green
-    } else if app.output_text.starts_with("✗") {
-        Color::Red
+    let status = match &app.batch {
+        Some(batch) if app.batch_finished() => {
+            Some(RunStatus::from_exit_code(if batch.failed == 0 { 0 } else { 1 }))
+        }
+        Some(_) => Some(RunStatus::Running),
+        None => app.last_status,
+    };
+    let color = match status {
+        Some(RunStatus::Success) => app.theme.success,
+        Some(RunStatus::Failure(_) | RunStatus::Signal(_)) => app.theme.error,
+        Some(RunStatus::Running) | None => app.theme.accent,
+    };
+    
+    let heading = match &app.batch {
+        Some(batch) => format!(
+            "Batch: {} of {} scripts run",
+            batch.finished(),
+            batch.total
+        ),
+        None => format!("Output: {}", selected_name(app)),
+    };
+    let heading = match app.spinner() {
+        Some(frame) => format!("{} {}", frame, heading),
+        None => heading,
+    };
+    let title = Paragraph::new(heading)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Script Output")
+                .border_style(fg(app.use_color, color))
+        );
+    f.render_widget(title, chunks[0]);
+    
+    let visible_height = scroll_view_height(size);
+    let gutter = if app.show_line_numbers {
+        gutter_width(&app.output_text)
     } else {
-        Color::Yellow
+        0
     };
+    let lines = numbered_rows(
+        &app.output_text,
+        scroll_view_width(size).saturating_sub(gutter),
+        app.wrap_output,
+    );
+    let total = lines.len();
+    
+    let start = app.output_scroll.min(total);
+    let end = (start + visible_height).min(total);
+    
+    let highlight = if app.use_color {
+        Style::default().bg(app.theme.accent).fg(Color::Black)
+    } else {
+        Style::default().add_modifier(Modifier::REVERSED)
+    };
+    let query = app.output_search.as_deref().unwrap_or("");
+    let text: Vec<Line> = (start..end)
+        .map(|i| {
+            let mut line = highlight_matches(lines[i].1, query, highlight);
+            if app.show_line_numbers {
+                // Only the first row of a wrapped line is numbered
+                let first = i == 0 || lines[i - 1].0 != lines[i].0;
+                line.spans.insert(0, line_number(app, first.then_some(lines[i].0), gutter));
+            }
+            line
+        })
+        .collect();
+    
+    let output = Paragraph::new(text)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(fg(app.use_color, color))
+        )
+        .style(fg(app.use_color, app.theme.text));
+    f.render_widget(output, chunks[1]);
+    if total > visible_height {
+        render_scrollbar(f, app, chunks[1], start, total, visible_height, color);
+    }
+    
+    let footer_text = if app.searching_output {
+        format!(
+            "Search: {}_ | {} matches | Enter: Find | Esc: Cancel",
+            query,
+            app.output_matches.len()
+        )
+    } else if app.is_running() {
+        format!(
+            "Running... | {} | {} lines so far",
+            hint_bar(RUNNING_HINTS, &app.keymap),
+            total
+        )
+    } else if total > visible_height {
+        format!(
+            "{} | {} | Lines {}-{} of {} | Other: Back",
+            hint_bar(SCROLL_HINTS, &app.keymap),
+            hint_bar(OUTPUT_HINTS, &app.keymap),
+            start + 1,
+            end,
+            total
+        )
+    } else {
+        format!("{} | Press any key to go back", hint_bar(OUTPUT_HINTS, &app.keymap))
+    };
+    let footer_text = match &app.status_message {
+        Some(message) => format!("{} | {}", message, footer_text),
+        None => footer_text,
+    };
+    let footer_text = match &app.output_search {
+        Some(query) if !app.searching_output => format!(
+            "/{}: {} matches, n/N: Next/Prev | {}",
+            query,
+            app.output_matches.len(),
+            footer_text
+        ),
+        _ => footer_text,
+    };
+    
+    let footer = Paragraph::new(ascii_or(app, footer_text))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(fg(app.use_color, color))
+        )
+        .style(fg(app.use_color, app.theme.muted));
+    f.render_widget(footer, chunks[2]);
+}
+
+pub fn render_source_view(
+    f: &mut ratatui::Frame,
+    app: &App,
+) {
+    let size = f.size();
+    
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(3),
+        ])
+        .split(size);
     
-    let script_name = &app.scripts[app.selected_index].name;
     let title = Paragraph::new(
-        format!("Output: {}", script_name)
+        format!("Source: {}", selected_name(app))
     )
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Script Output")
-                .border_style(Style::default().fg(color))
+                .title("Script Source")
+                .border_style(fg(app.use_color, app.theme.border))
         );
     f.render_widget(title, chunks[0]);
     
-    let visible_height = chunks[1].height as usize - 2;
-    let lines: Vec<&str> = app.output_text
+    let visible_height = scroll_view_height(size);
+    let lines: Vec<&str> = app.source_text
         .lines()
         .collect();
     let total = lines.len();
     
-    let start = app.output_scroll;
+    let start = app.source_scroll.min(total);
     let end = (start + visible_height).min(total);
-    let visible: Vec<&str> = lines[start..end].to_vec();
     
-    let output = Paragraph::new(visible.join("\n"))
+    let gutter = gutter_width(&app.source_text);
+    let text: Vec<Line> = (start..end)
+        .map(|i| {
+            if app.show_line_numbers {
+                Line::from(vec![line_number(app, Some(i), gutter), Span::raw(lines[i])])
+            } else {
+                Line::raw(lines[i])
+            }
+        })
+        .collect();
+    let source = Paragraph::new(text)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(color))
+                .border_style(fg(app.use_color, app.theme.border))
         )
-        .style(Style::default().fg(Color::White));
-    f.render_widget(output, chunks[1]);
+        .style(fg(app.use_color, app.theme.text));
+    f.render_widget(source, chunks[1]);
     
     let footer_text = if total > visible_height {
         format!(
-            "↑/↓: Scroll | Lines {}-{} of {} | Other: Back",
+            "{} | Lines {}-{} of {} | Other: Back",
+            hint_bar(SCROLL_HINTS, &app.keymap),
             start + 1,
             end,
             total
@@ -162,17 +1078,160 @@ pub fn render_output_view(
         "Press any key to go back".to_string()
     };
     
-    let footer = Paragraph::new(footer_text)
+    let footer = Paragraph::new(ascii_or(app, footer_text))
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(color))
+                .border_style(fg(app.use_color, app.theme.border))
         )
-        .style(Style::default().fg(Color::Gray));
+        .style(fg(app.use_color, app.theme.muted));
     f.render_widget(footer, chunks[2]);
 }
 
-pub fn render_help_view(f: &mut ratatui::Frame) {
+/// How long ago `time` was, e.g. `42s`, `5m`, `3h` or `2d`.
+pub fn format_age(time: SystemTime, now: SystemTime) -> String {
+    let secs = now.duration_since(time).map(|d| d.as_secs()).unwrap_or(0);
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3600 => format!("{}m", secs / 60),
+        3600..86400 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
+
+pub fn render_history_view(
+    f: &mut ratatui::Frame,
+    app: &App,
+) {
+    let size = f.size();
+    
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(3),
+        ])
+        .split(size);
+    
+    let title = Paragraph::new(
+        format!("{} recent runs", app.history.entries.len())
+    )
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("History")
+                .border_style(fg(app.use_color, app.theme.border))
+        );
+    f.render_widget(title, chunks[0]);
+    
+    let now = SystemTime::now();
+    let items: Vec<ListItem> = app.history.entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let name = app.scripts
+                .iter()
+                .find(|s| s.path == entry.path)
+                .map(|s| app.display_path(s))
+                .unwrap_or_else(|| format!("{} (missing)", entry.path));
+            let (mark, color) = if entry.status == RunStatus::Success {
+                ("✓", app.theme.success)
+            } else {
+                ("✗", app.theme.error)
+            };
+            let selected = i == app.history_index;
+            let text = format!(
+                "{} {} {}  {}  {} ago",
+                if selected { "▶" } else { " " },
+                mark,
+                name,
+                entry.status.outcome(),
+                format_age(entry.ran_at, now)
+            );
+            let style = if selected {
+                fg(app.use_color, app.theme.selection).add_modifier(Modifier::BOLD)
+            } else {
+                fg(app.use_color, color)
+            };
+            ListItem::new(ascii_or(app, text)).style(style)
+        })
+        .collect();
+    
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(fg(app.use_color, app.theme.border))
+        );
+    f.render_widget(list, chunks[1]);
+    
+    let footer = Paragraph::new(ascii_or(app, format!("{} | Other: Back", hint_bar(HISTORY_HINTS, &app.keymap))))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(fg(app.use_color, app.theme.border))
+        )
+        .style(fg(app.use_color, app.theme.muted));
+    f.render_widget(footer, chunks[2]);
+}
+
+pub fn render_warnings_view(
+    f: &mut ratatui::Frame,
+    app: &App,
+) {
+    let size = f.size();
+    
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(3),
+        ])
+        .split(size);
+    
+    let title = Paragraph::new(
+        format!("{} entries could not be read", app.scan_warnings.len())
+    )
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Scan Warnings")
+                .border_style(fg(app.use_color, app.theme.border))
+        );
+    f.render_widget(title, chunks[0]);
+    
+    let items: Vec<ListItem> = app.scan_warnings
+        .iter()
+        .map(|warning| {
+            ListItem::new(ascii_or(app, format!("⚠ {}", warning)))
+                .style(fg(app.use_color, app.theme.accent))
+        })
+        .collect();
+    
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(fg(app.use_color, app.theme.border))
+        );
+    f.render_widget(list, chunks[1]);
+    
+    let footer = Paragraph::new("Press any key to go back")
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(fg(app.use_color, app.theme.border))
+        )
+        .style(fg(app.use_color, app.theme.muted));
+    f.render_widget(footer, chunks[2]);
+}
+
+pub fn render_help_view(
+    f: &mut ratatui::Frame,
+    app: &App,
+) {
     let size = f.size();
     
     let chunks = Layout::default()
@@ -190,47 +1249,48 @@ pub fn render_help_view(f: &mut ratatui::Frame) {
                 .borders(Borders::ALL)
                 .title("Help")
                 .border_style(
-                    Style::default().fg(Color::Yellow)
+                    fg(app.use_color, app.theme.accent)
                 )
         );
     f.render_widget(title, chunks[0]);
     
-    let help_text = "\
-Script List View:
-  ↑/k         - Move selection up
-  ↓/j         - Move selection down
-  Enter       - Run selected script
-  ?           - Show this help
-  q/Esc       - Quit application
-
-Output View:
-  ↑/k         - Scroll up
-  ↓/j         - Scroll down
-  Any other   - Return to script list
-
-General:
-  All commands are case-sensitive
-  Navigation uses vim keys (j/k) or arrows";
+    let lines = help_lines();
+    let visible_height = scroll_view_height(size);
+    let total = lines.len();
+    let start = app.help_scroll.min(total);
+    let end = (start + visible_height).min(total);
     
-    let help = Paragraph::new(help_text)
+    let help = Paragraph::new(lines[start..end].join("\n"))
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_style(
-                    Style::default().fg(Color::Yellow)
+                    fg(app.use_color, app.theme.accent)
                 )
         )
-        .style(Style::default().fg(Color::White));
+        .style(fg(app.use_color, app.theme.text));
     f.render_widget(help, chunks[1]);
     
-    let footer = Paragraph::new("Press any key to close")
+    let footer_text = if total > visible_height {
+        format!(
+            "{} | Lines {}-{} of {} | Other: Close",
+            hint_bar(SCROLL_HINTS, &app.keymap),
+            start + 1,
+            end,
+            total
+        )
+    } else {
+        "Press any key to close".to_string()
+    };
+    
+    let footer = Paragraph::new(ascii_or(app, footer_text))
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_style(
-                    Style::default().fg(Color::Yellow)
+                    fg(app.use_color, app.theme.accent)
                 )
         )
-        .style(Style::default().fg(Color::Gray));
+        .style(fg(app.use_color, app.theme.muted));
     f.render_widget(footer, chunks[2]);
 }
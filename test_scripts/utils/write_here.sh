@@ -0,0 +1,4 @@
+#!/bin/bash
+# Write out.txt next to this script
+echo "written by $0" > ./out.txt
+echo "Wrote $(pwd)/out.txt"
@@ -1,16 +1,38 @@
 use std::fs::{self, File};
-use std::io::Write;
+use std::io::{self, Write};
 use std::os::unix::fs::PermissionsExt;
+use std::time::{Duration, SystemTime};
 
 use tempfile::TempDir;
-use rusty_herring::{App, Script, extract_description, scan_directory};
+use rusty_herring::config::Config;
+use rusty_herring::history::{History, HISTORY_LIMIT};
+use rusty_herring::ignore::IgnorePatterns;
+use rusty_herring::{
+    ACTIVE_POLL_INTERVAL, IDLE_POLL_INTERVAL, NUMBER_JUMP_TIMEOUT, SPINNER_FRAMES, App, AppMode,
+    Overlay, RunStatus, ScanError, ScanOptions, ScanWarning, Script, SortMode, TagFilter, extract_description,
+    extract_metadata, format_command, group_by_category, mark_favorites, mark_run_counts,
+    order_pinned_first, output_log_name, parse_duration, parse_extensions, pin_scripts,
+    read_source, scan_directories, scan_directory, scan_directory_report, scan_directory_with,
+    sort_scripts, split_args, working_dir,
+};
 
 fn make_script(name: &str, category: Option<&str>) -> Script {
     Script {
         path: format!("/tmp/{}", name),
+        relative_path: name.to_string(),
         name: name.to_string(),
         description: None,
+        description_full: None,
         category: category.map(String::from),
+        pinned: false,
+        favorite: false,
+        run_count: 0,
+        tags: Vec::new(),
+        interactive: false,
+        interpreter: None,
+        run_command: None,
+        confirm_message: None,
+        modified: None,
     }
 }
 
@@ -30,8 +52,8 @@ mod app_tests {
 
         assert_eq!(app.selected_index, 0);
         assert!(!app.should_quit);
-        assert!(!app.viewing_output);
-        assert!(!app.showing_help);
+        assert!(!app.viewing_output());
+        assert_eq!(app.mode, AppMode::List);
         assert!(app.output_text.is_empty());
         assert_eq!(app.output_scroll, 0);
     }
@@ -103,10 +125,10 @@ mod app_tests {
         let mut app = App::new(vec![]);
 
         app.show_help();
-        assert!(app.showing_help);
+        assert_eq!(app.mode, AppMode::Help);
 
         app.hide_help();
-        assert!(!app.showing_help);
+        assert_eq!(app.mode, AppMode::List);
     }
 
     #[test]
@@ -136,23 +158,198 @@ mod app_tests {
         assert_eq!(app.output_scroll, 0);
     }
 
+    #[test]
+    fn display_path_toggles_between_relative_and_absolute() {
+        let mut app = App::new(vec![make_script("a.sh", Some("utils"))]);
+        app.root = std::path::PathBuf::from("/srv/scripts");
+
+        assert_eq!(app.display_path(&app.scripts[0]), "a.sh");
+
+        app.toggle_absolute_paths();
+        assert_eq!(app.display_path(&app.scripts[0]), "/srv/scripts/a.sh");
+
+        app.toggle_absolute_paths();
+        assert!(!app.show_absolute_paths);
+    }
+
+    #[test]
+    fn title_defaults_and_expands_template() {
+        let mut app = App::new(vec![make_script("a.sh", None)]);
+        assert_eq!(app.title(), "Script Runner - 1 scripts in 0 categories");
+        app.scripts.push(make_script("b.sh", Some("db")));
+        app.scripts.push(make_script("c.sh", Some("web")));
+        assert_eq!(app.title(), "Script Runner - 3 scripts in 2 categories");
+
+        app.root = std::path::PathBuf::from("/srv/ops");
+        app.title_template = Some("Ops @ {dir} ({count})".to_string());
+        assert_eq!(app.title(), "Ops @ /srv/ops (3)");
+    }
+
+    #[test]
+    fn append_output_follows_until_scrolled_up() {
+        let mut app = App::new(vec![]);
+        app.viewport_height = 2;
+
+        app.append_output("1\n2\n3\n4\n5\n");
+        assert!(app.follow_output);
+        assert_eq!(app.output_scroll, 3);
+
+        app.scroll_output_up();
+        assert!(!app.follow_output);
+
+        app.append_output("6\n7\n");
+        assert_eq!(app.output_scroll, 2);
+
+        app.scroll_output_down(5);
+        app.scroll_output_down(5);
+        app.scroll_output_down(5);
+        assert!(app.follow_output);
+
+        app.append_output("8\n");
+        assert_eq!(app.output_scroll, 6);
+    }
+
     #[test]
     fn back_to_list_resets_state() {
         let mut app = App::new(vec![]);
-        app.viewing_output = true;
+        app.mode = AppMode::Output;
         app.output_text = "some output".to_string();
         app.output_scroll = 5;
+        app.follow_output = false;
 
         app.back_to_list();
 
-        assert!(!app.viewing_output);
+        assert!(!app.viewing_output());
         assert!(app.output_text.is_empty());
         assert_eq!(app.output_scroll, 0);
+        assert!(app.follow_output);
+    }
+
+
+    fn long_output(lines: usize) -> App {
+        let mut app = App::new(vec![make_script("a.sh", None)]);
+        app.output_text = (0..lines).map(|i| format!("line {}\n", i)).collect();
+        app.viewport_height = 10;
+        app
+    }
+
+    #[test]
+    fn jump_to_top_and_bottom() {
+        let mut app = long_output(35);
+
+        app.scroll_output_to_bottom(app.max_output_scroll());
+        assert_eq!(app.output_scroll, 25);
+        assert!(app.follow_output);
+
+        app.scroll_output_to_top();
+        assert_eq!(app.output_scroll, 0);
+        assert!(!app.follow_output);
+    }
+
+    #[test]
+    fn paging_moves_by_viewport_and_clamps() {
+        let mut app = long_output(35);
+        let max = app.max_output_scroll();
+
+        app.page_output_down(app.viewport_height, max);
+        assert_eq!(app.output_scroll, 10);
+        app.page_output_down(app.viewport_height, max);
+        app.page_output_down(app.viewport_height, max);
+        assert_eq!(app.output_scroll, 25);
+        assert!(app.follow_output);
+
+        app.page_output_up(app.viewport_height);
+        assert_eq!(app.output_scroll, 15);
+        assert!(!app.follow_output);
+        app.page_output_up(app.viewport_height);
+        app.page_output_up(app.viewport_height);
+        assert_eq!(app.output_scroll, 0);
+    }
+
+    #[test]
+    fn half_pages_move_by_half_the_viewport_and_clamp() {
+        let mut app = long_output(35);
+        app.follow_output = false;
+
+        app.half_page_down(app.viewport_height);
+        assert_eq!(app.output_scroll, 5);
+        for _ in 0..5 {
+            app.half_page_down(app.viewport_height);
+        }
+        assert_eq!(app.output_scroll, 25);
+        assert!(app.follow_output);
+
+        app.half_page_up(app.viewport_height);
+        assert_eq!(app.output_scroll, 20);
+        assert!(!app.follow_output);
+        for _ in 0..5 {
+            app.half_page_up(app.viewport_height);
+        }
+        assert_eq!(app.output_scroll, 0);
+    }
+
+    #[test]
+    fn half_page_moves_at_least_one_line() {
+        let mut app = long_output(35);
+
+        app.half_page_down(1);
+        assert_eq!(app.output_scroll, 1);
+        app.half_page_up(1);
+        assert_eq!(app.output_scroll, 0);
+    }
+
+
+    #[test]
+    fn script_serializes_to_json() {
+        let script = Script {
+            description: Some("Ship it".to_string()),
+            ..make_script("deploy.sh", Some("ops"))
+        };
+
+        let json: serde_json::Value = serde_json::to_value(&script).unwrap();
+
+        assert_eq!(json["path"], "/tmp/deploy.sh");
+        assert_eq!(json["name"], "deploy.sh");
+        assert_eq!(json["description"], "Ship it");
+        assert_eq!(json["category"], "ops");
+        assert!(json.get("modified").is_none());
+    }
+
+
+    #[test]
+    fn warnings_view_only_opens_when_there_are_warnings() {
+        let mut app = App::new(vec![make_script("a.sh", None)]);
+        app.show_warnings();
+        assert_ne!(app.overlay, Some(Overlay::Warnings));
+
+        app.scan_warnings.push(ScanWarning {
+            path: "/tmp/locked".to_string(),
+            message: "Permission denied".to_string(),
+        });
+        app.show_warnings();
+        assert_eq!(app.overlay, Some(Overlay::Warnings));
+        assert_eq!(app.scan_warnings[0].to_string(), "/tmp/locked: Permission denied");
+    }
+
+
+    #[test]
+    fn opening_an_overlay_replaces_the_open_one() {
+        let mut app = App::new(vec![make_script("a.sh", None)]);
+        app.show_history();
+        app.show_info();
+        assert_eq!(app.overlay, Some(Overlay::Info));
+
+        app.hide_history();
+        assert_eq!(app.overlay, Some(Overlay::Info));
+        app.hide_info();
+        assert_eq!(app.overlay, None);
     }
 }
 
 mod extract_description_tests {
     use super::*;
+    use rusty_herring::{COMMENT_PREFIXES, comment_prefixes};
+    use std::path::Path;
 
     #[test]
     fn extracts_hash_comment() {
@@ -235,6 +432,311 @@ mod extract_description_tests {
         let desc = extract_description(path.to_str().unwrap()).unwrap();
         assert_eq!(desc, Some("Actual description".to_string()));
     }
+
+    #[test]
+    fn pin_directive_is_not_a_description() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("script.sh");
+        let mut file = File::create(&path).unwrap();
+        writeln!(file, "#!/bin/bash").unwrap();
+        writeln!(file, "# @pin").unwrap();
+        writeln!(file, "# Deploy to production").unwrap();
+
+        let metadata = extract_metadata(path.to_str().unwrap()).unwrap();
+        assert!(metadata.pinned);
+        assert_eq!(metadata.description, Some("Deploy to production".to_string()));
+    }
+
+    #[test]
+    fn unpinned_by_default() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("script.sh");
+        let mut file = File::create(&path).unwrap();
+        writeln!(file, "# Just a script").unwrap();
+        writeln!(file, "echo hi").unwrap();
+        writeln!(file, "# @pin").unwrap();
+
+        let metadata = extract_metadata(path.to_str().unwrap()).unwrap();
+        assert!(!metadata.pinned);
+    }
+
+    #[test]
+    fn marker_after_code_lines_wins() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("script.sh");
+        let mut file = File::create(&path).unwrap();
+        writeln!(file, "#!/bin/bash").unwrap();
+        writeln!(file, "# Copyright 2024 Example Corp").unwrap();
+        writeln!(file, "set -euo pipefail").unwrap();
+        writeln!(file, "cd \"$(dirname \"$0\")\"").unwrap();
+        writeln!(file, "# herring-desc:   Rebuild the search index  ").unwrap();
+
+        let desc = extract_description(path.to_str().unwrap()).unwrap();
+        assert_eq!(desc, Some("Rebuild the search index".to_string()));
+    }
+
+    #[test]
+    fn marker_works_with_all_prefixes() {
+        let dir = TempDir::new().unwrap();
+        for (name, line) in [
+            ("a.js", "// herring-desc: Marked"),
+            ("a.sql", "-- herring-desc: Marked"),
+        ] {
+            let path = dir.path().join(name);
+            fs::write(&path, format!("// vim: set ft=x:\n{}\n", line)).unwrap();
+
+            let desc = extract_description(path.to_str().unwrap()).unwrap();
+            assert_eq!(desc, Some("Marked".to_string()));
+        }
+    }
+
+    #[test]
+    fn marker_beyond_scan_window_is_ignored() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("script.sh");
+        let mut body = String::from("# Header comment\n");
+        for _ in 0..25 {
+            body.push_str("echo filler\n");
+        }
+        body.push_str("# herring-desc: Too late\n");
+        fs::write(&path, body).unwrap();
+
+        let desc = extract_description(path.to_str().unwrap()).unwrap();
+        assert_eq!(desc, Some("Header comment".to_string()));
+    }
+
+    fn tags_in(name: &str, body: &str) -> Vec<String> {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(name);
+        fs::write(&path, body).unwrap();
+        extract_metadata(path.to_str().unwrap()).unwrap().tags
+    }
+
+    #[test]
+    fn reads_comma_separated_tags() {
+        let tags = tags_in("a.sh", "#!/bin/sh\n# Deploy it\n# herring-tags: deploy, db,safe\n");
+        assert_eq!(tags, vec!["deploy", "db", "safe"]);
+    }
+
+    #[test]
+    fn reads_space_separated_tags() {
+        let tags = tags_in("a.js", "// herring-tags: deploy  db\n// Real description\n");
+        assert_eq!(tags, vec!["deploy", "db"]);
+    }
+
+    #[test]
+    fn reads_tags_after_double_dash() {
+        let tags = tags_in("a.sql", "-- Migrate\nSELECT 1;\n-- herring-tags: db\n");
+        assert_eq!(tags, vec!["db"]);
+    }
+
+    #[test]
+    fn tags_marker_is_not_a_description() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("a.sh");
+        fs::write(&path, "# herring-tags: ops\n# Rotate logs\n").unwrap();
+
+        let desc = extract_description(path.to_str().unwrap()).unwrap();
+        assert_eq!(desc, Some("Rotate logs".to_string()));
+    }
+
+
+    #[test]
+    fn interactive_marker_is_read() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("a.sh");
+        fs::write(&path, "#!/bin/sh\n# Ask for a name\n# herring-interactive\nread name\n").unwrap();
+
+        let metadata = extract_metadata(path.to_str().unwrap()).unwrap();
+        assert!(metadata.interactive);
+        assert_eq!(metadata.description, Some("Ask for a name".to_string()));
+    }
+
+
+    fn full_description(body: &str) -> (Option<String>, Option<String>) {
+        full_description_in("script.sh", body)
+    }
+
+    fn full_description_in(name: &str, body: &str) -> (Option<String>, Option<String>) {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(name);
+        fs::write(&path, body).unwrap();
+        let metadata = extract_metadata(path.to_str().unwrap()).unwrap();
+        (metadata.description, metadata.description_full)
+    }
+
+    #[test]
+    fn collects_three_line_comment_block() {
+        let (description, full) = full_description(
+            "#!/bin/bash\n# Deploy the app\n# Builds a release first\n# then uploads it\necho hi\n",
+        );
+        assert_eq!(description, Some("Deploy the app".to_string()));
+        assert_eq!(
+            full,
+            Some("Deploy the app\nBuilds a release first\nthen uploads it".to_string())
+        );
+    }
+
+    #[test]
+    fn single_comment_has_no_full_description() {
+        let (_, full) = full_description("# Only one line\necho hi\n# Later comment\n");
+        assert_eq!(full, None);
+    }
+
+    #[test]
+    fn full_description_stops_at_blank_line() {
+        let (_, full) = full_description("# First\n# Second\n\n# Separate\n");
+        assert_eq!(full, Some("First\nSecond".to_string()));
+    }
+
+    #[test]
+    fn full_description_stops_at_other_prefix() {
+        // Without an extension every prefix counts as a comment
+        let (_, full) = full_description_in("script", "-- First\n-- Second\n# Other\n");
+        assert_eq!(full, Some("First\nSecond".to_string()));
+    }
+
+    #[test]
+    fn slashes_in_a_shell_script_are_not_a_description() {
+        let (description, _) = full_description("//server/share/tool --sync\n# Sync the share\n");
+        assert_eq!(description, None);
+
+        let (description, _) = full_description_in("task.js", "# not js\n// Build the bundle\n");
+        assert_eq!(description, None);
+    }
+
+    #[test]
+    fn double_dashes_only_comment_languages_that_use_them() {
+        let (description, _) = full_description_in("seed.sql", "-- Seed the dev db\n");
+        assert_eq!(description, Some("Seed the dev db".to_string()));
+
+        let (description, _) = full_description("-- Not a comment\n");
+        assert_eq!(description, None);
+    }
+
+    #[test]
+    fn comment_prefixes_follow_the_extension() {
+        assert_eq!(comment_prefixes(Path::new("a.sh")), &["#"]);
+        assert_eq!(comment_prefixes(Path::new("a.PY")), &["#"]);
+        assert_eq!(comment_prefixes(Path::new("a.rs")), &["//"]);
+        assert_eq!(comment_prefixes(Path::new("a.lua")), &["--"]);
+        assert_eq!(comment_prefixes(Path::new("a.unknown")), COMMENT_PREFIXES);
+        assert_eq!(comment_prefixes(Path::new("deploy")), COMMENT_PREFIXES);
+    }
+
+    #[test]
+    fn full_description_keeps_empty_comment_lines_inside_block() {
+        let (_, full) = full_description("# First\n#\n# Third\n#\n");
+        assert_eq!(full, Some("First\n\nThird".to_string()));
+    }
+
+    #[test]
+    fn marker_replaces_full_description() {
+        let (description, full) =
+            full_description("# First\n# Second\n# herring-desc: Chosen\n");
+        assert_eq!(description, Some("Chosen".to_string()));
+        assert_eq!(full, None);
+    }
+
+
+    #[test]
+    fn run_marker_is_read() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("job.py");
+        fs::write(&path, "# Nightly job\n# herring-run: /opt/venv/bin/python3 {}\nprint(1)\n").unwrap();
+
+        let metadata = extract_metadata(path.to_str().unwrap()).unwrap();
+        assert_eq!(metadata.run_command, Some("/opt/venv/bin/python3 {}".to_string()));
+        assert_eq!(metadata.description, Some("Nightly job".to_string()));
+    }
+
+    #[test]
+    fn no_run_marker_means_direct_execution() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("job.sh");
+        fs::write(&path, "# Nightly job\n# herring-run:\n").unwrap();
+
+        let metadata = extract_metadata(path.to_str().unwrap()).unwrap();
+        assert_eq!(metadata.run_command, None);
+    }
+
+
+    #[test]
+    fn confirm_marker_is_read() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("drop.sh");
+        fs::write(
+            &path,
+            "#!/bin/sh\n# Reset prod\n# herring-confirm:  This will drop the production database \n",
+        )
+        .unwrap();
+
+        let metadata = extract_metadata(path.to_str().unwrap()).unwrap();
+        assert_eq!(
+            metadata.confirm_message,
+            Some("This will drop the production database".to_string())
+        );
+        assert_eq!(metadata.description, Some("Reset prod".to_string()));
+    }
+
+    #[test]
+    fn blank_or_missing_confirm_marker_is_none() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("a.sh");
+        fs::write(&path, "# herring-confirm:\n").unwrap();
+        assert_eq!(extract_metadata(path.to_str().unwrap()).unwrap().confirm_message, None);
+
+        fs::write(&path, "# Nothing to confirm\n").unwrap();
+        assert_eq!(extract_metadata(path.to_str().unwrap()).unwrap().confirm_message, None);
+    }
+
+
+    #[test]
+    fn extracts_semicolon_percent_and_rem_comments() {
+        let dir = TempDir::new().unwrap();
+        for (name, line, expected) in [
+            ("settings.ini", "; Local overrides", "Local overrides"),
+            ("paper.tex", "% Build the paper", "Build the paper"),
+            ("setup.bat", "REM Install the tools", "Install the tools"),
+        ] {
+            let path = dir.path().join(name);
+            fs::write(&path, format!("{}\n", line)).unwrap();
+
+            let desc = extract_description(path.to_str().unwrap()).unwrap();
+            assert_eq!(desc, Some(expected.to_string()), "{}", name);
+        }
+    }
+
+    #[test]
+    fn rem_needs_a_word_boundary() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("note.bat");
+        fs::write(&path, "REMINDER to self\nREM Real description\n").unwrap();
+
+        let desc = extract_description(path.to_str().unwrap()).unwrap();
+        assert_eq!(desc, None);
+    }
+
+
+    #[test]
+    fn binary_files_have_no_description() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("tool");
+        fs::write(&path, b"# not a comment\x00\x7fELF\x02\x01\n# Garbage\n").unwrap();
+
+        let desc = extract_description(path.to_str().unwrap()).unwrap();
+        assert_eq!(desc, None);
+    }
+
+    #[test]
+    fn non_utf8_files_have_no_description() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("tool");
+        fs::write(&path, b"# Looks like text\n\xff\xfe\xfd\n").unwrap();
+
+        let desc = extract_description(path.to_str().unwrap()).unwrap();
+        assert_eq!(desc, None);
+    }
 }
 
 mod scan_directory_tests {
@@ -279,9 +781,26 @@ mod scan_directory_tests {
 
         assert_eq!(scripts.len(), 1);
         assert_eq!(scripts[0].name, "helper.sh");
+        assert_eq!(scripts[0].relative_path, "utils/helper.sh");
         assert_eq!(scripts[0].category, Some("utils".to_string()));
     }
 
+    #[test]
+    fn two_level_deep_script_keeps_the_whole_category_path() {
+        let dir = TempDir::new().unwrap();
+        let nested = dir.path().join("db").join("migrations");
+        fs::create_dir_all(&nested).unwrap();
+        let script_path = nested.join("run.sh");
+        File::create(&script_path).unwrap();
+        make_executable(&script_path);
+
+        let scripts = scan_directory(dir.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(scripts.len(), 1);
+        assert_eq!(scripts[0].category, Some("db/migrations".to_string()));
+        assert_eq!(scripts[0].relative_path, "db/migrations/run.sh");
+    }
+
     #[test]
     fn handles_mixed_root_and_subdirectory_scripts() {
         let dir = TempDir::new().unwrap();
@@ -331,4 +850,4630 @@ mod scan_directory_tests {
 
         assert!(scripts.is_empty());
     }
+
+    #[test]
+    fn filters_by_modification_age() {
+        let dir = TempDir::new().unwrap();
+
+        let fresh = dir.path().join("fresh.sh");
+        File::create(&fresh).unwrap();
+        make_executable(&fresh);
+
+        let stale = dir.path().join("stale.sh");
+        let file = File::create(&stale).unwrap();
+        let ten_days = Duration::from_secs(10 * 24 * 60 * 60);
+        file.set_modified(SystemTime::now() - ten_days).unwrap();
+        drop(file);
+        make_executable(&stale);
+
+        let path = dir.path().to_str().unwrap();
+
+        let recent = ScanOptions {
+            modified_within: parse_duration("7d"),
+            ..ScanOptions::default()
+        };
+        let scripts = scan_directory_with(path, &recent).unwrap();
+        assert_eq!(scripts.len(), 1);
+        assert_eq!(scripts[0].name, "fresh.sh");
+
+        let old = ScanOptions {
+            older_than: parse_duration("7d"),
+            ..ScanOptions::default()
+        };
+        let scripts = scan_directory_with(path, &old).unwrap();
+        assert_eq!(scripts.len(), 1);
+        assert_eq!(scripts[0].name, "stale.sh");
+    }
+
+    #[test]
+    fn scans_multiple_directories_grouped_by_source() {
+        let home = TempDir::new().unwrap();
+        let bin = home.path().join("bin");
+        let work = home.path().join("work");
+        fs::create_dir_all(bin.join("git")).unwrap();
+        fs::create_dir(&work).unwrap();
+
+        for path in [bin.join("top.sh"), bin.join("git/sync.sh"), work.join("build.sh")] {
+            File::create(&path).unwrap();
+            make_executable(&path);
+        }
+
+        let dirs = vec![
+            bin.to_str().unwrap().to_string(),
+            work.to_str().unwrap().to_string(),
+        ];
+        let scripts = scan_directories(&dirs).unwrap();
+
+        assert_eq!(scripts.len(), 3);
+        let category = |name: &str| {
+            scripts.iter().find(|s| s.name == name).unwrap().category.clone()
+        };
+        assert_eq!(category("top.sh"), Some("bin".to_string()));
+        assert_eq!(category("sync.sh"), Some("bin/git".to_string()));
+        assert_eq!(category("build.sh"), Some("work".to_string()));
+    }
+
+    #[test]
+    fn multiple_directories_dedup_symlinked_paths() {
+        let home = TempDir::new().unwrap();
+        let real = home.path().join("real");
+        fs::create_dir(&real).unwrap();
+        let script = real.join("tool.sh");
+        File::create(&script).unwrap();
+        make_executable(&script);
+        let link = home.path().join("link");
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+
+        let dirs = vec![
+            real.to_str().unwrap().to_string(),
+            link.to_str().unwrap().to_string(),
+        ];
+        let scripts = scan_directories(&dirs).unwrap();
+
+        assert_eq!(scripts.len(), 1);
+    }
+
+    #[test]
+    fn single_directory_keeps_plain_categories() {
+        let dir = TempDir::new().unwrap();
+        let script = dir.path().join("solo.sh");
+        File::create(&script).unwrap();
+        make_executable(&script);
+
+        let scripts = scan_directories(&[dir.path().to_str().unwrap().to_string()]).unwrap();
+
+        assert_eq!(scripts[0].category, None);
+    }
+
+
+    #[test]
+    fn herringignore_excludes_matching_paths() {
+        let dir = TempDir::new().unwrap();
+        let hooks = dir.path().join(".git/hooks");
+        fs::create_dir_all(&hooks).unwrap();
+        let hook = hooks.join("pre-commit");
+        File::create(&hook).unwrap();
+        make_executable(&hook);
+        let real = dir.path().join("deploy.sh");
+        File::create(&real).unwrap();
+        make_executable(&real);
+        fs::write(dir.path().join(".herringignore"), "# vcs\n.git/\n").unwrap();
+
+        let scripts = scan_directory(dir.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(scripts.len(), 1);
+        assert_eq!(scripts[0].name, "deploy.sh");
+    }
+
+
+    #[test]
+    fn self_referential_symlink_terminates() {
+        let dir = TempDir::new().unwrap();
+        let sub = dir.path().join("tools");
+        fs::create_dir(&sub).unwrap();
+        let script = sub.join("run.sh");
+        File::create(&script).unwrap();
+        make_executable(&script);
+        std::os::unix::fs::symlink(dir.path(), sub.join("loop")).unwrap();
+
+        let options = ScanOptions {
+            follow_symlinks: true,
+            ..ScanOptions::default()
+        };
+        let followed = scan_directory_with(dir.path().to_str().unwrap(), &options).unwrap();
+        let skipped = scan_directory(dir.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(followed.len(), 1);
+        assert_eq!(skipped.len(), 1);
+    }
+
+    #[test]
+    fn symlinked_directories_are_only_followed_on_request() {
+        let dir = TempDir::new().unwrap();
+        let elsewhere = TempDir::new().unwrap();
+        let script = elsewhere.path().join("remote.sh");
+        File::create(&script).unwrap();
+        make_executable(&script);
+        std::os::unix::fs::symlink(elsewhere.path(), dir.path().join("linked")).unwrap();
+
+        let options = ScanOptions {
+            follow_symlinks: true,
+            ..ScanOptions::default()
+        };
+
+        assert!(scan_directory(dir.path().to_str().unwrap()).unwrap().is_empty());
+        let followed = scan_directory_with(dir.path().to_str().unwrap(), &options).unwrap();
+        assert_eq!(followed[0].category, Some("linked".to_string()));
+    }
+
+    #[test]
+    fn symlinked_files_are_listed_and_broken_links_skipped() {
+        let dir = TempDir::new().unwrap();
+        let target = dir.path().join("real.sh");
+        File::create(&target).unwrap();
+        make_executable(&target);
+        std::os::unix::fs::symlink(&target, dir.path().join("alias.sh")).unwrap();
+        std::os::unix::fs::symlink(dir.path().join("missing"), dir.path().join("dangling")).unwrap();
+
+        let scripts = scan_directory(dir.path().to_str().unwrap()).unwrap();
+        let names: Vec<&str> = scripts.iter().map(|s| s.name.as_str()).collect();
+
+        assert_eq!(names, vec!["alias.sh", "real.sh"]);
+    }
+
+    #[test]
+    fn dedupe_keeps_the_shortest_path_to_each_script() {
+        let dir = TempDir::new().unwrap();
+        let tools = dir.path().join("tools");
+        fs::create_dir(&tools).unwrap();
+        let target = tools.join("deploy.sh");
+        File::create(&target).unwrap();
+        make_executable(&target);
+        std::os::unix::fs::symlink(&target, dir.path().join("d.sh")).unwrap();
+        let root = dir.path().to_str().unwrap();
+
+        let options = ScanOptions {
+            dedupe: true,
+            ..ScanOptions::default()
+        };
+        let deduped = scan_directory_with(root, &options).unwrap();
+
+        assert_eq!(scan_directory(root).unwrap().len(), 2);
+        assert_eq!(deduped.len(), 1);
+        assert_eq!(deduped[0].relative_path, "d.sh");
+    }
+
+
+    #[test]
+    fn rescan_picks_up_new_scripts_and_keeps_selection() {
+        let dir = TempDir::new().unwrap();
+        for name in ["b.sh", "c.sh"] {
+            let path = dir.path().join(name);
+            File::create(&path).unwrap();
+            make_executable(&path);
+        }
+        let root = dir.path().to_str().unwrap().to_string();
+        let mut app = App::new(scan_directory(&root).unwrap());
+        app.scan_dirs = vec![root];
+        app.selected_index = 1;
+        let selected = app.scripts[1].path.clone();
+        app.last_results.insert(
+            selected.clone(),
+            rusty_herring::RunResult { status: RunStatus::from_exit_code(0), duration: Duration::ZERO },
+        );
+
+        let added = dir.path().join("a.sh");
+        File::create(&added).unwrap();
+        make_executable(&added);
+        app.rescan();
+
+        assert_eq!(app.scripts.len(), 3);
+        assert_eq!(app.selected_script().unwrap().path, selected);
+        assert!(app.last_results.contains_key(&selected));
+        assert_eq!(app.status_message.as_deref(), Some("Rescanned: 3 scripts"));
+    }
+
+    #[test]
+    fn rescan_failure_is_reported_not_fatal() {
+        let dir = TempDir::new().unwrap();
+        let gone = dir.path().join("gone");
+        let mut app = App::new(vec![make_script("a.sh", None)]);
+        app.scan_dirs = vec![gone.to_str().unwrap().to_string()];
+
+        app.rescan();
+
+        assert_eq!(app.scripts.len(), 1);
+        assert!(app.status_message.unwrap().starts_with("✗ Rescan failed"));
+    }
+
+
+    #[test]
+    fn shebang_files_without_exec_bit_are_opt_in() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("plain.sh"), "#!/bin/sh\necho hi\n").unwrap();
+        fs::write(dir.path().join("notes.txt"), "just text\n").unwrap();
+        let exec = dir.path().join("exec.sh");
+        fs::write(&exec, "#!/bin/sh\n").unwrap();
+        make_executable(&exec);
+
+        let options = ScanOptions {
+            include_shebang: true,
+            ..ScanOptions::default()
+        };
+        let default = scan_directory(dir.path().to_str().unwrap()).unwrap();
+        let with_shebang = scan_directory_with(dir.path().to_str().unwrap(), &options).unwrap();
+
+        assert_eq!(default.len(), 1);
+        let names: Vec<_> = with_shebang.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["exec.sh", "plain.sh"]);
+        assert_eq!(with_shebang[0].interpreter, None);
+        assert_eq!(with_shebang[1].interpreter, Some("/bin/sh".to_string()));
+    }
+
+
+    #[test]
+    fn unreadable_subdirectory_is_a_warning_not_an_error() {
+        let dir = TempDir::new().unwrap();
+        let sibling = dir.path().join("ok.sh");
+        File::create(&sibling).unwrap();
+        make_executable(&sibling);
+        let locked = dir.path().join("locked");
+        fs::create_dir(&locked).unwrap();
+        let hidden = locked.join("hidden.sh");
+        File::create(&hidden).unwrap();
+        make_executable(&hidden);
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+
+        let report =
+            scan_directory_report(dir.path().to_str().unwrap(), &ScanOptions::default());
+        // Root can read the directory anyway, so only check for the
+        // warning when the permissions actually apply
+        let denied = fs::read_dir(&locked).is_err();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let report = report.unwrap();
+        assert!(report.scripts.iter().any(|s| s.name == "ok.sh"));
+        if denied {
+            assert_eq!(report.scripts.len(), 1);
+            assert_eq!(report.warnings.len(), 1);
+            assert!(report.warnings[0].path.ends_with("locked"));
+        }
+    }
+
+
+    #[test]
+    fn max_depth_skips_deeper_directories() {
+        let dir = TempDir::new().unwrap();
+        for relative in ["top.sh", "tools/build.sh", "tools/db/reset.sh", "tools/db/x/deep.sh"] {
+            let path = dir.path().join(relative);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            File::create(&path).unwrap();
+            make_executable(&path);
+        }
+        let scan = |max_depth| {
+            let options = ScanOptions { max_depth, ..ScanOptions::default() };
+            scan_directory_with(dir.path().to_str().unwrap(), &options)
+                .unwrap()
+                .into_iter()
+                .map(|s| s.relative_path)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(scan(Some(0)), ["top.sh"]);
+        assert_eq!(scan(Some(2)), ["tools/build.sh", "tools/db/reset.sh", "top.sh"]);
+        assert_eq!(scan(None).len(), 4);
+
+        let scripts = scan_directory(dir.path().to_str().unwrap()).unwrap();
+        let reset = scripts.iter().find(|s| s.name == "reset.sh").unwrap();
+        assert_eq!(reset.category.as_deref(), Some("tools/db"));
+    }
+
+
+    #[test]
+    fn missing_root_is_an_io_error_naming_it() {
+        let dir = TempDir::new().unwrap();
+        let missing = dir.path().join("nope");
+
+        let error = scan_directory(missing.to_str().unwrap()).unwrap_err();
+
+        assert!(matches!(&error, ScanError::Io { source, .. } if source.kind() == io::ErrorKind::NotFound));
+        assert_eq!(error.path(), missing);
+        assert!(error.to_string().starts_with(&format!("{}: ", missing.display())));
+        assert!(std::error::Error::source(&error).is_some());
+    }
+
+    #[test]
+    fn unreadable_root_is_permission_denied() {
+        let dir = TempDir::new().unwrap();
+        let locked = dir.path().join("locked");
+        fs::create_dir(&locked).unwrap();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+
+        let result = scan_directory(locked.to_str().unwrap());
+        let denied = fs::read_dir(&locked).is_err();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+
+        // Root reads the directory regardless
+        if denied {
+            let error = result.unwrap_err();
+            assert!(matches!(error, ScanError::PermissionDenied { .. }));
+            assert_eq!(error.to_string(), format!("{}: Permission denied", locked.display()));
+        }
+    }
+
+    #[test]
+    fn non_utf8_names_are_skipped_with_a_warning() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let dir = TempDir::new().unwrap();
+        let ok = dir.path().join("ok.sh");
+        File::create(&ok).unwrap();
+        make_executable(&ok);
+        let bad = dir.path().join(OsStr::from_bytes(b"caf\xe9.sh"));
+        File::create(&bad).unwrap();
+        make_executable(&bad);
+
+        let report =
+            scan_directory_report(dir.path().to_str().unwrap(), &ScanOptions::default()).unwrap();
+
+        assert_eq!(report.scripts.len(), 1);
+        assert_eq!(report.scripts[0].name, "ok.sh");
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].message, "Name is not valid UTF-8");
+        assert!(report.warnings[0].path.ends_with("caf\u{fffd}.sh"));
+    }
+
+
+    #[test]
+    fn extension_allowlist_drops_other_files() {
+        let dir = TempDir::new().unwrap();
+        for name in ["deploy.sh", "report.PY", "tool", "cache.pyc"] {
+            let path = dir.path().join(name);
+            File::create(&path).unwrap();
+            make_executable(&path);
+        }
+        let root = dir.path().to_str().unwrap();
+        let names = |options: &ScanOptions| -> Vec<String> {
+            scan_directory_with(root, options).unwrap().into_iter().map(|s| s.name).collect()
+        };
+
+        let allow = ScanOptions {
+            extensions: parse_extensions("sh, .py"),
+            ..ScanOptions::default()
+        };
+        assert_eq!(names(&allow), vec!["deploy.sh", "report.PY"]);
+
+        let with_bare = ScanOptions {
+            extensionless: Some(true),
+            ..allow
+        };
+        assert_eq!(names(&with_bare), vec!["deploy.sh", "report.PY", "tool"]);
+
+        let deny = ScanOptions {
+            excluded_extensions: vec!["pyc".to_string()],
+            ..ScanOptions::default()
+        };
+        assert_eq!(names(&deny), vec!["deploy.sh", "report.PY", "tool"]);
+
+        let no_bare = ScanOptions {
+            extensionless: Some(false),
+            ..deny
+        };
+        assert_eq!(names(&no_bare), vec!["deploy.sh", "report.PY"]);
+    }
+
+
+    #[test]
+    fn skips_executable_files_that_are_not_regular() {
+        let dir = TempDir::new().unwrap();
+        let fifo = dir.path().join("pipe.sh");
+        let made = std::process::Command::new("mkfifo").arg(&fifo).status();
+        if !made.is_ok_and(|status| status.success()) {
+            return;
+        }
+        fs::set_permissions(&fifo, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let scripts = scan_directory(dir.path().to_str().unwrap()).unwrap();
+
+        assert!(scripts.is_empty());
+    }
+
+
+    fn lazy_options() -> ScanOptions {
+        ScanOptions {
+            lazy_metadata: true,
+            ..ScanOptions::default()
+        }
+    }
+
+    fn write_described(dir: &TempDir, name: &str, body: &str) -> String {
+        let path = dir.path().join(name);
+        fs::write(&path, body).unwrap();
+        make_executable(&path);
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn lazy_scan_leaves_descriptions_for_later() {
+        let dir = TempDir::new().unwrap();
+        write_described(&dir, "a.sh", "#!/bin/sh\n# Does a\n");
+
+        let scripts = scan_directory_with(dir.path().to_str().unwrap(), &lazy_options()).unwrap();
+
+        assert_eq!(scripts[0].name, "a.sh");
+        assert_eq!(scripts[0].description, None);
+    }
+
+    #[test]
+    fn descriptions_arrive_from_the_background_reader() {
+        let dir = TempDir::new().unwrap();
+        write_described(&dir, "a.sh", "#!/bin/sh\n# Does a\n# herring-tags: x\n");
+        write_described(&dir, "b.sh", "#!/bin/sh\n# @pin\n# Does b\n");
+        let options = lazy_options();
+        let mut app = App::new(scan_directory_with(dir.path().to_str().unwrap(), &options).unwrap());
+        app.scan_options = options;
+
+        app.start_metadata_loader();
+        assert_eq!(app.loading_metadata.len(), 2);
+        for _ in 0..500 {
+            app.poll_metadata();
+            if app.metadata_updates.is_none() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        assert!(app.loading_metadata.is_empty());
+        assert!(app.dirty);
+        // b.sh's `@pin` moves it to the top, still selected
+        assert_eq!(app.scripts[0].name, "b.sh");
+        assert!(app.scripts[0].pinned);
+        assert_eq!(app.scripts[0].description, Some("Does b".to_string()));
+        assert_eq!(app.scripts[1].description, Some("Does a".to_string()));
+        assert_eq!(app.scripts[1].tags, vec!["x".to_string()]);
+        assert_eq!(app.selected_script().unwrap().name, "a.sh");
+    }
+
+    #[test]
+    fn a_pending_script_is_read_before_it_runs() {
+        let dir = TempDir::new().unwrap();
+        let path = write_described(&dir, "drop.sh", "#!/bin/sh\n# herring-confirm: Drops it\n");
+        let options = lazy_options();
+        let mut app = App::new(scan_directory_with(dir.path().to_str().unwrap(), &options).unwrap());
+        // Loading, but the reader has not got to it
+        app.loading_metadata.insert(path.clone());
+
+        app.ensure_metadata(&path);
+
+        assert!(app.loading_metadata.is_empty());
+        assert_eq!(app.scripts[0].confirm_message, Some("Drops it".to_string()));
+        assert!(app.needs_confirmation());
+    }
+
+
+    #[test]
+    fn herring_settings_files_are_not_listed() {
+        let dir = TempDir::new().unwrap();
+        for name in [".herring.env", ".herring.toml", ".herringignore", "herring.toml", "real.sh"] {
+            let path = dir.path().join(name);
+            fs::write(&path, "").unwrap();
+            make_executable(&path);
+        }
+
+        let scripts = scan_directory(dir.path().to_str().unwrap()).unwrap();
+
+        let names: Vec<&str> = scripts.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["real.sh"]);
+    }
+}
+
+mod parse_duration_tests {
+    use super::*;
+
+    #[test]
+    fn parses_units() {
+        assert_eq!(parse_duration("45"), Some(Duration::from_secs(45)));
+        assert_eq!(parse_duration("15m"), Some(Duration::from_secs(900)));
+        assert_eq!(parse_duration("2h"), Some(Duration::from_secs(7200)));
+        assert_eq!(parse_duration("7d"), Some(Duration::from_secs(604800)));
+        assert_eq!(parse_duration("1w"), Some(Duration::from_secs(604800)));
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("d"), None);
+        assert_eq!(parse_duration("7y"), None);
+    }
+}
+
+mod config_tests {
+    use super::*;
+    use rusty_herring::input::DefaultAction;
+    use rusty_herring::run::WorkingDir;
+
+    #[test]
+    fn missing_file_yields_defaults() {
+        let dir = TempDir::new().unwrap();
+        let config = Config::load_from(&dir.path().join("config.toml")).unwrap();
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn reads_title_template() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "title_template = \"{count} team scripts\"\n").unwrap();
+
+        let config = Config::load_from(&path).unwrap();
+        assert_eq!(config.title_template, Some("{count} team scripts".to_string()));
+    }
+
+    #[test]
+    fn invalid_file_is_an_error() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "title_template = [").unwrap();
+
+        assert!(Config::load_from(&path).is_err());
+    }
+
+    #[test]
+    fn local_config_takes_precedence() {
+        let global = Config {
+            title_template: Some("global".to_string()),
+            pinned: vec!["a.sh".to_string()],
+            require_confirmation: Some(true),
+            ..Config::default()
+        };
+        let local = Config {
+            title_template: Some("local".to_string()),
+            pinned: vec!["b.sh".to_string()],
+            require_confirmation: None,
+            ..Config::default()
+        };
+
+        let merged = global.merge(local);
+        assert_eq!(merged.title_template, Some("local".to_string()));
+        assert_eq!(merged.pinned, vec!["a.sh".to_string(), "b.sh".to_string()]);
+        assert_eq!(merged.require_confirmation, Some(true));
+    }
+
+    #[test]
+    fn missing_local_values_fall_back_to_global() {
+        let global = Config {
+            title_template: Some("global".to_string()),
+            ..Config::default()
+        };
+
+        let merged = global.merge(Config::default());
+        assert_eq!(merged.title_template, Some("global".to_string()));
+    }
+
+    #[test]
+    fn local_timeout_overrides_global() {
+        let global = Config {
+            timeout: Some("5m".to_string()),
+            ..Config::default()
+        };
+        let local = Config {
+            timeout: Some("30s".to_string()),
+            ..Config::default()
+        };
+
+        assert_eq!(global.merge(local).timeout, Some("30s".to_string()));
+    }
+
+
+    #[test]
+    fn parses_env_file() {
+        let vars = rusty_herring::config::parse_env(
+            "# deploy settings\nENV=staging\nexport TOKEN=\"abc 123\"\n\nbroken line\nQUOTED='x'\n",
+        );
+
+        assert_eq!(vars, vec![
+            ("ENV".to_string(), "staging".to_string()),
+            ("TOKEN".to_string(), "abc 123".to_string()),
+            ("QUOTED".to_string(), "x".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn missing_env_file_is_empty() {
+        let dir = TempDir::new().unwrap();
+        assert!(rusty_herring::config::load_env(dir.path()).is_empty());
+    }
+
+
+    #[test]
+    fn reads_batch_failure_policy() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "batch_on_failure = \"stop\"\n").unwrap();
+
+        let config = Config::load_from(&path).unwrap();
+        assert_eq!(config.batch_on_failure, Some(rusty_herring::batch::OnFailure::Stop));
+    }
+
+
+    #[test]
+    fn reads_scan_defaults() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "scan_dir = \"/srv/scripts\"\nmax_depth = 2\nfollow_symlinks = true\n",
+        )
+        .unwrap();
+
+        let config = Config::load_from(&path).unwrap();
+        assert_eq!(config.scan_dir(), Some("/srv/scripts".to_string()));
+        assert_eq!(config.max_depth, Some(2));
+        assert_eq!(config.follow_symlinks, Some(true));
+    }
+
+    #[test]
+    fn scan_dir_expands_home() {
+        let config = Config {
+            scan_dir: Some("~/scripts".to_string()),
+            ..Config::default()
+        };
+
+        if let Some(home) = std::env::var_os("HOME") {
+            let expected = std::path::PathBuf::from(home).join("scripts");
+            assert_eq!(config.scan_dir(), Some(expected.display().to_string()));
+        }
+    }
+
+    #[test]
+    fn local_scan_options_override_global_ones() {
+        let global = Config {
+            max_depth: Some(5),
+            follow_symlinks: Some(true),
+            ..Config::default()
+        };
+        let local = Config {
+            max_depth: Some(1),
+            ..Config::default()
+        };
+
+        let merged = global.merge(local);
+        assert_eq!(merged.max_depth, Some(1));
+        assert_eq!(merged.follow_symlinks, Some(true));
+    }
+
+
+    #[test]
+    fn reads_working_dir() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "working_dir = \"launch\"\n").unwrap();
+
+        let config = Config::load_from(&path).unwrap();
+        assert_eq!(config.working_dir, Some(WorkingDir::Launch));
+    }
+
+
+    #[test]
+    fn reads_keybindings_as_one_key_or_several() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "[keybindings]\nnext = \"h\"\nquit = [\"ctrl-q\", \"Q\"]\n").unwrap();
+
+        let config = Config::load_from(&path).unwrap();
+        assert_eq!(
+            config.keybindings.get("next"),
+            Some(&rusty_herring::keymap::KeyList::One("h".to_string()))
+        );
+        assert_eq!(
+            config.keybindings.get("quit"),
+            Some(&rusty_herring::keymap::KeyList::Many(vec![
+                "ctrl-q".to_string(),
+                "Q".to_string()
+            ]))
+        );
+    }
+
+
+    #[test]
+    fn local_config_can_turn_off_zebra_rows() {
+        let global = Config { zebra_rows: Some(true), ..Config::default() };
+        let local: Config = toml::from_str("zebra_rows = false\n").unwrap();
+
+        assert_eq!(global.merge(local).zebra_rows, Some(false));
+    }
+
+
+    #[test]
+    fn filter_smart_case_is_read_and_merged() {
+        let global = || -> Config { toml::from_str("filter_smart_case = true\n").unwrap() };
+        assert_eq!(global().filter_smart_case, Some(true));
+
+        let local: Config = toml::from_str("filter_smart_case = false\n").unwrap();
+        assert_eq!(global().merge(local).filter_smart_case, Some(false));
+        assert_eq!(global().merge(Config::default()).filter_smart_case, Some(true));
+    }
+
+    #[test]
+    fn local_theme_env_and_categories_are_merged_over_global() {
+        let global: Config = toml::from_str(
+            "collapsed_categories = [\"archive\"]\n\
+             [theme]\nborder = \"blue\"\naccent = \"red\"\n\
+             [env]\nSTAGE = \"dev\"\nREGION = \"eu\"\n",
+        )
+        .unwrap();
+        let local: Config = toml::from_str(
+            "default_action = \"dry-run\"\n\
+             collapsed_categories = [\"db/old\"]\n\
+             [theme]\nborder = \"green\"\n\
+             [env]\nSTAGE = \"prod\"\n",
+        )
+        .unwrap();
+
+        let merged = global.merge(local);
+        assert_eq!(merged.theme.border.as_deref(), Some("green"));
+        assert_eq!(merged.theme.accent.as_deref(), Some("red"));
+        assert_eq!(merged.default_action, Some(DefaultAction::DryRun));
+        assert_eq!(merged.collapsed_categories, ["archive", "db/old"]);
+        assert_eq!(merged.env["STAGE"], "prod");
+        assert_eq!(merged.env["REGION"], "eu");
+    }
+
+    #[test]
+    fn env_file_wins_over_the_env_table() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join(".herring.env"), "STAGE=local\n").unwrap();
+        let config: Config = toml::from_str("[env]\nSTAGE = \"prod\"\nREGION = \"eu\"\n").unwrap();
+
+        let env = config.run_env(dir.path());
+
+        assert_eq!(env.iter().rev().find(|(k, _)| k == "STAGE").unwrap().1, "local");
+        assert!(env.contains(&("REGION".to_string(), "eu".to_string())));
+    }
+}
+
+mod pin_tests {
+    use super::*;
+
+    #[test]
+    fn pinned_scripts_come_first_sorted_by_name() {
+        let mut scripts = vec![
+            make_script("c.sh", None),
+            make_script("z.sh", None),
+            make_script("a.sh", None),
+            make_script("m.sh", None),
+        ];
+        scripts[1].pinned = true;
+        scripts[3].pinned = true;
+
+        order_pinned_first(&mut scripts);
+
+        let names: Vec<&str> = scripts.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["m.sh", "z.sh", "c.sh", "a.sh"]);
+    }
+
+    #[test]
+    fn pins_by_name_or_relative_path() {
+        let mut scripts = vec![
+            make_script("deploy.sh", None),
+            make_script("reset.sh", Some("db")),
+            make_script("other.sh", None),
+        ];
+        scripts[1].relative_path = "db/reset.sh".to_string();
+
+        pin_scripts(
+            &mut scripts,
+            &["deploy.sh".to_string(), "db/reset.sh".to_string()],
+        );
+
+        assert!(scripts[0].pinned);
+        assert!(scripts[1].pinned);
+        assert!(!scripts[2].pinned);
+    }
+}
+
+mod color_tests {
+    use rusty_herring::ui::color_supported;
+
+    #[test]
+    fn colors_on_by_default() {
+        assert!(color_supported(None, Some("xterm-256color")));
+        assert!(color_supported(None, None));
+    }
+
+    #[test]
+    fn no_color_disables_colors() {
+        assert!(!color_supported(Some("1"), Some("xterm-256color")));
+    }
+
+    #[test]
+    fn empty_no_color_is_ignored() {
+        assert!(color_supported(Some(""), Some("xterm")));
+    }
+
+    #[test]
+    fn dumb_terminal_disables_colors() {
+        assert!(!color_supported(None, Some("dumb")));
+    }
+
+
+    #[test]
+    fn ascii_mode_spells_out_symbols() {
+        use rusty_herring::ui::ascii_glyphs;
+
+        assert_eq!(ascii_glyphs("✓ Script completed"), "[OK] Script completed");
+        assert_eq!(ascii_glyphs("[✗ code 2]"), "[[FAIL] code 2]");
+        assert_eq!(ascii_glyphs("▶ 1 📌 ★ a.sh"), "> 1 [pin] * a.sh");
+        assert_eq!(ascii_glyphs("plain"), "plain");
+    }
+
+    #[test]
+    fn no_color_asks_for_plain_output() {
+        use rusty_herring::ui::plain_requested;
+
+        assert!(plain_requested(Some("1")));
+        assert!(!plain_requested(Some("")));
+        assert!(!plain_requested(None));
+    }
+}
+
+mod input_tests {
+    use super::*;
+    use std::io;
+
+    use std::time::Instant;
+
+    use crossterm::event::{
+        KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    };
+    use ratatui::layout::Rect;
+    use rusty_herring::input::{
+        Action, DefaultAction, Runner, View, apply_action, current_view, dispatch, handle_key,
+        handle_mouse,
+    };
+
+    /// Records runs instead of spawning anything.
+    #[derive(Default)]
+    struct StubRunner {
+        runs: Vec<String>,
+        args: Vec<Vec<String>>,
+        fail: bool,
+    }
+
+    impl Runner for StubRunner {
+        fn run_selected(&mut self, app: &mut App) -> Result<(), io::Error> {
+            if self.fail {
+                return Err(io::Error::other("boom"));
+            }
+            self.runs.push(app.scripts[app.selected_index].name.clone());
+            self.args.push(split_args(&app.pending_args));
+            app.output_text = "✓ done".to_string();
+            app.mode = AppMode::Output;
+            Ok(())
+        }
+    }
+
+    fn press(app: &mut App, runner: &mut StubRunner, keys: &[KeyCode]) {
+        for key in keys {
+            handle_key(app, KeyEvent::from(*key), runner);
+        }
+    }
+
+    fn three_scripts() -> App {
+        App::new(vec![
+            make_script("a.sh", None),
+            make_script("b.sh", None),
+            make_script("c.sh", None),
+        ])
+    }
+
+    #[test]
+    fn navigation_keys_move_selection() {
+        let mut app = three_scripts();
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[
+            KeyCode::Char('j'),
+            KeyCode::Down,
+            KeyCode::Down,
+            KeyCode::Char('k'),
+        ]);
+
+        assert_eq!(app.selected_index, 1);
+        assert!(runner.runs.is_empty());
+    }
+
+    #[test]
+    fn enter_runs_selected_script_and_any_key_returns() {
+        let mut app = three_scripts();
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[KeyCode::Char('j'), KeyCode::Enter]);
+        assert_eq!(runner.runs, vec!["b.sh".to_string()]);
+        assert!(app.viewing_output());
+
+        press(&mut app, &mut runner, &[KeyCode::Char('x')]);
+        assert!(!app.viewing_output());
+        assert_eq!(app.selected_index, 1);
+    }
+
+    #[test]
+    fn run_errors_are_shown_in_output_view() {
+        let mut app = three_scripts();
+        let mut runner = StubRunner { fail: true, ..StubRunner::default() };
+
+        press(&mut app, &mut runner, &[KeyCode::Enter]);
+
+        assert!(app.viewing_output());
+        assert!(app.output_text.starts_with("✗ Error running script"));
+    }
+
+    #[test]
+    fn help_opens_and_any_key_closes_it() {
+        let mut app = three_scripts();
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[KeyCode::Char('?')]);
+        assert_eq!(app.mode, AppMode::Help);
+
+        press(&mut app, &mut runner, &[KeyCode::Char('q')]);
+        assert_eq!(app.mode, AppMode::List);
+        assert!(!app.should_quit);
+
+        press(&mut app, &mut runner, &[KeyCode::Char('q')]);
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn output_scroll_keys() {
+        let mut app = three_scripts();
+        let mut runner = StubRunner::default();
+        app.mode = AppMode::Output;
+        app.output_text = (0..30).map(|i| format!("line {}\n", i)).collect();
+
+        press(&mut app, &mut runner, &[KeyCode::Char('j'), KeyCode::Down]);
+        assert_eq!(app.output_scroll, 2);
+
+        press(&mut app, &mut runner, &[KeyCode::Up]);
+        assert_eq!(app.output_scroll, 1);
+        assert!(app.viewing_output());
+    }
+
+    #[test]
+    fn dispatch_maps_keys_per_view() {
+        assert_eq!(dispatch(View::List, KeyCode::Enter.into()), Some(Action::Run));
+        assert_eq!(dispatch(View::List, KeyCode::Char('j').into()), Some(Action::Next));
+        assert_eq!(dispatch(View::List, KeyCode::Char('q').into()), Some(Action::Quit));
+        assert_eq!(dispatch(View::List, KeyCode::Esc.into()), Some(Action::Cancel));
+        assert_eq!(dispatch(View::Filter, KeyCode::Char('q').into()), Some(Action::FilterChar('q')));
+        assert_eq!(dispatch(View::List, KeyCode::Char('z').into()), None);
+
+        assert_eq!(dispatch(View::Output, KeyCode::Char('j').into()), Some(Action::ScrollDown));
+        assert_eq!(dispatch(View::Output, KeyCode::Esc.into()), Some(Action::BackToList));
+
+        assert_eq!(dispatch(View::Help, KeyCode::Char('j').into()), Some(Action::ScrollDown));
+        assert_eq!(dispatch(View::Help, KeyCode::Esc.into()), Some(Action::HideHelp));
+    }
+
+    #[test]
+    fn transitions_between_views() {
+        let mut app = three_scripts();
+        let mut runner = StubRunner::default();
+        assert_eq!(current_view(&app), View::List);
+
+        apply_action(&mut app, Action::ShowHelp, &mut runner);
+        assert_eq!(current_view(&app), View::Help);
+
+        apply_action(&mut app, Action::HideHelp, &mut runner);
+        assert_eq!(current_view(&app), View::List);
+
+        apply_action(&mut app, Action::Run, &mut runner);
+        assert_eq!(current_view(&app), View::Output);
+
+        apply_action(&mut app, Action::BackToList, &mut runner);
+        assert_eq!(current_view(&app), View::List);
+    }
+
+    #[test]
+    fn run_with_no_scripts_does_nothing() {
+        let mut app = App::new(vec![]);
+        let mut runner = StubRunner::default();
+
+        apply_action(&mut app, Action::Run, &mut runner);
+
+        assert!(runner.runs.is_empty());
+        assert_eq!(current_view(&app), View::List);
+    }
+
+    #[test]
+    fn filter_typing_narrows_and_esc_restores() {
+        let mut app = three_scripts();
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[
+            KeyCode::Char('/'),
+            KeyCode::Char('b'),
+        ]);
+        assert_eq!(app.overlay, Some(Overlay::Filter));
+        assert_eq!(app.filtered_indices(), vec![1]);
+        assert_eq!(app.selected_index, 1);
+
+        press(&mut app, &mut runner, &[KeyCode::Enter, KeyCode::Enter]);
+        assert_eq!(runner.runs, vec!["b.sh".to_string()]);
+
+        press(&mut app, &mut runner, &[KeyCode::Char('x'), KeyCode::Esc]);
+        assert!(app.filter_query.is_empty());
+        assert!(!app.should_quit);
+        assert_eq!(app.filtered_indices(), vec![0, 1, 2]);
+
+        press(&mut app, &mut runner, &[KeyCode::Esc]);
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn args_prompt_runs_with_typed_arguments() {
+        let mut app = three_scripts();
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[KeyCode::Char('a')]);
+        assert_eq!(current_view(&app), View::Args);
+
+        for c in "--dry-run 'a b'".chars() {
+            press(&mut app, &mut runner, &[KeyCode::Char(c)]);
+        }
+        press(&mut app, &mut runner, &[KeyCode::Enter]);
+
+        assert_ne!(app.overlay, Some(Overlay::Args));
+        assert_eq!(runner.args, vec![vec!["--dry-run".to_string(), "a b".to_string()]]);
+
+        press(&mut app, &mut runner, &[KeyCode::Esc, KeyCode::Enter]);
+        assert_eq!(runner.args[1], Vec::<String>::new());
+    }
+
+    #[test]
+    fn esc_cancels_args_prompt() {
+        let mut app = three_scripts();
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[
+            KeyCode::Char('a'),
+            KeyCode::Char('x'),
+            KeyCode::Esc,
+        ]);
+
+        assert_ne!(app.overlay, Some(Overlay::Args));
+        assert!(app.pending_args.is_empty());
+        assert!(runner.runs.is_empty());
+        assert!(!app.should_quit);
+    }
+
+    #[test]
+    fn ctrl_c_and_x_cancel_in_output_view() {
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(dispatch(View::Output, ctrl_c), Some(Action::CancelRun));
+        assert_eq!(dispatch(View::Output, KeyCode::Char('x').into()), Some(Action::CancelRun));
+        assert_eq!(dispatch(View::Output, KeyCode::Char('c').into()), Some(Action::BackToList));
+    }
+
+    #[test]
+    fn confirmation_runs_only_on_y() {
+        let mut app = three_scripts();
+        app.require_confirmation = true;
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[KeyCode::Enter]);
+        assert_eq!(current_view(&app), View::Confirm);
+        assert!(runner.runs.is_empty());
+
+        press(&mut app, &mut runner, &[KeyCode::Char('y')]);
+        assert_ne!(app.overlay, Some(Overlay::Confirm));
+        assert_eq!(runner.runs, vec!["a.sh".to_string()]);
+    }
+
+    #[test]
+    fn confirmation_cancels_on_n_or_other_keys() {
+        let mut app = three_scripts();
+        app.require_confirmation = true;
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[KeyCode::Enter, KeyCode::Char('n')]);
+        assert_ne!(app.overlay, Some(Overlay::Confirm));
+
+        press(&mut app, &mut runner, &[KeyCode::Enter, KeyCode::Enter]);
+        assert_ne!(app.overlay, Some(Overlay::Confirm));
+
+        press(&mut app, &mut runner, &[KeyCode::Enter, KeyCode::Char('q')]);
+        assert_ne!(app.overlay, Some(Overlay::Confirm));
+        assert!(!app.should_quit);
+        assert!(runner.runs.is_empty());
+    }
+
+    #[test]
+    fn confirmation_keeps_typed_arguments() {
+        let mut app = three_scripts();
+        app.require_confirmation = true;
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[
+            KeyCode::Char('a'),
+            KeyCode::Char('-'),
+            KeyCode::Char('v'),
+            KeyCode::Enter,
+            KeyCode::Char('y'),
+        ]);
+
+        assert_eq!(runner.args, vec![vec!["-v".to_string()]]);
+    }
+
+    #[test]
+    fn v_previews_source_and_any_other_key_closes() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("a.sh");
+        let body: String = (1..=40).map(|i| format!("echo {}\n", i)).collect();
+        fs::write(&path, body).unwrap();
+        let mut script = make_script("a.sh", None);
+        script.path = path.to_str().unwrap().to_string();
+        let mut app = App::new(vec![script]);
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[KeyCode::Char('v')]);
+        assert_eq!(current_view(&app), View::Source);
+        assert!(app.source_text.starts_with("echo 1\n"));
+
+        press(&mut app, &mut runner, &[KeyCode::Down, KeyCode::Down, KeyCode::Up]);
+        assert_eq!(app.source_scroll, 1);
+        assert_eq!(app.output_scroll, 0);
+
+        press(&mut app, &mut runner, &[KeyCode::Enter]);
+        assert_eq!(current_view(&app), View::List);
+        assert!(runner.runs.is_empty());
+    }
+
+
+    #[test]
+    fn history_view_reruns_selected_entry() {
+        let mut app = three_scripts();
+        app.history.record("/tmp/a.sh", RunStatus::from_exit_code(0));
+        app.history.record("/tmp/c.sh", RunStatus::from_exit_code(1));
+        app.filter_query = "b".to_string();
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[KeyCode::Char('r')]);
+        assert_eq!(current_view(&app), View::History);
+
+        press(&mut app, &mut runner, &[KeyCode::Down, KeyCode::Down, KeyCode::Up, KeyCode::Enter]);
+
+        assert_eq!(runner.runs, vec!["c.sh".to_string()]);
+        assert!(app.filter_query.is_empty());
+        assert_ne!(app.overlay, Some(Overlay::History));
+    }
+
+    #[test]
+    fn history_rerun_clears_tag_and_failed_filters_hiding_the_script() {
+        let mut app = three_scripts();
+        app.history.record("/tmp/c.sh", RunStatus::from_exit_code(0));
+        app.tag_filter = Some(TagFilter::Untagged);
+        app.scripts[2].tags = vec!["db".to_string()];
+        app.failed_only = true;
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[KeyCode::Char('r'), KeyCode::Enter]);
+
+        assert_eq!(runner.runs, vec!["c.sh".to_string()]);
+        assert_eq!(app.tag_filter, None);
+        assert!(!app.failed_only);
+    }
+
+    #[test]
+    fn clearing_history_drops_the_failed_only_filter() {
+        let dir = TempDir::new().unwrap();
+        let history_path = dir.path().join("history");
+        let mut app = three_scripts();
+        app.history_path = Some(history_path.clone());
+        app.history.record("/tmp/b.sh", RunStatus::from_exit_code(1));
+        app.history.save_to(&history_path).unwrap();
+        app.last_results.insert(
+            "/tmp/b.sh".to_string(),
+            rusty_herring::RunResult { status: RunStatus::Failure(1), duration: Duration::ZERO },
+        );
+        app.failed_only = true;
+        assert_eq!(app.filtered_indices(), vec![1]);
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[KeyCode::Char('r'), KeyCode::Char('D')]);
+
+        assert!(!app.failed_only);
+        assert!(app.last_results.is_empty());
+        assert!(app.history.entries.is_empty());
+        assert!(History::load_from(&history_path).unwrap().entries.is_empty());
+        assert_eq!(app.filtered_indices(), vec![0, 1, 2]);
+    }
+
+
+    #[test]
+    fn help_scrolls_with_j_k_and_closes_on_other_keys() {
+        let mut app = three_scripts();
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[
+            KeyCode::Char('?'),
+            KeyCode::Char('j'),
+            KeyCode::Down,
+            KeyCode::Char('k'),
+        ]);
+        assert_eq!(current_view(&app), View::Help);
+        assert_eq!(app.help_scroll, 1);
+
+        press(&mut app, &mut runner, &[KeyCode::Char('x')]);
+        assert_eq!(current_view(&app), View::List);
+    }
+
+    #[test]
+    fn help_lines_cover_every_section() {
+        let lines = rusty_herring::input::help_lines();
+
+        for (section, bindings) in rusty_herring::input::KEY_HELP {
+            assert!(lines.contains(&format!("{}:", section)));
+            assert!(bindings.iter().all(|(keys, _)| {
+                lines.iter().any(|line| line.trim_start().starts_with(keys))
+            }));
+        }
+    }
+
+
+    #[test]
+    fn output_search_finds_and_cycles_matches() {
+        let mut app = three_scripts();
+        let mut runner = StubRunner::default();
+        app.mode = AppMode::Output;
+        app.viewport_height = 10;
+        app.output_text = (0..30)
+            .map(|i| if i % 10 == 3 { format!("ERROR {}\n", i) } else { format!("ok {}\n", i) })
+            .collect();
+
+        press(&mut app, &mut runner, &[
+            KeyCode::Char('/'),
+            KeyCode::Char('e'),
+            KeyCode::Char('r'),
+            KeyCode::Char('r'),
+        ]);
+        assert_eq!(current_view(&app), View::OutputSearch);
+        assert_eq!(app.output_matches, vec![3, 13, 23]);
+
+        press(&mut app, &mut runner, &[KeyCode::Enter]);
+        assert_eq!(current_view(&app), View::Output);
+        assert_eq!(app.output_scroll, 3);
+
+        press(&mut app, &mut runner, &[KeyCode::Char('n')]);
+        assert_eq!(app.output_scroll, 13);
+        press(&mut app, &mut runner, &[KeyCode::Char('n')]);
+        // Clamped to the last full page
+        assert_eq!(app.output_scroll, 20);
+        press(&mut app, &mut runner, &[KeyCode::Char('N')]);
+        assert_eq!(app.output_scroll, 13);
+    }
+
+    #[test]
+    fn output_search_tracks_new_output() {
+        let mut app = three_scripts();
+        app.mode = AppMode::Output;
+        app.start_output_search();
+        app.push_search_char('x');
+        app.confirm_output_search();
+        assert!(app.output_matches.is_empty());
+
+        app.append_output("a\nfox\n");
+
+        assert_eq!(app.output_matches, vec![1]);
+    }
+
+
+    fn click(app: &mut App, runner: &mut StubRunner, row: u16) {
+        let mouse = MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: 5,
+            row,
+            modifiers: KeyModifiers::NONE,
+        };
+        handle_mouse(app, mouse, runner);
+    }
+
+    #[test]
+    fn clicking_a_row_selects_and_double_click_runs() {
+        let mut app = three_scripts();
+        app.list_area = Rect::new(1, 4, 40, 10);
+        let mut runner = StubRunner::default();
+
+        // Row 4 is the group header, then one row per script
+        click(&mut app, &mut runner, 7);
+        assert_eq!(app.selected_index, 2);
+        assert!(runner.runs.is_empty());
+
+        click(&mut app, &mut runner, 7);
+        assert_eq!(runner.runs, vec!["c.sh"]);
+    }
+
+    #[test]
+    fn clicks_outside_the_list_or_on_headers_are_ignored() {
+        let mut app = three_scripts();
+        app.list_area = Rect::new(1, 4, 40, 10);
+        let mut runner = StubRunner::default();
+        app.selected_index = 1;
+
+        click(&mut app, &mut runner, 4);
+        click(&mut app, &mut runner, 2);
+        click(&mut app, &mut runner, 12);
+
+        assert_eq!(app.selected_index, 1);
+    }
+
+    #[test]
+    fn wheel_scrolls_the_output() {
+        let mut app = three_scripts();
+        app.mode = AppMode::Output;
+        app.output_text = (0..100).map(|i| format!("line {}\n", i)).collect();
+        app.follow_output = false;
+        let mut runner = StubRunner::default();
+
+        let wheel = MouseEvent {
+            kind: MouseEventKind::ScrollDown,
+            column: 0,
+            row: 0,
+            modifiers: KeyModifiers::NONE,
+        };
+        handle_mouse(&mut app, wheel, &mut runner);
+
+        assert_eq!(app.output_scroll, 1);
+    }
+
+    #[test]
+    fn double_click_needs_the_same_script_in_time() {
+        let mut app = three_scripts();
+        let start = Instant::now();
+
+        assert!(!app.click_script(0, start));
+        assert!(!app.click_script(1, start + Duration::from_millis(100)));
+        assert!(!app.click_script(1, start + Duration::from_secs(2)));
+        assert!(app.click_script(1, start + Duration::from_millis(2300)));
+        assert!(!app.click_script(1, start + Duration::from_millis(2400)));
+    }
+
+
+    #[test]
+    fn d_previews_the_command_without_running() {
+        let mut app = App::new(vec![Script {
+            interpreter: Some("/bin/sh -e".to_string()),
+            ..make_script("wipe.sh", None)
+        }]);
+        app.env = vec![("STAGE".to_string(), "prod".to_string())];
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[KeyCode::Char('d')]);
+
+        assert!(runner.runs.is_empty());
+        assert!(app.running.is_none());
+        assert_eq!(current_view(&app), View::Output);
+        assert!(app.output_text.starts_with("◌ Dry run"));
+        assert!(app.output_text.contains("Command: /bin/sh -e /tmp/wipe.sh\n"));
+        assert!(app.output_text.contains("Working directory: "));
+        assert!(app.output_text.contains("  STAGE=prod\n"));
+    }
+
+
+    #[test]
+    fn alt_letter_jumps_to_the_next_name_with_it() {
+        let mut app = App::new(vec![
+            make_script("build.sh", None),
+            make_script("Bump.sh", None),
+            make_script("clean.sh", None),
+            make_script("bench.sh", None),
+        ]);
+        let mut runner = StubRunner::default();
+        let alt = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::ALT);
+
+        handle_key(&mut app, alt('b'), &mut runner);
+        assert_eq!(app.selected_index, 1);
+        handle_key(&mut app, alt('b'), &mut runner);
+        assert_eq!(app.selected_index, 3);
+        // Wraps around to the top
+        handle_key(&mut app, alt('B'), &mut runner);
+        assert_eq!(app.selected_index, 0);
+
+        handle_key(&mut app, alt('z'), &mut runner);
+        assert_eq!(app.selected_index, 0);
+        assert!(runner.runs.is_empty());
+        assert!(!app.should_quit);
+    }
+
+    #[test]
+    fn letter_jumps_skip_filtered_out_scripts() {
+        let mut app = App::new(vec![
+            make_script("deploy.sh", None),
+            make_script("db-backup.sh", None),
+            make_script("dump.sh", None),
+        ]);
+        app.filter_query = "p".to_string();
+
+        app.jump_to_letter('d');
+        assert_eq!(app.selected_index, 1);
+        app.jump_to_letter('d');
+        assert_eq!(app.selected_index, 2);
+        app.jump_to_letter('d');
+        assert_eq!(app.selected_index, 0);
+
+        app.filter_query = "u".to_string();
+        app.selected_index = 2;
+        app.jump_to_letter('d');
+        assert_eq!(app.selected_index, 1);
+    }
+
+
+    #[test]
+    fn dot_reruns_the_last_script_with_its_args() {
+        let mut app = three_scripts();
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[KeyCode::Down, KeyCode::Char('a')]);
+        press(&mut app, &mut runner, &[KeyCode::Char('-'), KeyCode::Char('v'), KeyCode::Enter]);
+        assert_eq!(current_view(&app), View::Output);
+        app.output_scroll = 3;
+        press(&mut app, &mut runner, &[KeyCode::Char('.')]);
+
+        assert_eq!(runner.runs, vec!["b.sh", "b.sh"]);
+        assert_eq!(runner.args, vec![vec!["-v"], vec!["-v"]]);
+        assert_eq!(current_view(&app), View::Output);
+    }
+
+    #[test]
+    fn dot_does_nothing_before_the_first_run() {
+        let mut app = three_scripts();
+        app.mode = AppMode::Output;
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[KeyCode::Char('.')]);
+
+        assert!(runner.runs.is_empty());
+        assert!(app.viewing_output());
+    }
+
+
+    #[test]
+    fn e_queues_the_selected_script_for_the_editor() {
+        let mut app = three_scripts();
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[KeyCode::Down, KeyCode::Char('e')]);
+
+        assert_eq!(app.edit_request, Some(app.scripts[1].path.clone()));
+        assert!(runner.runs.is_empty());
+    }
+
+
+    #[test]
+    fn typed_row_number_jumps_on_enter_without_running() {
+        let mut app = three_scripts();
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[KeyCode::Char('3')]);
+        assert_eq!(app.jump_number, "3");
+        assert_eq!(app.selected_index, 0);
+
+        press(&mut app, &mut runner, &[KeyCode::Enter]);
+        assert_eq!(app.selected_index, 2);
+        assert!(app.jump_number.is_empty());
+        assert!(runner.runs.is_empty());
+    }
+
+    #[test]
+    fn row_numbers_are_clamped_to_the_list() {
+        let mut app = three_scripts();
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[KeyCode::Char('4'), KeyCode::Char('2'), KeyCode::Enter]);
+        assert_eq!(app.selected_index, 2);
+
+        press(&mut app, &mut runner, &[KeyCode::Char('0'), KeyCode::Enter]);
+        assert_eq!(app.selected_index, 0);
+    }
+
+    #[test]
+    fn other_keys_drop_the_typed_number() {
+        let mut app = three_scripts();
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[KeyCode::Char('3'), KeyCode::Char('j')]);
+
+        assert!(app.jump_number.is_empty());
+        assert_eq!(app.selected_index, 1);
+    }
+
+    #[test]
+    fn typed_number_jumps_after_a_pause() {
+        let mut app = three_scripts();
+        let start = Instant::now();
+        app.push_jump_digit('2', start);
+
+        assert!(!app.expire_number_jump(start + NUMBER_JUMP_TIMEOUT / 2));
+        assert_eq!(app.selected_index, 0);
+
+        assert!(app.expire_number_jump(start + NUMBER_JUMP_TIMEOUT));
+        assert_eq!(app.selected_index, 1);
+        assert!(app.jump_number.is_empty());
+    }
+
+    #[test]
+    fn row_numbers_skip_filtered_and_collapsed_scripts() {
+        let mut app = App::new(vec![
+            make_script("a.sh", Some("db")),
+            make_script("b.sh", Some("db")),
+            make_script("c.sh", Some("web")),
+            make_script("d.sh", Some("web")),
+        ]);
+        app.collapsed.insert("db".to_string());
+
+        assert_eq!(app.numbered_indices(), vec![2, 3]);
+        app.push_jump_digit('2', Instant::now());
+        app.finish_number_jump();
+        assert_eq!(app.selected_index, 3);
+    }
+
+
+    fn six_scripts_wide() -> App {
+        let mut app = App::new(
+            ["a.sh", "b.sh", "c.sh", "d.sh", "e.sh", "f.sh"]
+                .iter()
+                .map(|name| make_script(name, Some("ops")))
+                .collect(),
+        );
+        // Header and three scripts on the left, three on the right
+        app.list_area = Rect::new(1, 4, 160, 20);
+        app
+    }
+
+    #[test]
+    fn left_and_right_move_between_columns_on_wide_lists() {
+        let mut app = six_scripts_wide();
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[KeyCode::Right]);
+        assert_eq!(app.selected_index, 4);
+
+        press(&mut app, &mut runner, &[KeyCode::Char('l')]);
+        assert_eq!(app.selected_index, 4, "no column past the right edge");
+
+        press(&mut app, &mut runner, &[KeyCode::Down, KeyCode::Left]);
+        assert_eq!(app.selected_index, 1);
+
+        press(&mut app, &mut runner, &[KeyCode::Char('h')]);
+        assert_eq!(app.selected_index, 1, "no column past the left edge");
+    }
+
+    #[test]
+    fn down_runs_on_into_the_next_column() {
+        let mut app = six_scripts_wide();
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[KeyCode::Down, KeyCode::Down, KeyCode::Down]);
+
+        assert_eq!(app.selected_index, 3);
+        // d.sh tops the right column, level with the header on the left
+        press(&mut app, &mut runner, &[KeyCode::Left]);
+        assert_eq!(app.selected_index, 0);
+    }
+
+    #[test]
+    fn narrow_lists_stay_in_one_column() {
+        let mut app = six_scripts_wide();
+        app.list_area = Rect::new(1, 4, 80, 20);
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[KeyCode::Right]);
+
+        assert_eq!(app.selected_index, 0);
+    }
+
+    #[test]
+    fn clicking_the_right_column_selects_its_script() {
+        let mut app = six_scripts_wide();
+        let mut runner = StubRunner::default();
+        let mouse = MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: 100,
+            row: 6,
+            modifiers: KeyModifiers::NONE,
+        };
+
+        handle_mouse(&mut app, mouse, &mut runner);
+
+        assert_eq!(app.selected_index, 5);
+    }
+
+
+    #[test]
+    fn confirm_marker_asks_even_without_the_global_setting() {
+        let mut app = three_scripts();
+        app.scripts[1].confirm_message = Some("Drops the production database".to_string());
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[KeyCode::Enter]);
+        assert_eq!(runner.runs, vec!["a.sh".to_string()]);
+
+        press(&mut app, &mut runner, &[KeyCode::Char('x'), KeyCode::Down, KeyCode::Enter]);
+        assert_eq!(current_view(&app), View::Confirm);
+        assert_eq!(runner.runs.len(), 1);
+
+        press(&mut app, &mut runner, &[KeyCode::Char('y')]);
+        assert_eq!(runner.runs, vec!["a.sh".to_string(), "b.sh".to_string()]);
+    }
+
+
+    #[test]
+    fn command_line_has_the_directory_env_and_args() {
+        let mut app = App::new(vec![Script {
+            interpreter: Some("/bin/sh -e".to_string()),
+            ..make_script("wipe.sh", None)
+        }]);
+        app.env = vec![("STAGE".to_string(), "prod east".to_string())];
+        app.pending_args = "--force 'a b'".to_string();
+
+        assert_eq!(
+            app.command_line().unwrap(),
+            "cd /tmp && STAGE='prod east' /bin/sh -e /tmp/wipe.sh --force 'a b'"
+        );
+
+        app.working_dir = rusty_herring::run::WorkingDir::Launch;
+        app.env.clear();
+        app.pending_args.clear();
+        assert_eq!(app.command_line().unwrap(), "/bin/sh -e /tmp/wipe.sh");
+    }
+
+    #[cfg(not(feature = "clipboard"))]
+    #[test]
+    fn y_reports_the_clipboard_is_unavailable_without_the_feature() {
+        let mut app = three_scripts();
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[KeyCode::Char('y')]);
+
+        assert!(runner.runs.is_empty());
+        assert_eq!(current_view(&app), View::List);
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("✗ Clipboard unavailable (built without the clipboard feature)")
+        );
+    }
+
+
+    #[test]
+    fn shift_f_toggles_the_failed_filter_and_esc_clears_it() {
+        let mut app = three_scripts();
+        app.last_results.insert(
+            "/tmp/b.sh".to_string(),
+            rusty_herring::RunResult { status: RunStatus::from_exit_code(1), duration: Duration::ZERO },
+        );
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[KeyCode::Char('F')]);
+        assert!(app.failed_only);
+        assert_eq!(app.selected_script().unwrap().name, "b.sh");
+
+        press(&mut app, &mut runner, &[KeyCode::Esc]);
+        assert!(!app.failed_only);
+        assert!(!app.should_quit);
+    }
+
+
+    #[test]
+    fn c_toggles_compact_mode() {
+        let mut app = three_scripts();
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[KeyCode::Char('c')]);
+        assert!(app.compact);
+        press(&mut app, &mut runner, &[KeyCode::Char('c')]);
+        assert!(!app.compact);
+        assert!(runner.runs.is_empty());
+    }
+
+
+    #[test]
+    fn ctrl_d_and_ctrl_u_scroll_half_a_page() {
+        let mut app = three_scripts();
+        app.mode = AppMode::Output;
+        app.output_text = (0..40).map(|i| format!("line {}\n", i)).collect();
+        app.viewport_height = 10;
+        let mut runner = StubRunner::default();
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+
+        handle_key(&mut app, ctrl('d'), &mut runner);
+        handle_key(&mut app, ctrl('d'), &mut runner);
+        assert_eq!(app.output_scroll, 10);
+        handle_key(&mut app, ctrl('u'), &mut runner);
+        assert_eq!(app.output_scroll, 5);
+        assert_eq!(current_view(&app), View::Output);
+    }
+
+
+    #[test]
+    fn i_opens_the_info_popup_and_any_key_closes_it() {
+        let mut app = three_scripts();
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[KeyCode::Char('i')]);
+        assert_eq!(current_view(&app), View::Info);
+        press(&mut app, &mut runner, &[KeyCode::Char('j')]);
+        assert_eq!(current_view(&app), View::List);
+        assert_eq!(app.selected_index, 0);
+    }
+
+    #[test]
+    fn enter_does_the_configured_default_action() {
+        let mut app = three_scripts();
+        app.default_action = DefaultAction::Info;
+        let mut runner = StubRunner::default();
+
+        press(&mut app, &mut runner, &[KeyCode::Enter]);
+
+        assert!(runner.runs.is_empty());
+        assert_eq!(current_view(&app), View::Info);
+    }
+}
+
+mod filter_tests {
+    use super::*;
+    use rusty_herring::RunResult;
+
+    fn described(name: &str, description: &str) -> Script {
+        Script {
+            description: Some(description.to_string()),
+            ..make_script(name, None)
+        }
+    }
+
+    #[test]
+    fn matches_name_or_description_case_insensitively() {
+        let mut app = App::new(vec![
+            described("backup.sh", "Copy files offsite"),
+            described("deploy.sh", "Ship to PRODUCTION"),
+            make_script("Cleanup.sh", None),
+        ]);
+
+        app.filter_query = "prod".to_string();
+        assert_eq!(app.filtered_indices(), vec![1]);
+
+        app.filter_query = "CLEAN".to_string();
+        assert_eq!(app.filtered_indices(), vec![2]);
+
+        app.filter_query.clear();
+        assert_eq!(app.filtered_indices(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn navigation_skips_filtered_out_scripts() {
+        let mut app = App::new(vec![
+            make_script("db-reset.sh", None),
+            make_script("hello.sh", None),
+            make_script("db-seed.sh", None),
+        ]);
+        app.push_filter_char('d');
+        app.push_filter_char('b');
+
+        assert_eq!(app.selected_index, 0);
+        app.next();
+        assert_eq!(app.selected_index, 2);
+        app.next();
+        assert_eq!(app.selected_index, 2);
+        app.previous();
+        assert_eq!(app.selected_index, 0);
+    }
+
+    #[test]
+    fn selection_moves_when_filtered_out() {
+        let mut app = App::new(vec![
+            make_script("alpha.sh", None),
+            make_script("beta.sh", None),
+            make_script("gamma.sh", None),
+        ]);
+        app.selected_index = 1;
+
+        for c in "gam".chars() {
+            app.push_filter_char(c);
+        }
+        assert_eq!(app.selected_index, 2);
+        assert_eq!(app.selected_script().unwrap().name, "gamma.sh");
+
+        app.pop_filter_char();
+        app.pop_filter_char();
+        app.pop_filter_char();
+        assert_eq!(app.selected_index, 2);
+    }
+
+    #[test]
+    fn no_matches_leaves_nothing_selected() {
+        let mut app = App::new(vec![make_script("alpha.sh", None)]);
+
+        app.push_filter_char('z');
+
+        assert!(app.filtered_indices().is_empty());
+        assert!(app.selected_script().is_none());
+        app.next();
+        app.previous();
+        assert_eq!(app.selected_index, 0);
+    }
+
+
+    fn tagged(name: &str, tags: &[&str]) -> Script {
+        Script {
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..make_script(name, None)
+        }
+    }
+
+    #[test]
+    fn t_cycles_through_tags_then_untagged() {
+        let mut app = App::new(vec![
+            tagged("deploy.sh", &["deploy", "safe"]),
+            tagged("migrate.sh", &["db"]),
+            make_script("misc.sh", None),
+        ]);
+        let visible = |app: &App| -> Vec<String> {
+            app.filtered_indices().iter().map(|&i| app.scripts[i].name.clone()).collect()
+        };
+
+        app.cycle_tag_filter();
+        assert_eq!(app.tag_filter, Some(TagFilter::Tag("db".to_string())));
+        assert_eq!(visible(&app), vec!["migrate.sh"]);
+        assert_eq!(app.selected_index, 1);
+
+        app.cycle_tag_filter();
+        app.cycle_tag_filter();
+        assert_eq!(app.tag_filter, Some(TagFilter::Tag("safe".to_string())));
+
+        app.cycle_tag_filter();
+        assert_eq!(app.tag_filter, Some(TagFilter::Untagged));
+        assert_eq!(visible(&app), vec!["misc.sh"]);
+
+        app.cycle_tag_filter();
+        assert_eq!(app.tag_filter, None);
+        assert_eq!(visible(&app).len(), 3);
+    }
+
+
+    #[test]
+    fn smart_case_is_insensitive_until_the_query_has_uppercase() {
+        let mut app = App::new(vec![
+            make_script("DBBackup.sh", None),
+            make_script("dbbackup.sh", None),
+            described("restore.sh", "Reload the DB"),
+        ]);
+        app.filter_smart_case = true;
+
+        app.filter_query = "db".to_string();
+        assert_eq!(app.filtered_indices(), vec![0, 1, 2]);
+
+        app.filter_query = "DB".to_string();
+        assert_eq!(app.filtered_indices(), vec![0, 2]);
+    }
+
+    #[test]
+    fn without_smart_case_uppercase_queries_still_ignore_case() {
+        let mut app = App::new(vec![make_script("dbbackup.sh", None)]);
+
+        app.filter_query = "DB".to_string();
+        assert_eq!(app.filtered_indices(), vec![0]);
+    }
+
+
+    fn result(exit_code: i32) -> RunResult {
+        RunResult {
+            status: RunStatus::from_exit_code(exit_code),
+            duration: Duration::from_millis(100),
+        }
+    }
+
+    #[test]
+    fn failed_only_keeps_scripts_whose_last_run_failed() {
+        let mut app = App::new(vec![
+            make_script("passed.sh", None),
+            make_script("failed.sh", None),
+            make_script("killed.sh", None),
+            make_script("never.sh", None),
+        ]);
+        app.last_results.insert("/tmp/passed.sh".to_string(), result(0));
+        app.last_results.insert("/tmp/failed.sh".to_string(), result(2));
+        app.last_results.insert("/tmp/killed.sh".to_string(), result(-1));
+
+        app.toggle_failed_only();
+        assert_eq!(app.filtered_indices(), vec![1, 2]);
+        assert_eq!(app.selected_index, 1);
+
+        app.toggle_failed_only();
+        assert_eq!(app.filtered_indices(), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn rescan_clears_the_failed_filter() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("a.sh");
+        fs::write(&path, "#!/bin/sh\n").unwrap();
+        make_executable(&path);
+        let mut app = App::new(scan_directory(dir.path().to_str().unwrap()).unwrap());
+        app.scan_dirs = vec![dir.path().to_str().unwrap().to_string()];
+
+        app.toggle_failed_only();
+        assert!(app.filtered_indices().is_empty());
+        app.rescan();
+        assert!(!app.failed_only);
+        assert_eq!(app.filtered_indices(), vec![0]);
+    }
+}
+
+mod args_tests {
+    use super::*;
+
+    #[test]
+    fn splits_on_whitespace() {
+        assert_eq!(split_args("  -v   --dry-run path "), vec!["-v", "--dry-run", "path"]);
+        assert!(split_args("").is_empty());
+        assert!(split_args("   ").is_empty());
+    }
+
+    #[test]
+    fn respects_quotes_and_escapes() {
+        assert_eq!(
+            split_args(r#"--msg "hello world" 'it''s' a\ b"#),
+            vec!["--msg", "hello world", "its", "a b"]
+        );
+        assert_eq!(split_args(r#"'single \n' "double \" q""#), vec![r"single \n", r#"double " q"#]);
+        assert_eq!(split_args("''"), vec![""]);
+    }
+
+    #[test]
+    fn formats_reproducible_command() {
+        let args = split_args("--dry-run 'two words' it\\'s");
+        assert_eq!(
+            format_command("./deploy.sh", &args),
+            r#"./deploy.sh --dry-run 'two words' 'it'\''s'"#
+        );
+    }
+}
+
+mod run_tests {
+    use super::*;
+    use std::path::{Path, PathBuf};
+    use std::thread;
+
+    use crossterm::event::{KeyCode, KeyEvent};
+    use rusty_herring::batch::OnFailure;
+    use rusty_herring::RunStatus;
+    use rusty_herring::input::{View, current_view, handle_key};
+    use rusty_herring::run::{self, ProcessRunner};
+    use rusty_herring::run_counts::RunCounts;
+
+    /// Polls until the run finishes, failing the test if it hangs.
+    fn wait_for_run(app: &mut App) {
+        for _ in 0..500 {
+            app.poll_run();
+            if !app.is_running() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        panic!("script did not finish");
+    }
+
+    fn write_script(dir: &TempDir, name: &str, body: &str) -> Script {
+        let path = dir.path().join(name);
+        fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        make_executable(&path);
+        Script {
+            path: path.to_str().unwrap().to_string(),
+            ..make_script(name, None)
+        }
+    }
+
+    #[test]
+    fn streams_output_then_shows_result_banner() {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(&dir, "ok.sh", "echo out; echo err >&2");
+        let mut app = App::new(vec![script]);
+
+        app.start_run().unwrap();
+        assert!(app.is_running());
+        assert!(app.viewing_output());
+        assert!(app.output_text.starts_with("Running script..."));
+
+        wait_for_run(&mut app);
+
+        assert!(app.output_text.starts_with("✓ Script completed successfully"));
+        assert!(app.output_text.contains("=== OUTPUT ===\nout\n"));
+        assert!(app.output_text.contains("=== ERRORS ===\nerr\n"));
+    }
+
+    #[test]
+    fn reports_nonzero_exit() {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(&dir, "fail.sh", "exit 3");
+        let mut app = App::new(vec![script]);
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+
+        assert!(app.output_text.starts_with("✗ Script failed\nExit code: 3"));
+        assert!(app.output_text.contains("(no output)"));
+    }
+
+    #[test]
+    fn last_exit_code_follows_the_latest_run() {
+        let dir = TempDir::new().unwrap();
+        let mut app = App::new(vec![
+            write_script(&dir, "fail.sh", "exit 3"),
+            write_script(&dir, "ok.sh", "exit 0"),
+        ]);
+        assert_eq!(app.last_exit_code, None);
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+        assert_eq!(app.last_exit_code, Some(3));
+
+        app.selected_index = 1;
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+        assert_eq!(app.last_exit_code, Some(0));
+    }
+
+    #[test]
+    fn last_status_tracks_the_run_from_start_to_finish() {
+        let dir = TempDir::new().unwrap();
+        let mut app = App::new(vec![
+            write_script(&dir, "ok.sh", "sleep 0.1"),
+            write_script(&dir, "fail.sh", "exit 3"),
+        ]);
+        assert_eq!(app.last_status, None);
+
+        app.start_run().unwrap();
+        assert_eq!(app.last_status, Some(RunStatus::Running));
+        wait_for_run(&mut app);
+        assert_eq!(app.last_status, Some(RunStatus::Success));
+
+        app.selected_index = 1;
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+        assert_eq!(app.last_status, Some(RunStatus::Failure(3)));
+
+        app.back_to_list();
+        assert_eq!(app.last_status, None);
+    }
+
+    #[test]
+    fn last_status_names_the_signal_that_killed_the_script() {
+        let dir = TempDir::new().unwrap();
+        let mut app = App::new(vec![
+            write_script(&dir, "term.sh", "kill -TERM $$"),
+            write_script(&dir, "slow.sh", "sleep 5"),
+        ]);
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+        assert_eq!(app.last_status, Some(RunStatus::Signal(15)));
+        assert_eq!(app.last_exit_code, Some(128 + 15));
+
+        app.selected_index = 1;
+        app.start_run().unwrap();
+        app.cancel_run();
+        assert_eq!(app.last_status, Some(RunStatus::Signal(9)));
+        assert_eq!(app.last_exit_code, Some(128 + 9));
+    }
+
+    #[test]
+    fn output_starting_with_a_check_mark_is_not_a_success() {
+        let dir = TempDir::new().unwrap();
+        let mut app = App::new(vec![write_script(&dir, "fake.sh", "echo '✓ fine'; exit 1")]);
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+
+        assert_eq!(app.last_status, Some(RunStatus::Failure(1)));
+    }
+
+    #[test]
+    fn passes_arguments_and_echoes_command() {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(&dir, "args.sh", "echo \"[$1] [$2]\"");
+        let mut app = App::new(vec![script]);
+        app.pending_args = "one 'two words'".to_string();
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+
+        assert!(app.output_text.contains("[one] [two words]"));
+        assert!(app.output_text.contains("Command: "));
+        assert!(app.output_text.contains("args.sh one 'two words'"));
+    }
+
+    #[test]
+    fn follows_long_output_to_the_bottom() {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(&dir, "long.sh", "seq 1 100");
+        let mut app = App::new(vec![script]);
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+
+        let total = app.output_text.lines().count();
+        assert_eq!(app.output_scroll, total - 20);
+    }
+
+    #[test]
+    fn cancel_kills_and_reaps_the_child() {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(&dir, "hang.sh", "echo started; exec sleep 30");
+        let mut app = App::new(vec![script]);
+
+        app.start_run().unwrap();
+        for _ in 0..200 {
+            app.poll_run();
+            if app.output_text.contains("started") {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        app.cancel_run();
+
+        assert!(!app.is_running());
+        assert!(app.output_text.starts_with("✗ Script terminated by user"));
+        assert!(app.output_text.contains("Exit state: signal: 9"));
+        assert!(app.output_text.contains("started"));
+    }
+
+    #[test]
+    fn cancel_after_exit_does_not_panic() {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(&dir, "quick.sh", "exit 0");
+        let mut app = App::new(vec![script]);
+
+        app.start_run().unwrap();
+        thread::sleep(Duration::from_millis(200));
+        app.cancel_run();
+
+        assert!(!app.is_running());
+        assert!(app.output_text.contains("Exit state: exit status: 0"));
+    }
+
+    #[test]
+    fn log_name_sanitizes_script_name() {
+        assert_eq!(
+            output_log_name("db/back up.sh", 1700000000),
+            "herring-output-db_back_up.sh-1700000000.log"
+        );
+    }
+
+    #[test]
+    fn strip_banner_drops_first_two_lines() {
+        let text = run::format_output("✓ Script completed successfully\nExit code: 0", "", "hi\n", "");
+
+        assert!(run::strip_banner(&text).starts_with("\n=== OUTPUT ===\nhi"));
+    }
+
+    #[test]
+    fn save_output_writes_log_and_reports_it() {
+        let dir = TempDir::new().unwrap();
+        let mut app = App::new(vec![make_script("deploy.sh", None)]);
+        app.output_text = "✓ Script completed successfully\nExit code: 0\nok".to_string();
+        app.output_path = Some(app.scripts[0].path.clone());
+        app.keep_banner_in_logs = false;
+
+        app.save_output(dir.path());
+
+        let entry = fs::read_dir(dir.path()).unwrap().next().unwrap().unwrap();
+        let name = entry.file_name().into_string().unwrap();
+        assert!(name.starts_with("herring-output-deploy.sh-"));
+        assert_eq!(fs::read_to_string(entry.path()).unwrap(), "ok");
+        assert!(app.status_message.unwrap().starts_with("Saved to "));
+    }
+
+    #[test]
+    fn save_output_reports_write_errors() {
+        let mut app = App::new(vec![make_script("deploy.sh", None)]);
+        app.output_text = "text".to_string();
+        app.output_path = Some(app.scripts[0].path.clone());
+
+        app.save_output(Path::new("/nonexistent/dir"));
+
+        assert!(app.status_message.unwrap().starts_with("✗ Could not save"));
+    }
+
+    #[test]
+    fn saved_log_is_named_for_the_script_that_ran() {
+        let dir = TempDir::new().unwrap();
+        let logs = TempDir::new().unwrap();
+        let mut app = App::new(vec![
+            write_script(&dir, "deploy.sh", "echo shipped"),
+            write_script(&dir, "other.sh", "true"),
+        ]);
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+        app.selected_index = 1;
+        app.save_output(logs.path());
+
+        let entry = fs::read_dir(logs.path()).unwrap().next().unwrap().unwrap();
+        let name = entry.file_name().into_string().unwrap();
+        assert!(name.starts_with("herring-output-deploy.sh-"), "{}", name);
+    }
+
+    #[test]
+    fn timeout_kills_a_runaway_script() {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(&dir, "hang.sh", "echo started; exec sleep 30");
+        let mut app = App::new(vec![script]);
+        app.timeout = Some(Duration::from_secs(1));
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+
+        assert!(app.output_text.starts_with("✗ Script timed out after 1s"));
+        assert!(app.output_text.contains("started"));
+    }
+
+
+    #[test]
+    fn finished_run_is_recorded_in_history() {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(&dir, "fail.sh", "exit 4");
+        let path = script.path.clone();
+        let mut app = App::new(vec![script]);
+        app.history_path = Some(dir.path().join("history"));
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+
+        assert_eq!(app.history.entries[0].path, path);
+        assert_eq!(app.history.entries[0].status, RunStatus::Failure(4));
+        assert_eq!(app.last_results[&path].status, RunStatus::Failure(4));
+        let saved = History::load_from(&dir.path().join("history")).unwrap();
+        assert_eq!(saved.entries[0].path, path);
+    }
+
+
+    #[test]
+    fn finished_runs_are_counted_and_saved() {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(&dir, "ok.sh", "exit 0");
+        let key = fs::canonicalize(&script.path).unwrap().to_str().unwrap().to_string();
+        let mut app = App::new(vec![script]);
+        app.run_counts_path = Some(dir.path().join("run-counts"));
+
+        for _ in 0..2 {
+            app.start_run().unwrap();
+            wait_for_run(&mut app);
+        }
+
+        assert_eq!(app.scripts[0].run_count, 2);
+        let saved = RunCounts::load_from(&dir.path().join("run-counts")).unwrap();
+        assert_eq!(saved.get(&key), 2);
+    }
+
+    #[test]
+    fn injected_env_is_visible_to_the_script() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join(".herring.env"), "FOO=injected\n").unwrap();
+        let script = write_script(&dir, "env.sh", "echo \"foo=$FOO home=${HOME:+set}\"");
+        let mut app = App::new(vec![script]);
+        app.env = rusty_herring::config::load_env(dir.path());
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+
+        assert!(app.output_text.contains("foo=injected home=set"));
+    }
+
+
+    #[test]
+    fn non_executable_script_runs_through_its_shebang() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("plain.sh");
+        fs::write(&path, "#!/bin/sh -e\necho \"via $0 with $1\"\n").unwrap();
+        let script = Script {
+            path: path.to_str().unwrap().to_string(),
+            interpreter: Some("/bin/sh -e".to_string()),
+            ..make_script("plain.sh", None)
+        };
+        let mut app = App::new(vec![script]);
+        app.pending_args = "arg".to_string();
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+
+        assert!(app.output_text.contains("with arg"), "{}", app.output_text);
+        assert!(app.output_text.contains("✓"), "{}", app.output_text);
+    }
+
+
+    #[test]
+    fn idle_app_polls_slowly_and_stays_clean() {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(&dir, "slow.sh", "sleep 5");
+        let mut app = App::new(vec![script]);
+        app.dirty = false;
+
+        assert!(!app.poll_run());
+        assert!(!app.dirty);
+        assert_eq!(app.poll_interval(), IDLE_POLL_INTERVAL);
+
+        app.start_run().unwrap();
+        assert_eq!(app.poll_interval(), ACTIVE_POLL_INTERVAL);
+        app.cancel_run();
+    }
+
+
+    fn wait_for_batch(app: &mut App) {
+        for _ in 0..500 {
+            app.poll_run();
+            if app.batch_finished() || app.batch_awaiting_decision() || app.batch_awaiting_confirm() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        panic!("batch did not finish");
+    }
+
+    fn batch_app(dir: &TempDir) -> App {
+        let mut app = App::new(vec![
+            write_script(dir, "a.sh", "echo first"),
+            write_script(dir, "b.sh", "echo broken; exit 3"),
+            write_script(dir, "c.sh", "echo last"),
+        ]);
+        for i in 0..3 {
+            app.selected_index = i;
+            app.toggle_mark();
+        }
+        app
+    }
+
+    #[test]
+    fn batch_runs_marked_scripts_in_order_with_a_summary() {
+        let dir = TempDir::new().unwrap();
+        let mut app = batch_app(&dir);
+        app.batch_on_failure = OnFailure::Continue;
+
+        app.start_batch();
+        wait_for_batch(&mut app);
+
+        let text = &app.output_text;
+        let first = text.find("[1/3] a.sh").unwrap();
+        let second = text.find("[2/3] b.sh").unwrap();
+        let third = text.find("[3/3] c.sh").unwrap();
+        assert!(first < second && second < third, "{}", text);
+        assert!(text.ends_with("=== SUMMARY ===\n2 passed, 1 failed"), "{}", text);
+        assert_eq!(app.last_results.len(), 3);
+    }
+
+    #[test]
+    fn batch_log_is_named_for_the_batch_and_kept_whole() {
+        let dir = TempDir::new().unwrap();
+        let mut app = batch_app(&dir);
+        app.batch_on_failure = OnFailure::Continue;
+        app.keep_banner_in_logs = false;
+        app.start_batch();
+        wait_for_batch(&mut app);
+        let logs = TempDir::new().unwrap();
+
+        app.save_output(logs.path());
+
+        let entry = fs::read_dir(logs.path()).unwrap().next().unwrap().unwrap();
+        let name = entry.file_name().into_string().unwrap();
+        assert!(name.starts_with("herring-batch-"), "{}", name);
+        assert_eq!(fs::read_to_string(entry.path()).unwrap(), app.output_text);
+    }
+
+    #[test]
+    fn batch_stops_after_a_failure_when_configured() {
+        let dir = TempDir::new().unwrap();
+        let mut app = batch_app(&dir);
+        app.batch_on_failure = OnFailure::Stop;
+
+        app.start_batch();
+        wait_for_batch(&mut app);
+
+        assert!(!app.output_text.contains("c.sh"), "{}", app.output_text);
+        assert!(app.output_text.ends_with("1 passed, 1 failed, 1 skipped"));
+    }
+
+    #[test]
+    fn batch_asks_before_going_on_after_a_failure() {
+        let dir = TempDir::new().unwrap();
+        let mut app = batch_app(&dir);
+
+        app.start_batch();
+        wait_for_batch(&mut app);
+        assert!(app.batch_awaiting_decision());
+        assert_eq!(current_view(&app), View::BatchPrompt);
+
+        handle_key(&mut app, KeyEvent::from(KeyCode::Char('y')), &mut ProcessRunner);
+        wait_for_batch(&mut app);
+
+        assert!(app.batch_finished());
+        assert!(app.output_text.ends_with("2 passed, 1 failed"), "{}", app.output_text);
+    }
+
+    fn in_category(script: Script, category: &str) -> Script {
+        Script {
+            category: Some(category.to_string()),
+            ..script
+        }
+    }
+
+    #[test]
+    fn a_runs_every_script_in_the_selected_category() {
+        let dir = TempDir::new().unwrap();
+        let mut app = App::new(vec![
+            in_category(write_script(&dir, "lint.sh", "echo lint"), "checks"),
+            in_category(write_script(&dir, "test.sh", "echo test"), "checks"),
+            in_category(write_script(&dir, "ship.sh", "echo ship"), "deploy"),
+        ]);
+        app.selected_index = 1;
+
+        handle_key(&mut app, KeyEvent::from(KeyCode::Char('A')), &mut ProcessRunner);
+        wait_for_batch(&mut app);
+
+        let text = &app.output_text;
+        assert!(text.contains("[1/2] lint.sh") && text.contains("[2/2] test.sh"), "{}", text);
+        assert!(!text.contains("ship"), "{}", text);
+        assert!(text.ends_with("=== SUMMARY ===\n2 passed, 0 failed"), "{}", text);
+    }
+
+    #[test]
+    fn run_category_leaves_a_collapsed_category_alone() {
+        let dir = TempDir::new().unwrap();
+        let mut app = App::new(vec![in_category(write_script(&dir, "lint.sh", "echo lint"), "checks")]);
+        app.toggle_collapse();
+
+        app.run_category();
+
+        assert!(app.batch.is_none());
+        assert_eq!(app.mode, AppMode::List);
+    }
+
+    #[test]
+    fn batch_scripts_with_a_confirm_marker_ask_first() {
+        let dir = TempDir::new().unwrap();
+        let risky = |name: &str| Script {
+            confirm_message: Some("Drops the table".to_string()),
+            ..in_category(write_script(&dir, name, "echo dropped"), "db")
+        };
+        let mut app = App::new(vec![
+            risky("drop.sh"),
+            in_category(write_script(&dir, "seed.sh", "echo seeded"), "db"),
+            risky("redrop.sh"),
+        ]);
+
+        app.run_category();
+        wait_for_batch(&mut app);
+        assert_eq!(current_view(&app), View::BatchConfirm);
+
+        handle_key(&mut app, KeyEvent::from(KeyCode::Char('n')), &mut ProcessRunner);
+        wait_for_batch(&mut app);
+        assert_eq!(current_view(&app), View::BatchConfirm);
+
+        handle_key(&mut app, KeyEvent::from(KeyCode::Char('y')), &mut ProcessRunner);
+        wait_for_batch(&mut app);
+
+        assert!(app.batch_finished());
+        let text = &app.output_text;
+        assert!(text.contains("seeded") && text.contains("dropped"), "{}", text);
+        assert!(text.ends_with("2 passed, 0 failed, 1 skipped"), "{}", text);
+    }
+
+
+    fn run_binary(mode: run::InvalidUtf8) -> String {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(&dir, "bin.sh", "printf 'ok\\n'; printf 'caf\\351 \\377\\n'");
+        let mut app = App::new(vec![script]);
+        app.invalid_utf8 = mode;
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+        app.output_text
+    }
+
+    #[test]
+    fn invalid_utf8_is_counted_when_replaced() {
+        let text = run_binary(run::InvalidUtf8::Lossy);
+
+        assert!(text.contains("⚠ 2 invalid UTF-8 sequences replaced"), "{}", text);
+        assert!(text.contains("caf� �"), "{}", text);
+    }
+
+    #[test]
+    fn invalid_utf8_can_be_hex_dumped_or_suppressed() {
+        let hex = run_binary(run::InvalidUtf8::Hex);
+        assert!(hex.contains("00000003  63 61 66 e9 20 ff 0a"), "{}", hex);
+        assert!(hex.contains("|caf. ..|"), "{}", hex);
+
+        let suppressed = run_binary(run::InvalidUtf8::Suppress);
+        assert!(suppressed.contains("Binary output suppressed (7 bytes)"), "{}", suppressed);
+        assert!(suppressed.contains("ok\n"));
+        assert!(!suppressed.contains("caf"));
+    }
+
+
+    #[test]
+    fn runs_in_the_scripts_own_directory() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("tools")).unwrap();
+        let script = write_script(&dir, "tools/write.sh", "echo hi > ./out.txt");
+        let mut app = App::new(vec![script]);
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+
+        assert!(app.output_text.starts_with("✓"), "{}", app.output_text);
+        assert!(dir.path().join("tools/out.txt").exists());
+        assert!(!dir.path().join("out.txt").exists());
+    }
+
+    #[test]
+    fn working_dir_follows_the_mode() {
+        let script = Script {
+            path: "/srv/scripts/db/seed.sh".to_string(),
+            relative_path: "db/seed.sh".to_string(),
+            ..make_script("seed.sh", Some("db"))
+        };
+
+        assert_eq!(
+            working_dir(&script, run::WorkingDir::Script),
+            Some(PathBuf::from("/srv/scripts/db"))
+        );
+        assert_eq!(
+            working_dir(&script, run::WorkingDir::Root),
+            Some(PathBuf::from("/srv/scripts"))
+        );
+        assert_eq!(working_dir(&script, run::WorkingDir::Launch), None);
+    }
+
+
+    #[test]
+    fn spinner_turns_while_running_and_stops_after() {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(&dir, "slow.sh", "sleep 0.4");
+        let mut app = App::new(vec![script]);
+
+        app.start_run().unwrap();
+        let mut frames = std::collections::HashSet::new();
+        while app.is_running() {
+            app.poll_run();
+            frames.extend(app.spinner());
+            thread::sleep(Duration::from_millis(20));
+        }
+
+        assert!(frames.len() > 1, "{:?}", frames);
+        assert!(frames.iter().all(|c| SPINNER_FRAMES.contains(c)));
+        assert_eq!(app.spinner(), None);
+        assert!(app.output_text.starts_with("✓"));
+    }
+
+
+    #[test]
+    fn quitting_during_a_run_asks_first() {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(&dir, "long.sh", "sleep 5");
+        let mut app = App::new(vec![script]);
+        let press = |app: &mut App, key: KeyCode| {
+            handle_key(app, KeyEvent::from(key), &mut ProcessRunner);
+        };
+
+        app.start_run().unwrap();
+        app.back_to_list();
+        press(&mut app, KeyCode::Char('q'));
+        assert_eq!(current_view(&app), View::QuitPrompt);
+        assert!(!app.should_quit);
+
+        press(&mut app, KeyCode::Char('n'));
+        assert_eq!(current_view(&app), View::List);
+        assert!(app.is_running());
+
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(current_view(&app), View::QuitPrompt);
+        press(&mut app, KeyCode::Char('y'));
+        assert!(app.should_quit);
+        assert!(!app.is_running());
+    }
+
+
+    #[test]
+    fn output_buffer_keeps_the_newest_lines() {
+        let mut buffer = run::OutputBuffer::default();
+        buffer.limit = Some(3);
+
+        buffer.push_str("one\ntwo\nthr");
+        buffer.push_str("ee\nfour\n");
+        buffer.push_str("five\n");
+
+        assert_eq!(buffer.text(), "three\nfour\nfive\n");
+        assert_eq!(buffer.dropped(), 2);
+    }
+
+    #[test]
+    fn runaway_output_is_capped_with_a_note() {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(&dir, "noisy.sh", "seq 1 50");
+        let mut app = App::new(vec![script]);
+        app.max_output_lines = Some(5);
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+
+        let text = &app.output_text;
+        assert!(text.contains("⚠ Output truncated, showing the last 5 lines"), "{}", text);
+        assert!(text.contains("=== OUTPUT ===\n46\n47\n48\n49\n50\n"), "{}", text);
+        assert!(!text.contains("\n45\n"));
+    }
+
+
+    #[test]
+    fn deleted_script_is_reported_as_no_longer_available() {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(&dir, "gone.sh", "echo hi");
+        fs::remove_file(&script.path).unwrap();
+        let mut app = App::new(vec![script]);
+
+        let error = app.start_run().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        assert!(error.to_string().starts_with("Script no longer available: "));
+        assert!(!app.is_running());
+
+        handle_key(&mut app, KeyEvent::from(KeyCode::Enter), &mut ProcessRunner);
+        assert!(app.viewing_output());
+        assert!(app.output_text.contains("Script no longer available"));
+    }
+
+    #[test]
+    fn script_replaced_by_directory_is_not_run() {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(&dir, "moved.sh", "echo hi");
+        fs::remove_file(&script.path).unwrap();
+        fs::create_dir(&script.path).unwrap();
+        let mut app = App::new(vec![script]);
+
+        let error = app.start_run().unwrap_err();
+        assert!(error.to_string().contains("no longer available"));
+    }
+
+    #[test]
+    fn batch_counts_deleted_script_as_failed() {
+        let dir = TempDir::new().unwrap();
+        let gone = write_script(&dir, "a.sh", "echo a");
+        let kept = write_script(&dir, "b.sh", "echo b");
+        fs::remove_file(&gone.path).unwrap();
+        let mut app = App::new(vec![gone, kept]);
+        app.batch_on_failure = OnFailure::Continue;
+
+        for i in 0..2 {
+            app.selected_index = i;
+            app.toggle_mark();
+        }
+
+        app.start_batch();
+        wait_for_batch(&mut app);
+
+        assert!(app.output_text.contains("Script no longer available"));
+        assert!(app.output_text.contains("1 passed, 1 failed"));
+    }
+
+
+    #[test]
+    fn editor_runs_with_its_own_arguments_and_the_path() {
+        let dir = TempDir::new().unwrap();
+        let marker = dir.path().join("edited");
+        let editor = format!("sh -c 'echo \"$0\" > {}'", marker.display());
+
+        let status = run::open_in_editor(&editor, "script.sh").unwrap();
+
+        assert!(status.success());
+        assert_eq!(fs::read_to_string(&marker).unwrap(), "script.sh\n");
+    }
+
+    #[test]
+    fn clean_editor_exit_rescans() {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(&dir, "a.sh", "# Old description");
+        let path = script.path.clone();
+        let mut app = App::new(vec![script]);
+        app.scan_dirs = vec![dir.path().to_str().unwrap().to_string()];
+        fs::write(&path, "#!/bin/sh\n# New description\n").unwrap();
+
+        let status = run::open_in_editor("true", &path);
+        app.finish_edit("true", status);
+
+        assert_eq!(app.scripts[0].description, Some("New description".to_string()));
+        assert_eq!(app.status_message, Some("Rescanned: 1 scripts".to_string()));
+    }
+
+    #[test]
+    fn failed_editor_is_reported() {
+        let mut app = App::new(vec![make_script("a.sh", None)]);
+
+        app.finish_edit("false", run::open_in_editor("false", "a.sh"));
+        assert_eq!(
+            app.status_message,
+            Some("✗ false exited with exit status: 1".to_string())
+        );
+
+        let missing = "/nonexistent/editor";
+        app.finish_edit(missing, run::open_in_editor(missing, "a.sh"));
+        assert!(app
+            .status_message
+            .as_ref()
+            .unwrap()
+            .starts_with("✗ Could not start /nonexistent/editor: "));
+    }
+
+
+    #[test]
+    fn run_command_replaces_direct_execution() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("snippet.sh");
+        // Not executable, and would fail if it were run itself
+        fs::write(&path, "exit 1\n").unwrap();
+        let script = Script {
+            path: path.to_str().unwrap().to_string(),
+            run_command: Some("echo running {} now".to_string()),
+            ..make_script("snippet.sh", None)
+        };
+        let mut app = App::new(vec![script]);
+        app.pending_args = "extra".to_string();
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+
+        assert!(app.output_text.starts_with("✓"), "{}", app.output_text);
+        let expected = format!("=== OUTPUT ===\nrunning {} now extra\n", path.display());
+        assert!(app.output_text.contains(&expected), "{}", app.output_text);
+    }
+
+    #[test]
+    fn run_command_without_placeholder_gets_the_path_last() {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(&dir, "a.sh", "exit 1");
+        let path = script.path.clone();
+        let mut app = App::new(vec![Script {
+            run_command: Some("echo via".to_string()),
+            ..script
+        }]);
+
+        app.dry_run();
+
+        assert!(app.output_text.contains(&format!("Command: echo via {}\n", path)));
+    }
+
+
+    #[test]
+    fn verbose_failures_add_diagnostics() {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(&dir, "fail.sh", "exit 3");
+        let path = script.path.clone();
+        let mut app = App::new(vec![script]);
+        app.verbose_failures = true;
+        app.env = vec![("DEPLOY_TARGET".to_string(), "staging".to_string())];
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+
+        let text = &app.output_text;
+        let diagnostics = &text[text.find("\n\n=== DIAGNOSTICS ===\n").expect(text)..];
+        assert!(diagnostics.contains(&format!("Executable: {}\n", path)), "{}", text);
+        assert!(
+            diagnostics.contains(&format!("Working directory: {}\n", dir.path().display())),
+            "{}",
+            text
+        );
+        assert!(diagnostics.contains("Exit code: 3\n"));
+        assert!(diagnostics.contains("\nPATH="));
+        assert!(diagnostics.ends_with("DEPLOY_TARGET=staging\n"));
+    }
+
+    #[test]
+    fn diagnostics_show_the_signal_and_skip_successes() {
+        let dir = TempDir::new().unwrap();
+        let mut app = App::new(vec![
+            write_script(&dir, "killed.sh", "kill -9 $$"),
+            write_script(&dir, "ok.sh", "true"),
+        ]);
+        app.verbose_failures = true;
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+        assert!(app.output_text.contains("=== DIAGNOSTICS ===\n"));
+        assert!(app.output_text.contains("\nSignal: 9 (SIGKILL)\n"), "{}", app.output_text);
+
+        app.selected_index = 1;
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+        assert!(!app.output_text.contains("DIAGNOSTICS"));
+    }
+
+    #[test]
+    fn failures_have_no_diagnostics_unless_verbose() {
+        let dir = TempDir::new().unwrap();
+        let mut app = App::new(vec![write_script(&dir, "fail.sh", "exit 3")]);
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+
+        assert!(!app.output_text.contains("DIAGNOSTICS"));
+    }
+
+
+    #[test]
+    fn signal_termination_is_reported_by_name() {
+        let dir = TempDir::new().unwrap();
+        let mut app = App::new(vec![write_script(&dir, "crash.sh", "kill -SEGV $$")]);
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+
+        assert!(
+            app.output_text
+                .starts_with("✗ Script killed by signal 11 (SIGSEGV)\nExit code: none\n"),
+            "{}",
+            app.output_text
+        );
+    }
+
+    #[test]
+    fn signal_termination_is_named_in_the_badge_and_history() {
+        let dir = TempDir::new().unwrap();
+        let dir_path = dir.path().to_path_buf();
+        let mut app = App::new(vec![write_script(&dir, "crash.sh", "kill -SEGV $$")]);
+        app.history_path = Some(dir_path.join("history"));
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+
+        let path = app.scripts[0].path.clone();
+        assert_eq!(app.last_results[&path].badge(), "[✗ SIGSEGV]");
+        assert_eq!(app.history.entries[0].status, RunStatus::Signal(11));
+        let saved = History::load_from(&dir_path.join("history")).unwrap();
+        assert_eq!(saved.entries[0].status, RunStatus::Signal(11));
+    }
+
+    #[test]
+    fn nonzero_exit_is_not_mistaken_for_a_signal() {
+        let dir = TempDir::new().unwrap();
+        let mut app = App::new(vec![write_script(&dir, "fail.sh", "exit 143")]);
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+
+        assert!(app.output_text.starts_with("✗ Script failed\nExit code: 143\n"));
+    }
+
+    #[test]
+    fn signal_names() {
+        assert_eq!(run::signal_name(15), Some("SIGTERM"));
+        assert_eq!(run::signal_name(6), Some("SIGABRT"));
+        assert_eq!(run::signal_name(64), None);
+    }
+
+
+    #[test]
+    fn rerun_with_the_same_output_keeps_the_scroll_position() {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(&dir, "count.sh", "seq 1 60");
+        let mut app = App::new(vec![script]);
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+        app.scroll_output_to_top();
+        app.scroll_output_down(app.max_output_scroll());
+        app.scroll_output_down(app.max_output_scroll());
+        app.back_to_list();
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+        assert_eq!(app.output_scroll, 2);
+        assert!(!app.follow_output);
+    }
+
+    #[test]
+    fn rerun_with_different_output_starts_at_the_end() {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(&dir, "count.sh", "seq 1 60");
+        let path = script.path.clone();
+        let mut app = App::new(vec![script]);
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+        app.scroll_output_to_top();
+        app.back_to_list();
+
+        fs::write(&path, "#!/bin/sh\nseq 1 61\n").unwrap();
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+        assert_eq!(app.output_scroll, app.max_output_scroll());
+        assert!(app.output_scroll > 0);
+    }
+
+
+    #[test]
+    fn mode_is_running_until_the_script_exits() {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(&dir, "ok.sh", "echo done");
+        let mut app = App::new(vec![script]);
+        assert_eq!(app.mode, AppMode::List);
+
+        app.start_run().unwrap();
+        assert_eq!(app.mode, AppMode::Running);
+        assert_eq!(current_view(&app), View::Output);
+
+        wait_for_run(&mut app);
+        assert_eq!(app.mode, AppMode::Output);
+
+        app.back_to_list();
+        assert_eq!(app.mode, AppMode::List);
+    }
+
+    #[test]
+    fn cancelling_leaves_the_output_showing() {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(&dir, "slow.sh", "sleep 5");
+        let mut app = App::new(vec![script]);
+
+        app.start_run().unwrap();
+        app.cancel_run();
+
+        assert_eq!(app.mode, AppMode::Output);
+        assert!(app.output_text.starts_with("✗ Script terminated by user"));
+    }
+
+    #[test]
+    fn terminal_lines_lose_escapes_and_redrawn_text() {
+        assert_eq!(run::terminal_line(b"\x1b[1;31mred\x1b[0m\r\n"), b"red\n");
+        assert_eq!(run::terminal_line(b"\x1b]0;title\x07done"), b"done");
+        assert_eq!(run::terminal_line(b"10%\r50%\r100%\r\n"), b"100%\n");
+    }
+
+    #[cfg(not(feature = "pty"))]
+    #[test]
+    fn pty_runs_fall_back_to_pipes_without_the_feature() {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(&dir, "tty.sh", "[ -t 1 ] && echo tty || echo pipe");
+        let mut app = App::new(vec![script]);
+        app.capture = run::Capture::Pty;
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+
+        assert!(app.output_text.contains("⚠ Built without the pty feature"));
+        assert!(app.output_text.contains("=== OUTPUT ===\npipe\n"));
+    }
+
+    #[cfg(feature = "pty")]
+    #[test]
+    fn pty_runs_see_a_terminal_on_both_streams() {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(
+            &dir,
+            "tty.sh",
+            "[ -t 1 ] && [ -t 2 ] && echo tty || echo pipe\nprintf '\\033[32mok\\033[0m\\n' >&2",
+        );
+        let mut app = App::new(vec![script]);
+        app.capture = run::Capture::Pty;
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+
+        assert!(app.output_text.contains("◌ Ran in a pseudo-terminal"));
+        assert!(app.output_text.contains("=== OUTPUT ===\ntty\nok\n"));
+        assert!(app.output_text.contains("=== ERRORS ===\n(none)"));
+    }
+
+
+    #[test]
+    fn ascii_banner_is_built_plain_and_the_output_kept() {
+        let dir = TempDir::new().unwrap();
+        let script = write_script(&dir, "fail.sh", "echo '✓ step — done'; exit 2");
+        let mut app = App::new(vec![script]);
+        app.ascii = true;
+
+        app.start_run().unwrap();
+        wait_for_run(&mut app);
+
+        assert!(app.output_text.starts_with("[FAIL] Script failed"), "{}", app.output_text);
+        assert!(app.output_text.contains("=== OUTPUT ===\n✓ step — done\n"));
+        // Rows are counted from the text the view draws
+        app.viewport_width = 12;
+        app.wrap_output = true;
+        assert!(app.output_rows().contains(&"✓ step — don"));
+    }
+}
+
+mod group_tests {
+    use super::*;
+
+    fn grouped_app() -> App {
+        let mut scripts = vec![
+            make_script("seed.sh", Some("db")),
+            make_script("root.sh", None),
+            make_script("backup.sh", Some("utils")),
+            make_script("migrate.sh", Some("db")),
+        ];
+        group_by_category(&mut scripts);
+        App::new(scripts)
+    }
+
+    fn names(app: &App, indices: &[usize]) -> Vec<String> {
+        indices.iter().map(|&i| app.scripts[i].name.clone()).collect()
+    }
+
+    #[test]
+    fn uncategorized_first_then_alphabetical() {
+        let app = grouped_app();
+        let all: Vec<usize> = (0..app.scripts.len()).collect();
+
+        assert_eq!(
+            names(&app, &all),
+            vec!["root.sh", "seed.sh", "migrate.sh", "backup.sh"]
+        );
+    }
+
+    #[test]
+    fn collapsing_moves_selection_to_header_and_skips_members() {
+        let mut app = grouped_app();
+        app.selected_index = 2;
+
+        app.toggle_collapse();
+        assert!(app.is_collapsed("db"));
+        assert_eq!(app.selected_index, 1);
+        assert!(app.selected_script().is_none());
+
+        assert_eq!(names(&app, &app.navigable_indices()), vec!["root.sh", "seed.sh", "backup.sh"]);
+
+        app.next();
+        assert_eq!(app.selected_index, 3);
+        app.previous();
+        assert_eq!(app.selected_index, 1);
+        app.previous();
+        assert_eq!(app.selected_index, 0);
+    }
+
+    #[test]
+    fn toggling_again_expands() {
+        let mut app = grouped_app();
+        app.selected_index = 1;
+
+        app.toggle_collapse();
+        app.toggle_collapse();
+
+        assert!(!app.is_collapsed("db"));
+        assert_eq!(app.selected_script().unwrap().name, "seed.sh");
+        assert_eq!(app.navigable_indices(), vec![0, 1, 2, 3]);
+    }
+}
+
+mod ignore_tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn unanchored_pattern_matches_name_at_any_depth() {
+        let ignore = IgnorePatterns::parse("node_modules\n*.bak\n");
+
+        assert!(ignore.is_ignored(Path::new("node_modules"), true));
+        assert!(ignore.is_ignored(Path::new("web/node_modules"), true));
+        assert!(ignore.is_ignored(Path::new("tools/old.sh.bak"), false));
+        assert!(!ignore.is_ignored(Path::new("tools/old.sh"), false));
+    }
+
+    #[test]
+    fn anchored_pattern_matches_from_root() {
+        let ignore = IgnorePatterns::parse("/build.sh\nvendor/*/bin\n");
+
+        assert!(ignore.is_ignored(Path::new("build.sh"), false));
+        assert!(!ignore.is_ignored(Path::new("ci/build.sh"), false));
+        assert!(ignore.is_ignored(Path::new("vendor/foo/bin"), true));
+        assert!(!ignore.is_ignored(Path::new("vendor/foo/sub/bin"), true));
+    }
+
+    #[test]
+    fn double_star_spans_directories() {
+        let ignore = IgnorePatterns::parse("**/tmp/**\n");
+
+        assert!(ignore.is_ignored(Path::new("tmp/a.sh"), false));
+        assert!(ignore.is_ignored(Path::new("x/y/tmp/a.sh"), false));
+        assert!(!ignore.is_ignored(Path::new("x/tmpfile"), false));
+    }
+
+    #[test]
+    fn trailing_slash_only_matches_directories() {
+        let ignore = IgnorePatterns::parse("cache/\n");
+
+        assert!(ignore.is_ignored(Path::new("cache"), true));
+        assert!(!ignore.is_ignored(Path::new("cache"), false));
+    }
+}
+
+mod source_tests {
+    use super::*;
+    use rusty_herring::SOURCE_PREVIEW_LIMIT;
+
+    #[test]
+    fn reads_text_source() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("hello.sh");
+        fs::write(&path, "#!/bin/sh\necho hi\n").unwrap();
+
+        let source = read_source(path.to_str().unwrap()).unwrap();
+
+        assert_eq!(source, "#!/bin/sh\necho hi\n");
+    }
+
+    #[test]
+    fn binary_source_is_not_shown() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("tool");
+        fs::write(&path, b"\x7fELF\x02\x01\x00\x00garbage").unwrap();
+
+        let source = read_source(path.to_str().unwrap()).unwrap();
+
+        assert_eq!(source, "(binary file, 15 bytes; not shown)");
+    }
+
+    #[test]
+    fn large_source_is_truncated_with_notice() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("big.sh");
+        fs::write(&path, "x".repeat(SOURCE_PREVIEW_LIMIT + 10)).unwrap();
+
+        let source = read_source(path.to_str().unwrap()).unwrap();
+
+        assert!(source.starts_with(&"x".repeat(SOURCE_PREVIEW_LIMIT)));
+        assert!(source.ends_with(&format!(
+            "--- truncated: showing the first {} of {} bytes ---",
+            SOURCE_PREVIEW_LIMIT,
+            SOURCE_PREVIEW_LIMIT + 10
+        )));
+    }
+}
+
+mod history_tests {
+    use super::*;
+
+    #[test]
+    fn record_keeps_newest_first_and_caps_length() {
+        let mut history = History::default();
+        for i in 0..HISTORY_LIMIT + 5 {
+            history.record(&format!("/s/{}.sh", i), RunStatus::from_exit_code(0));
+        }
+
+        assert_eq!(history.entries.len(), HISTORY_LIMIT);
+        assert_eq!(history.entries[0].path, format!("/s/{}.sh", HISTORY_LIMIT + 4));
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("cache/history");
+        let mut history = History::default();
+        history.record("/s/a.sh", RunStatus::from_exit_code(0));
+        history.record("/s/with\ttab.sh", RunStatus::from_exit_code(3));
+
+        history.save_to(&path).unwrap();
+        let loaded = History::load_from(&path).unwrap();
+
+        assert_eq!(loaded.entries.len(), 2);
+        assert_eq!(loaded.entries[0].path, "/s/with\ttab.sh");
+        assert_eq!(loaded.entries[0].status, RunStatus::Failure(3));
+        assert_eq!(loaded.entries[1].path, "/s/a.sh");
+    }
+
+    #[test]
+    fn killed_runs_keep_their_signal() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history");
+        let mut history = History::default();
+        history.record("/s/crash.sh", RunStatus::Signal(11));
+
+        history.save_to(&path).unwrap();
+        assert!(fs::read_to_string(&path).unwrap().contains("\tsig11\t/s/crash.sh"));
+        let loaded = History::load_from(&path).unwrap();
+
+        assert_eq!(loaded.entries[0].status, RunStatus::Signal(11));
+        assert_eq!(loaded.entries[0].status.outcome(), "SIGSEGV");
+    }
+
+    #[test]
+    fn missing_file_is_empty_history() {
+        let dir = TempDir::new().unwrap();
+        let history = History::load_from(&dir.path().join("history")).unwrap();
+        assert!(history.entries.is_empty());
+    }
+}
+
+mod sort_tests {
+    use super::*;
+
+    fn aged(name: &str, category: Option<&str>, secs_ago: u64) -> Script {
+        Script {
+            modified: Some(SystemTime::now() - Duration::from_secs(secs_ago)),
+            ..make_script(name, category)
+        }
+    }
+
+    #[test]
+    fn scan_results_are_sorted_by_path() {
+        let dir = TempDir::new().unwrap();
+        for name in ["zeta.sh", "alpha.sh", "mid.sh"] {
+            let path = dir.path().join(name);
+            File::create(&path).unwrap();
+            make_executable(&path);
+        }
+
+        let scripts = scan_directory(dir.path().to_str().unwrap()).unwrap();
+        let names: Vec<&str> = scripts.iter().map(|s| s.name.as_str()).collect();
+
+        assert_eq!(names, vec!["alpha.sh", "mid.sh", "zeta.sh"]);
+        assert!(scripts[0].modified.is_some());
+    }
+
+    #[test]
+    fn sort_modes_order_scripts() {
+        let mut scripts = vec![
+            aged("b.sh", Some("x"), 30),
+            aged("C.sh", None, 10),
+            aged("a.sh", Some("x"), 20),
+        ];
+        let names = |scripts: &[Script]| -> Vec<String> {
+            scripts.iter().map(|s| s.name.clone()).collect()
+        };
+
+        sort_scripts(&mut scripts, SortMode::Category);
+        assert_eq!(names(&scripts), vec!["C.sh", "a.sh", "b.sh"]);
+
+        sort_scripts(&mut scripts, SortMode::Name);
+        assert_eq!(names(&scripts), vec!["a.sh", "b.sh", "C.sh"]);
+
+        sort_scripts(&mut scripts, SortMode::Modified);
+        assert_eq!(names(&scripts), vec!["C.sh", "a.sh", "b.sh"]);
+    }
+
+    #[test]
+    fn cycle_sort_keeps_selected_script() {
+        let mut app = App::new(vec![
+            aged("c.sh", None, 10),
+            aged("a.sh", Some("x"), 30),
+            aged("b.sh", Some("x"), 20),
+        ]);
+        app.selected_index = 2;
+
+        app.cycle_sort();
+
+        assert_eq!(app.sort_mode, SortMode::Name);
+        assert_eq!(app.selected_script().unwrap().name, "b.sh");
+        assert_eq!(app.scripts[0].name, "a.sh");
+    }
+
+    #[test]
+    fn flat_sorts_ignore_collapsed_groups() {
+        let mut app = App::new(vec![
+            make_script("a.sh", Some("x")),
+            make_script("b.sh", Some("x")),
+        ]);
+        app.toggle_collapse();
+        assert_eq!(app.navigable_indices(), vec![0]);
+
+        app.cycle_sort();
+
+        assert_eq!(app.navigable_indices(), vec![0, 1]);
+        app.toggle_collapse();
+        assert!(!app.is_collapsed("x"));
+    }
+}
+
+mod viewport_tests {
+    use super::*;
+    use ratatui::{Terminal, backend::TestBackend, layout::Rect};
+    use rusty_herring::input::{Action, Runner, apply_action};
+    use rusty_herring::ui;
+
+    struct NoRun;
+
+    impl Runner for NoRun {
+        fn run_selected(&mut self, _app: &mut App) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn row(terminal: &Terminal<TestBackend>, y: u16) -> String {
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.width).map(|x| buffer.get(x, y).symbol()).collect()
+    }
+
+    #[test]
+    fn max_scroll_matches_rendered_viewport() {
+        let mut app = App::new(vec![make_script("a.sh", None)]);
+        app.mode = AppMode::Output;
+        app.output_text = (0..50).map(|i| format!("line {}\n", i)).collect();
+        app.viewport_height = ui::scroll_view_height(Rect::new(0, 0, 80, 30));
+        assert_eq!(app.viewport_height, 22);
+
+        apply_action(&mut app, Action::ScrollBottom, &mut NoRun);
+        assert_eq!(app.output_scroll, 50 - app.viewport_height);
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 30)).unwrap();
+        terminal.draw(|f| ui::render_output_view(f, &app)).unwrap();
+
+        // Title box is rows 0-2, content border row 3, last content row 25
+        assert!(row(&terminal, 25).contains("line 49"));
+        assert!(row(&terminal, 4).contains("line 28"));
+    }
+
+
+    #[test]
+    fn display_rows_wraps_long_lines() {
+        let text = "abcdefghij\nxy\n\n";
+
+        assert_eq!(rusty_herring::display_rows(text, 4, false), vec!["abcdefghij", "xy", ""]);
+        assert_eq!(
+            rusty_herring::display_rows(text, 4, true),
+            vec!["abcd", "efgh", "ij", "xy", ""]
+        );
+    }
+
+    #[test]
+    fn wrapping_counts_wrapped_rows_for_scrolling() {
+        let mut app = App::new(vec![make_script("a.sh", None)]);
+        app.mode = AppMode::Output;
+        app.output_text = format!("{}\n", "x".repeat(100)).repeat(10);
+        app.viewport_width = 20;
+        app.viewport_height = 8;
+        assert_eq!(app.max_output_scroll(), 2);
+
+        apply_action(&mut app, Action::ToggleWrap, &mut NoRun);
+        apply_action(&mut app, Action::ScrollBottom, &mut NoRun);
+
+        assert!(app.wrap_output);
+        assert_eq!(app.output_scroll, 50 - 8);
+    }
+
+
+    #[test]
+    fn category_headers_show_their_counts() {
+        let app = App::new(vec![
+            make_script("a.sh", Some("db")),
+            make_script("b.sh", Some("db")),
+            make_script("c.sh", Some("web")),
+        ]);
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 30)).unwrap();
+        terminal.draw(|f| ui::render_list_view(f, &app)).unwrap();
+        let screen: Vec<String> = (0..30).map(|y| row(&terminal, y)).collect();
+
+        assert!(screen.iter().any(|r| r.contains("Script Runner - 3 scripts in 2 categories")));
+        assert!(screen.iter().any(|r| r.contains("── ▾ db (2) ──")));
+        assert!(screen.iter().any(|r| r.contains("── ▾ web (1) ──")));
+    }
+
+
+    #[test]
+    fn footer_shows_category_and_keeps_the_file_name_visible() {
+        let app = App::new(vec![Script {
+            relative_path: "ops/some/deeply/nested/directory/deploy.sh".to_string(),
+            ..make_script("deploy.sh", Some("ops"))
+        }]);
+
+        let mut terminal = Terminal::new(TestBackend::new(40, 30)).unwrap();
+        terminal.draw(|f| ui::render_list_view(f, &app)).unwrap();
+
+        // The footer box is the last four rows, its first line row 27
+        assert_eq!(row(&terminal, 27), "│[ops] …eply/nested/directory/deploy.sh│");
+    }
+
+
+    #[test]
+    fn line_numbers_follow_the_scroll_offset() {
+        let mut app = App::new(vec![make_script("a.sh", None)]);
+        app.mode = AppMode::Output;
+        app.output_text = (0..50).map(|i| format!("line {}\n", i)).collect();
+        app.output_scroll = 10;
+        app.follow_output = false;
+        apply_action(&mut app, Action::ToggleLineNumbers, &mut NoRun);
+        assert!(app.show_line_numbers);
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 30)).unwrap();
+        terminal.draw(|f| ui::render_output_view(f, &app)).unwrap();
+
+        assert!(row(&terminal, 4).starts_with("│11 line 10 "));
+        assert!(row(&terminal, 5).starts_with("│12 line 11 "));
+    }
+
+    #[test]
+    fn wrapped_rows_keep_their_line_number() {
+        let text = "abcdefghij\nxy\n";
+
+        assert_eq!(
+            rusty_herring::numbered_rows(text, 4, true),
+            vec![(0, "abcd"), (0, "efgh"), (0, "ij"), (1, "xy")]
+        );
+        assert_eq!(rusty_herring::gutter_width(text), 2);
+
+        let mut app = App::new(vec![make_script("a.sh", None)]);
+        app.output_text = text.to_string();
+        app.wrap_output = true;
+        app.viewport_width = 6;
+        app.show_line_numbers = true;
+        // Two columns go to the gutter
+        assert_eq!(app.output_rows(), vec!["abcd", "efgh", "ij", "xy"]);
+    }
+
+    #[test]
+    fn output_and_source_views_survive_an_empty_list() {
+        let mut app = App::new(Vec::new());
+        app.mode = AppMode::Output;
+        app.output_text = "done\n".to_string();
+
+        let mut terminal = Terminal::new(TestBackend::new(60, 12)).unwrap();
+        terminal.draw(|f| ui::render_output_view(f, &app)).unwrap();
+        assert!(row(&terminal, 1).contains("Output: (no script)"));
+        terminal.draw(|f| ui::render_source_view(f, &app)).unwrap();
+        assert!(row(&terminal, 1).contains("Source: (no script)"));
+    }
+
+    #[test]
+    fn source_view_numbers_lines_too() {
+        let mut app = App::new(vec![make_script("a.sh", None)]);
+        app.overlay = Some(Overlay::Source);
+        app.source_text = "#!/bin/sh\necho hi\n".to_string();
+        app.show_line_numbers = true;
+
+        let mut terminal = Terminal::new(TestBackend::new(40, 12)).unwrap();
+        terminal.draw(|f| ui::render_source_view(f, &app)).unwrap();
+
+        assert!(row(&terminal, 4).starts_with("│1 #!/bin/sh"));
+        assert!(row(&terminal, 5).starts_with("│2 echo hi"));
+    }
+
+
+    /// The output pane's right edge, from the first content row to the last.
+    fn scrollbar_column(terminal: &Terminal<TestBackend>) -> Vec<String> {
+        let buffer = terminal.backend().buffer();
+        (4..26).map(|y| buffer.get(79, y).symbol().to_string()).collect()
+    }
+
+    #[test]
+    fn scrollbar_thumb_tracks_scroll_and_visible_share() {
+        let mut app = App::new(vec![make_script("a.sh", None)]);
+        app.mode = AppMode::Output;
+        app.follow_output = false;
+        app.output_text = (0..44).map(|i| format!("line {}\n", i)).collect();
+        app.viewport_height = ui::scroll_view_height(Rect::new(0, 0, 80, 30));
+        let mut terminal = Terminal::new(TestBackend::new(80, 30)).unwrap();
+
+        terminal.draw(|f| ui::render_output_view(f, &app)).unwrap();
+        let column = scrollbar_column(&terminal);
+        // 22 of 44 rows are visible, so the thumb fills half the track
+        assert_eq!(column.iter().filter(|s| *s == "█").count(), 11);
+        assert_eq!(column[0], "█");
+        assert_ne!(column[21], "█");
+
+        apply_action(&mut app, Action::ScrollBottom, &mut NoRun);
+        terminal.draw(|f| ui::render_output_view(f, &app)).unwrap();
+        let column = scrollbar_column(&terminal);
+        assert_ne!(column[0], "█");
+        assert_eq!(column[21], "█");
+    }
+
+    #[test]
+    fn no_scrollbar_when_output_fits() {
+        let mut app = App::new(vec![make_script("a.sh", None)]);
+        app.mode = AppMode::Output;
+        app.output_text = "short\n".to_string();
+        let mut terminal = Terminal::new(TestBackend::new(80, 30)).unwrap();
+
+        terminal.draw(|f| ui::render_output_view(f, &app)).unwrap();
+
+        assert!(scrollbar_column(&terminal).iter().all(|s| s == "│"));
+    }
+
+
+    #[test]
+    fn list_rows_are_numbered_and_the_typed_number_shown() {
+        let mut app = App::new(
+            (0..10).map(|i| make_script(&format!("s{}.sh", i), None)).collect(),
+        );
+        app.jump_number = "7".to_string();
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 40)).unwrap();
+        terminal.draw(|f| ui::render_list_view(f, &app)).unwrap();
+        let screen: Vec<String> = (0..40).map(|y| row(&terminal, y)).collect();
+
+        assert!(screen.iter().any(|r| r.contains("▶ 1  s0.sh")));
+        assert!(screen.iter().any(|r| r.contains("  10 s9.sh")));
+        assert!(screen.iter().any(|r| r.contains("Go to row: 7_")));
+    }
+
+
+    #[test]
+    fn wide_lists_are_drawn_in_two_columns() {
+        let app = App::new(
+            ["a.sh", "b.sh", "c.sh", "d.sh", "e.sh", "f.sh"]
+                .iter()
+                .map(|name| make_script(name, Some("ops")))
+                .collect(),
+        );
+
+        let mut terminal = Terminal::new(TestBackend::new(160, 20)).unwrap();
+        terminal.draw(|f| ui::render_list_view(f, &app)).unwrap();
+
+        // Inside the border, a.sh tops the left column and d.sh the right
+        let first = row(&terminal, 4);
+        let (left, right) = first.split_at(first.char_indices().nth(80).unwrap().0);
+        assert!(left.contains("── ▾ ops (6) ──"), "{}", first);
+        assert!(right.contains("  4 d.sh"), "{}", first);
+        assert!(row(&terminal, 5).contains("a.sh"));
+    }
+
+
+    #[test]
+    fn confirm_prompt_shows_the_script_warning() {
+        let mut app = App::new(vec![Script {
+            confirm_message: Some("This will drop the production database".to_string()),
+            ..make_script("drop.sh", None)
+        }]);
+        app.overlay = Some(Overlay::Confirm);
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 30)).unwrap();
+        terminal.draw(|f| ui::render_confirm_view(f, &app)).unwrap();
+        let screen: Vec<String> = (0..30).map(|y| row(&terminal, y)).collect();
+
+        assert!(screen.iter().any(|r| r.contains("⚠ This will drop the production database")));
+        assert!(screen.iter().any(|r| r.contains("Run drop.sh?")));
+        assert!(screen.iter().any(|r| r.contains("y: Run | any other key: Cancel")));
+    }
+
+
+    #[test]
+    fn pending_descriptions_show_as_loading() {
+        let mut app = App::new(vec![
+            make_script("a.sh", None),
+            Script {
+                description: Some("Does b".to_string()),
+                ..make_script("b.sh", None)
+            },
+        ]);
+        app.loading_metadata.insert("/tmp/a.sh".to_string());
+
+        let mut terminal = Terminal::new(TestBackend::new(60, 20)).unwrap();
+        terminal.draw(|f| ui::render_list_view(f, &app)).unwrap();
+        let screen: Vec<String> = (0..20).map(|y| row(&terminal, y)).collect();
+
+        let name = screen.iter().position(|r| r.contains("a.sh")).unwrap();
+        assert!(screen[name + 1].contains(ui::LOADING_DESCRIPTION));
+        assert!(!screen.iter().any(|r| r.contains("b.sh") && r.contains("loading")));
+        assert!(screen.iter().any(|r| r.contains("Does b")));
+    }
+
+
+    #[test]
+    fn resize_updates_scroll_bounds_and_the_footer() {
+        let mut app = App::new(vec![make_script("a.sh", None)]);
+        app.mode = AppMode::Output;
+        app.output_text = (0..50).map(|i| format!("line {}\n", i)).collect();
+        let footer = |app: &App, width, height| {
+            let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+            terminal.draw(|f| ui::render_output_view(f, app)).unwrap();
+            (0..height).map(|y| row(&terminal, y)).find(|r| r.contains("Lines")).unwrap()
+        };
+
+        app.resize(Rect::new(0, 0, 80, 30));
+        apply_action(&mut app, Action::ScrollBottom, &mut NoRun);
+        assert!(footer(&app, 80, 30).contains("Lines 29-50 of 50"));
+
+        // Still following the end in a taller terminal
+        app.dirty = false;
+        app.resize(Rect::new(0, 0, 80, 40));
+        assert!(app.dirty);
+        assert_eq!(app.output_scroll, 50 - 32);
+        assert!(footer(&app, 80, 40).contains("Lines 19-50 of 50"));
+
+        apply_action(&mut app, Action::ScrollTop, &mut NoRun);
+        app.resize(Rect::new(0, 0, 80, 20));
+        assert_eq!(app.output_scroll, 0);
+        assert!(footer(&app, 80, 20).contains("Lines 1-12 of 50"));
+    }
+
+    #[test]
+    fn resize_pulls_a_scroll_past_the_end_back() {
+        let mut app = App::new(vec![make_script("a.sh", None)]);
+        app.mode = AppMode::Output;
+        app.output_text = (0..50).map(|i| format!("line {}\n", i)).collect();
+        app.resize(Rect::new(0, 0, 80, 30));
+        app.output_scroll = 20;
+        app.follow_output = false;
+
+        // 52 rows fit, so everything shows from the top
+        app.resize(Rect::new(0, 0, 80, 60));
+
+        assert_eq!(app.output_scroll, 0);
+    }
+
+
+    #[test]
+    fn compact_list_moves_the_description_to_the_footer() {
+        let described = |name: &str, description: &str| Script {
+            description: Some(description.to_string()),
+            ..make_script(name, None)
+        };
+        let mut app = App::new(vec![
+            described("a.sh", "Does a"),
+            described("b.sh", "Does b"),
+            described("c.sh", "Does c"),
+        ]);
+        app.selected_index = 1;
+        app.compact = true;
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal.draw(|f| ui::render_list_view(f, &app)).unwrap();
+        let screen: Vec<String> = (0..20).map(|y| row(&terminal, y)).collect();
+
+        let a = screen.iter().position(|r| r.contains("a.sh")).unwrap();
+        assert!(screen[a + 1].contains("b.sh"));
+        assert!(screen[a + 2].contains("c.sh"));
+        assert!(!screen.iter().any(|r| r.contains("Does a") || r.contains("Does c")));
+        assert!(screen.iter().any(|r| r.contains("b.sh — Does b")));
+    }
+
+
+    #[test]
+    fn info_popup_shows_file_details_and_full_description() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("backup.sh");
+        fs::write(&path, vec![b'#'; 2048]).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o750)).unwrap();
+        let mut app = App::new(vec![Script {
+            path: path.to_str().unwrap().to_string(),
+            tags: vec!["ops".to_string(), "nightly".to_string()],
+            description: Some("Back up the database".to_string()),
+            description_full: Some("Back up the database\nto the offsite bucket".to_string()),
+            ..make_script("backup.sh", Some("db"))
+        }]);
+
+        apply_action(&mut app, Action::ShowInfo, &mut NoRun);
+        let mut terminal = Terminal::new(TestBackend::new(80, 30)).unwrap();
+        terminal.draw(|f| ui::render_info_popup(f, &app)).unwrap();
+        let screen: Vec<String> = (0..30).map(|y| row(&terminal, y)).collect();
+        let shows = |text: &str| screen.iter().any(|r| r.contains(text));
+
+        assert!(shows("Category:    db"));
+        assert!(shows("Tags:        ops, nightly"));
+        assert!(shows("Size:        2.0 KiB"));
+        assert!(shows("Modified:    0s ago"));
+        assert!(shows("Permissions: rwxr-x---"));
+        assert!(shows("to the offsite bucket"));
+
+        apply_action(&mut app, Action::HideInfo, &mut NoRun);
+        assert_ne!(app.overlay, Some(Overlay::Info));
+    }
+
+    #[test]
+    fn file_sizes_use_binary_units() {
+        assert_eq!(ui::format_size(512), "512 B");
+        assert_eq!(ui::format_size(1536), "1.5 KiB");
+        assert_eq!(ui::format_size(3 * 1024 * 1024), "3.0 MiB");
+    }
+
+
+    #[test]
+    fn output_footer_hints_follow_the_keymap() {
+        let mut app = App::new(vec![make_script("a.sh", None)]);
+        app.mode = AppMode::Output;
+        app.output_text = (0..50).map(|i| format!("line {}\n", i)).collect();
+        let table = [("scroll_down".to_string(), rusty_herring::keymap::KeyList::One("space".to_string()))]
+            .into_iter()
+            .collect();
+        app.keymap = rusty_herring::keymap::KeyMap::from_config(&table).0;
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|f| ui::render_output_view(f, &app)).unwrap();
+        let footer = row(&terminal, 28);
+
+        assert!(footer.contains("↑/Space: Scroll | /: Search | s: Save"), "{}", footer);
+    }
+
+
+    #[test]
+    fn ascii_output_view_leaves_the_text_as_it_is() {
+        let mut app = App::new(vec![make_script("a.sh", None)]);
+        app.mode = AppMode::Output;
+        app.ascii = true;
+        app.use_color = false;
+        app.output_text = "[FAIL] Script failed\nExit code: 2\n\n✓ printed by the script\n".to_string();
+        app.last_status = Some(rusty_herring::RunStatus::Failure(2));
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal.draw(|f| ui::render_output_view(f, &app)).unwrap();
+        let screen: Vec<String> = (0..20).map(|y| row(&terminal, y)).collect();
+
+        assert!(screen.iter().any(|r| r.contains("[FAIL] Script failed")), "{:?}", screen);
+        assert!(screen.iter().any(|r| r.contains("✓ printed by the script")), "{:?}", screen);
+    }
+
+    #[test]
+    fn output_border_follows_the_run_status_not_the_text() {
+        use ratatui::style::Color;
+
+        let mut app = App::new(vec![make_script("a.sh", None)]);
+        app.mode = AppMode::Output;
+        // A script whose own output starts with a check mark
+        app.output_text = "✓ all good, says the script\n".to_string();
+        app.last_status = Some(rusty_herring::RunStatus::Failure(1));
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal.draw(|f| ui::render_output_view(f, &app)).unwrap();
+
+        let corner = terminal.backend().buffer().get(0, 0);
+        assert_eq!(corner.fg, Color::Red);
+    }
+
+
+    #[test]
+    fn palette_overlay_marks_the_highlighted_match() {
+        let mut app = App::new(vec![
+            Script {
+                description: Some("Ship it".to_string()),
+                ..make_script("deploy.sh", Some("ops"))
+            },
+            make_script("deploy-docs.sh", None),
+        ]);
+        app.open_palette();
+        for c in "depl".chars() {
+            app.push_palette_char(c);
+        }
+        app.next_palette_match();
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal.draw(|f| ui::render_palette(f, &app)).unwrap();
+        let screen: Vec<String> = (0..20).map(|y| row(&terminal, y)).collect();
+
+        assert!(screen.iter().any(|r| r.contains("> depl_")));
+        assert!(screen.iter().any(|r| r.contains("  deploy.sh (ops) — Ship it")), "{:?}", screen);
+        assert!(screen.iter().any(|r| r.contains("▶ deploy-docs.sh")), "{:?}", screen);
+    }
+}
+
+mod badge_tests {
+    use super::*;
+    use ratatui::{Terminal, backend::TestBackend};
+    use rusty_herring::{RunResult, ui};
+
+    fn screen(app: &App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(60, 12)).unwrap();
+        terminal.draw(|f| ui::render_list_view(f, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width).map(|x| buffer.get(x, y).symbol()).collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn badge_shows_duration_or_exit_code() {
+        let passed = RunResult { status: RunStatus::from_exit_code(0), duration: Duration::from_millis(420) };
+        let failed = RunResult { status: RunStatus::from_exit_code(2), duration: Duration::from_secs(3) };
+
+        assert_eq!(passed.badge(), "[✓ 0.4s]");
+        assert_eq!(failed.badge(), "[✗ code 2]");
+    }
+
+    #[test]
+    fn only_run_scripts_get_a_badge() {
+        let mut app = App::new(vec![make_script("ran.sh", None), make_script("never.sh", None)]);
+        app.last_results.insert(
+            "/tmp/ran.sh".to_string(),
+            RunResult { status: RunStatus::from_exit_code(1), duration: Duration::from_secs(1) },
+        );
+
+        let screen = screen(&app);
+        let line = |name: &str| screen.lines().find(|l| l.contains(name)).unwrap().to_string();
+
+        assert!(line("ran.sh").contains("[✗ code 1]"));
+        assert!(!line("never.sh").contains('['));
+    }
+}
+
+
+mod interactive_tests {
+    use super::*;
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::ExitStatus;
+    use crossterm::event::{KeyCode, KeyEvent};
+    use rusty_herring::InteractiveRun;
+    use rusty_herring::input::handle_key;
+    use rusty_herring::run::ProcessRunner;
+
+    #[test]
+    fn interactive_script_is_queued_instead_of_spawned() {
+        let mut script = make_script("ask.sh", None);
+        script.interactive = true;
+        let mut app = App::new(vec![script]);
+
+        app.start_run().unwrap();
+
+        let request = app.interactive_request.as_ref().unwrap();
+        assert_eq!(request.path, "/tmp/ask.sh");
+        assert!(app.running.is_none());
+    }
+
+    #[test]
+    fn capital_i_forces_an_interactive_run() {
+        let mut app = App::new(vec![make_script("plain.sh", None)]);
+
+        handle_key(&mut app, KeyEvent::from(KeyCode::Char('I')), &mut ProcessRunner);
+
+        assert!(app.interactive_request.is_some());
+        assert!(!app.force_interactive);
+        assert!(app.running.is_none());
+    }
+
+    #[test]
+    fn finishing_records_the_result() {
+        let mut app = App::new(vec![make_script("ask.sh", None)]);
+        let run = InteractiveRun {
+            path: "/tmp/ask.sh".to_string(),
+            interpreter: None,
+            run_command: None,
+            args: vec![],
+            dir: None,
+        };
+
+        app.finish_interactive(&run, Ok(ExitStatus::from_raw(3 << 8)), Duration::from_secs(2));
+
+        assert!(app.viewing_output());
+        assert!(app.output_text.contains("output went to the terminal"));
+        assert_eq!(app.last_results["/tmp/ask.sh"].status, RunStatus::Failure(3));
+    }
+}
+
+
+mod favorites_tests {
+    use super::*;
+    use rusty_herring::favorites::Favorites;
+
+    #[test]
+    fn toggling_twice_returns_to_unfavorited() {
+        let mut app = App::new(vec![make_script("a.sh", None), make_script("b.sh", None)]);
+        app.selected_index = 1;
+
+        app.toggle_favorite();
+        assert!(app.scripts[0].favorite);
+        assert_eq!(app.scripts[app.selected_index].name, "b.sh");
+        assert!(app.favorites.contains("/tmp/b.sh"));
+
+        app.toggle_favorite();
+        assert!(app.scripts.iter().all(|s| !s.favorite));
+        assert!(app.favorites.paths.is_empty());
+    }
+
+    #[test]
+    fn favorites_sort_first_within_their_category() {
+        let mut scripts = vec![
+            make_script("a.sh", Some("db")),
+            make_script("z.sh", Some("db")),
+            make_script("b.sh", Some("ops")),
+        ];
+        scripts[1].favorite = true;
+        sort_scripts(&mut scripts, SortMode::Category);
+
+        let names: Vec<_> = scripts.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["z.sh", "a.sh", "b.sh"]);
+    }
+
+    #[test]
+    fn favorites_round_trip_and_reapply_by_path() {
+        let dir = TempDir::new().unwrap();
+        let script_path = dir.path().join("deploy.sh");
+        File::create(&script_path).unwrap();
+        make_executable(&script_path);
+
+        let mut favorites = Favorites::default();
+        favorites.toggle(script_path.canonicalize().unwrap().to_str().unwrap());
+        let file = dir.path().join("state").join("favorites");
+        favorites.save_to(&file).unwrap();
+
+        let loaded = Favorites::load_from(&file).unwrap();
+        assert_eq!(loaded, favorites);
+
+        let mut scripts = scan_directory(dir.path().to_str().unwrap()).unwrap();
+        mark_favorites(&mut scripts, &loaded);
+        assert!(scripts[0].favorite);
+    }
+}
+
+
+mod theme_tests {
+    use super::*;
+    use ratatui::{Terminal, backend::TestBackend, style::Color};
+    use rusty_herring::theme::{Theme, ThemeColors};
+    use rusty_herring::ui;
+
+    #[test]
+    fn parses_named_and_hex_colors() {
+        let theme = Theme::parse("border = \"blue\"\nselection = \"#ff8800\"\n").unwrap();
+
+        assert_eq!(theme.border, Color::Blue);
+        assert_eq!(theme.selection, Color::Rgb(0xff, 0x88, 0x00));
+        assert_eq!(theme.success, Theme::default().success);
+    }
+
+    #[test]
+    fn bad_colors_and_keys_are_errors() {
+        assert!(Theme::parse("border = \"chartreuse-ish\"\n").is_err());
+        assert!(Theme::parse("borders = \"blue\"\n").is_err());
+    }
+
+    #[test]
+    fn missing_file_is_the_default_theme() {
+        let dir = TempDir::new().unwrap();
+        let theme = Theme::load_from(&dir.path().join("theme.toml")).unwrap();
+        assert_eq!(theme, Theme::default());
+    }
+
+    #[test]
+    fn list_borders_use_the_theme() {
+        let mut app = App::new(vec![make_script("a.sh", None)]);
+        app.theme.border = Color::Rgb(1, 2, 3);
+
+        let mut terminal = Terminal::new(TestBackend::new(40, 12)).unwrap();
+        terminal.draw(|f| ui::render_list_view(f, &app)).unwrap();
+
+        assert_eq!(terminal.backend().buffer().get(0, 0).fg, Color::Rgb(1, 2, 3));
+    }
+
+
+    /// Background of the row showing `name` in the list view.
+    fn row_background(app: &App, name: &str) -> Color {
+        let mut terminal = Terminal::new(TestBackend::new(40, 16)).unwrap();
+        terminal.draw(|f| ui::render_list_view(f, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let y = (0..buffer.area.height)
+            .find(|&y| {
+                let row: String = (0..buffer.area.width).map(|x| buffer.get(x, y).symbol()).collect();
+                row.contains(name)
+            })
+            .unwrap();
+        buffer.get(10, y).bg
+    }
+
+    fn striped_app() -> App {
+        let mut app = App::new(vec![
+            make_script("a.sh", None),
+            make_script("b.sh", None),
+            make_script("c.sh", None),
+        ]);
+        app.theme.stripe = Color::Rgb(9, 9, 9);
+        app
+    }
+
+    #[test]
+    fn every_other_row_is_striped_except_the_selection() {
+        let mut app = striped_app();
+
+        assert_eq!(row_background(&app, "b.sh"), Color::Rgb(9, 9, 9));
+        assert_eq!(row_background(&app, "c.sh"), Color::Reset);
+
+        app.selected_index = 1;
+        assert_eq!(row_background(&app, "b.sh"), Color::Reset);
+    }
+
+    #[test]
+    fn stripes_can_be_turned_off_and_need_color() {
+        let mut app = striped_app();
+        app.zebra_rows = false;
+        assert_eq!(row_background(&app, "b.sh"), Color::Reset);
+
+        let mut app = striped_app();
+        app.use_color = false;
+        assert_eq!(row_background(&app, "b.sh"), Color::Reset);
+    }
+
+    #[test]
+    fn stripe_color_is_read_from_the_theme() {
+        let theme = Theme::parse("stripe = \"236\"\n").unwrap();
+        assert_eq!(theme.stripe, Color::Indexed(236));
+    }
+
+    #[test]
+    fn config_colors_are_laid_over_the_theme() {
+        let theme = Theme::parse("border = \"blue\"\nmuted = \"red\"\n").unwrap();
+        let colors = ThemeColors {
+            border: Some("green".to_string()),
+            ..ThemeColors::default()
+        };
+
+        let theme = theme.with(&colors).unwrap();
+
+        assert_eq!(theme.border, Color::Green);
+        assert_eq!(theme.muted, Color::Red);
+    }
+}
+
+
+mod keymap_tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use rusty_herring::input::{Runner, handle_key};
+    use rusty_herring::keymap::{KeyList, KeyMap, parse_key};
+
+    struct NoRun;
+
+    impl Runner for NoRun {
+        fn run_selected(&mut self, _app: &mut App) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn keymap(entries: &[(&str, &[&str])]) -> (KeyMap, Vec<String>) {
+        let table: BTreeMap<String, KeyList> = entries
+            .iter()
+            .map(|(action, keys)| {
+                let keys = keys.iter().map(|k| k.to_string()).collect();
+                (action.to_string(), KeyList::Many(keys))
+            })
+            .collect();
+        KeyMap::from_config(&table)
+    }
+
+    #[test]
+    fn parses_key_strings() {
+        assert_eq!(parse_key("h"), Some((KeyCode::Char('h'), KeyModifiers::NONE)));
+        assert_eq!(parse_key("ctrl-n"), Some((KeyCode::Char('n'), KeyModifiers::CONTROL)));
+        assert_eq!(parse_key("shift-t"), Some((KeyCode::Char('T'), KeyModifiers::NONE)));
+        assert_eq!(parse_key("Enter"), Some((KeyCode::Enter, KeyModifiers::NONE)));
+        assert_eq!(parse_key("alt-pagedown"), Some((KeyCode::PageDown, KeyModifiers::ALT)));
+        assert_eq!(parse_key("f5"), Some((KeyCode::F(5), KeyModifiers::NONE)));
+        assert_eq!(parse_key("-"), Some((KeyCode::Char('-'), KeyModifiers::NONE)));
+        assert_eq!(parse_key("hyper-x"), None);
+        assert_eq!(parse_key("f99"), None);
+        assert_eq!(parse_key("banana"), None);
+    }
+
+    #[test]
+    fn bad_entries_warn_and_are_skipped() {
+        let (keymap, warnings) = keymap(&[("next", &["h", "nope"]), ("launch", &["x"])]);
+
+        assert_eq!(
+            warnings,
+            vec!["unknown action \"launch\"", "unknown key \"nope\" for next"]
+        );
+        assert_ne!(keymap, KeyMap::default());
+    }
+
+    #[test]
+    fn rebound_actions_replace_their_default_keys() {
+        let mut app = App::new(vec![
+            make_script("a.sh", None),
+            make_script("b.sh", None),
+            make_script("c.sh", None),
+        ]);
+        app.keymap = keymap(&[("next", &["t"]), ("quit", &["ctrl-q"])]).0;
+        let key = |code| KeyEvent::from(code);
+
+        handle_key(&mut app, key(KeyCode::Char('t')), &mut NoRun);
+        assert_eq!(app.selected_index, 1);
+        handle_key(&mut app, key(KeyCode::Char('j')), &mut NoRun);
+        assert_eq!(app.selected_index, 1);
+        // Unbound actions keep their defaults
+        handle_key(&mut app, key(KeyCode::Char('k')), &mut NoRun);
+        assert_eq!(app.selected_index, 0);
+
+        handle_key(&mut app, key(KeyCode::Char('q')), &mut NoRun);
+        assert!(!app.should_quit);
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL), &mut NoRun);
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn bindings_do_not_reach_text_entry() {
+        let mut app = App::new(vec![make_script("a.sh", None)]);
+        app.keymap = keymap(&[("next", &["t"])]).0;
+        app.start_filter();
+
+        handle_key(&mut app, KeyEvent::from(KeyCode::Char('t')), &mut NoRun);
+
+        assert_eq!(app.filter_query, "t");
+    }
+
+
+    #[test]
+    fn hint_bars_show_rebound_keys() {
+        use rusty_herring::input::{LIST_HINTS, SCROLL_HINTS, hint_bar};
+
+        let defaults = hint_bar(LIST_HINTS, &KeyMap::default());
+        assert!(defaults.starts_with("↑/↓: Move | Enter: Run | a: Args"), "{}", defaults);
+        assert!(defaults.ends_with("?: Help | q: Quit"), "{}", defaults);
+
+        let (keymap, _) = keymap(&[("next", &["ctrl-n"]), ("quit", &["x", "f10"])]);
+        let bar = hint_bar(LIST_HINTS, &keymap);
+        assert!(bar.starts_with("↑/Ctrl-N: Move"), "{}", bar);
+        assert!(bar.ends_with("x/F10: Quit"), "{}", bar);
+        assert_eq!(hint_bar(SCROLL_HINTS, &keymap), "↑/↓: Scroll");
+    }
+}
+
+
+mod cli_tests {
+    use super::*;
+    use std::path::Path;
+    use std::process::{Command, Output};
+
+    /// Runs the binary with a config directory of its own.
+    fn herring(args: &[&str]) -> Output {
+        let config = TempDir::new().unwrap();
+        Command::new(env!("CARGO_BIN_EXE_rusty-herring"))
+            .args(args)
+            .env("XDG_CONFIG_HOME", config.path())
+            .output()
+            .unwrap()
+    }
+
+    #[test]
+    fn version_prints_the_crate_version() {
+        let output = herring(&["--version"]);
+
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            format!("rusty-herring {}\n", env!("CARGO_PKG_VERSION"))
+        );
+    }
+
+    #[test]
+    fn unknown_short_flags_print_the_usage() {
+        let output = herring(&["-x", "."]);
+
+        assert_eq!(output.status.code(), Some(2));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.starts_with("Unknown option: -x\n"), "{}", stderr);
+        assert!(stderr.contains("Usage"), "{}", stderr);
+    }
+
+    #[test]
+    fn count_prints_only_the_number_of_scripts() {
+        let dir = TempDir::new().unwrap();
+        for name in ["a.sh", "b.sh"] {
+            let path = dir.path().join(name);
+            File::create(&path).unwrap();
+            make_executable(&path);
+        }
+        File::create(dir.path().join("notes.txt")).unwrap();
+
+        let output = herring(&["--count", dir.path().to_str().unwrap()]);
+
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "2\n");
+    }
+
+    #[test]
+    fn count_fails_when_the_directory_cannot_be_scanned() {
+        let dir = TempDir::new().unwrap();
+        let missing = dir.path().join("missing");
+
+        let output = herring(&["--count", missing.to_str().unwrap()]);
+
+        assert!(!output.status.success());
+        assert!(output.stdout.is_empty());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Cannot scan"));
+    }
+
+
+    fn script(dir: &Path, relative: &str, body: &str) {
+        let path = dir.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        make_executable(&path);
+    }
+
+    #[test]
+    fn run_passes_stdio_through_and_exits_with_the_script() {
+        let dir = TempDir::new().unwrap();
+        script(dir.path(), "deploy.sh", "echo deploying; echo oops >&2; exit 7");
+
+        let output = herring(&["--run", "deploy.sh", dir.path().to_str().unwrap()]);
+
+        assert_eq!(output.status.code(), Some(7));
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "deploying\n");
+        assert_eq!(String::from_utf8_lossy(&output.stderr), "oops\n");
+    }
+
+    #[test]
+    fn run_reports_a_missing_script() {
+        let dir = TempDir::new().unwrap();
+        script(dir.path(), "deploy.sh", "true");
+
+        let output = herring(&["--run", "nope.sh", dir.path().to_str().unwrap()]);
+
+        assert_eq!(output.status.code(), Some(1));
+        assert!(String::from_utf8_lossy(&output.stderr).starts_with("No script named nope.sh in "));
+    }
+
+    #[test]
+    fn ambiguous_run_lists_the_matches_and_a_path_picks_one() {
+        let dir = TempDir::new().unwrap();
+        script(dir.path(), "db/reset.sh", "echo db");
+        script(dir.path(), "web/reset.sh", "echo web");
+        let root = dir.path().to_str().unwrap();
+
+        let output = herring(&["--run", "reset.sh", root]);
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "reset.sh is ambiguous; it matches:\n  db/reset.sh\n  web/reset.sh\n"
+        );
+        assert!(output.stdout.is_empty());
+
+        let output = herring(&["--run", "web/reset.sh", root]);
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "web\n");
+    }
+}
+
+
+mod selection_tests {
+    use super::*;
+    use rusty_herring::selection::Selections;
+
+    #[test]
+    fn selections_round_trip_per_directory() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("cache").join("selection");
+        let mut selections = Selections::default();
+        selections.set("/work/scripts".to_string(), "/work/scripts/b.sh".to_string());
+        selections.set("/home/ops".to_string(), "/home/ops/deploy.sh".to_string());
+
+        selections.save_to(&path).unwrap();
+        let loaded = Selections::load_from(&path).unwrap();
+
+        assert_eq!(loaded, selections);
+        assert_eq!(loaded.get("/work/scripts"), Some("/work/scripts/b.sh"));
+        assert_eq!(loaded.get("/home/ops"), Some("/home/ops/deploy.sh"));
+        assert_eq!(loaded.get("/elsewhere"), None);
+    }
+
+    #[test]
+    fn missing_file_and_malformed_lines_are_ignored() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("selection");
+        assert_eq!(Selections::load_from(&path).unwrap(), Selections::default());
+
+        fs::write(&path, "no tab here\n/dir\t/dir/a.sh\n").unwrap();
+        let loaded = Selections::load_from(&path).unwrap();
+        assert_eq!(loaded.entries.len(), 1);
+        assert_eq!(loaded.get("/dir"), Some("/dir/a.sh"));
+    }
+
+    #[test]
+    fn key_uses_absolute_directories() {
+        let dir = TempDir::new().unwrap();
+        let canonical = fs::canonicalize(dir.path()).unwrap();
+        let with_dot = format!("{}/.", dir.path().display());
+
+        assert_eq!(Selections::key(&[with_dot]), canonical.display().to_string());
+    }
+
+    #[test]
+    fn selection_is_restored_by_path_or_falls_back_to_the_top() {
+        let dir = TempDir::new().unwrap();
+        let scripts: Vec<Script> = ["a.sh", "b.sh", "c.sh"]
+            .iter()
+            .map(|name| {
+                let path = dir.path().join(name);
+                File::create(&path).unwrap();
+                Script { path: path.to_str().unwrap().to_string(), ..make_script(name, None) }
+            })
+            .collect();
+        let mut app = App::new(scripts);
+        app.selected_index = 2;
+        let remembered = app.selected_path().unwrap();
+
+        app.selected_index = 0;
+        app.restore_selection(&remembered);
+        assert_eq!(app.selected_index, 2);
+
+        app.restore_selection("/no/longer/there.sh");
+        assert_eq!(app.selected_index, 0);
+    }
+}
+
+mod manifest_tests {
+    use super::*;
+    use rusty_herring::manifest::{MANIFEST_FILE, Manifest};
+
+    fn script(dir: &TempDir, relative: &str, body: &str) {
+        let path = dir.path().join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, body).unwrap();
+        make_executable(&path);
+    }
+
+    #[test]
+    fn manifest_overrides_or_adds_to_what_scripts_say() {
+        let dir = TempDir::new().unwrap();
+        script(&dir, "vendor/sync.sh", "#!/bin/sh\n# Own description\n# herring-tags: own\n");
+        script(&dir, "plain.sh", "#!/bin/sh\n");
+        fs::write(
+            dir.path().join(MANIFEST_FILE),
+            r#"
+[scripts."vendor/sync.sh"]
+description = "Mirror the vendor tree"
+category = "tools"
+tags = ["ext", "own"]
+
+[scripts."plain.sh"]
+confirm = "Touches production"
+"#,
+        )
+        .unwrap();
+
+        let report = scan_directory_report(dir.path().to_str().unwrap(), &ScanOptions::default())
+            .unwrap();
+        let plain = &report.scripts[0];
+        let sync = &report.scripts[1];
+
+        assert_eq!(sync.description, Some("Mirror the vendor tree".to_string()));
+        assert_eq!(sync.category, Some("tools".to_string()));
+        assert_eq!(sync.tags, vec!["own".to_string(), "ext".to_string()]);
+        assert_eq!(plain.confirm_message, Some("Touches production".to_string()));
+        assert_eq!(plain.description, None);
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn entries_match_by_name_and_unknown_ones_are_warned_about() {
+        let dir = TempDir::new().unwrap();
+        script(&dir, "db/reset.sh", "#!/bin/sh\n");
+        fs::write(
+            dir.path().join(MANIFEST_FILE),
+            "[scripts.\"reset.sh\"]\ndescription = \"Reset the db\"\n\n[scripts.\"gone.sh\"]\ncategory = \"x\"\n",
+        )
+        .unwrap();
+
+        let report = scan_directory_report(dir.path().to_str().unwrap(), &ScanOptions::default())
+            .unwrap();
+
+        assert_eq!(report.scripts[0].description, Some("Reset the db".to_string()));
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].path.ends_with(MANIFEST_FILE));
+        assert_eq!(report.warnings[0].message, "No script named \"gone.sh\"");
+    }
+
+    #[test]
+    fn unreadable_manifest_is_a_warning() {
+        let dir = TempDir::new().unwrap();
+        script(&dir, "a.sh", "#!/bin/sh\n# Mine\n");
+        fs::write(dir.path().join(MANIFEST_FILE), "[scripts.\"a.sh\"]\ncolour = \"red\"\n").unwrap();
+
+        let report = scan_directory_report(dir.path().to_str().unwrap(), &ScanOptions::default())
+            .unwrap();
+
+        assert_eq!(report.scripts[0].description, Some("Mine".to_string()));
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].message.contains("colour"), "{}", report.warnings[0].message);
+    }
+
+    #[test]
+    fn manifest_still_wins_once_lazy_metadata_arrives() {
+        let dir = TempDir::new().unwrap();
+        script(&dir, "a.sh", "#!/bin/sh\n# Mine\n# herring-confirm: Mine too\n");
+        fs::write(dir.path().join(MANIFEST_FILE), "[scripts.\"a.sh\"]\ndescription = \"Theirs\"\n").unwrap();
+        let options = ScanOptions { lazy_metadata: true, ..ScanOptions::default() };
+        let mut app = App::new(scan_directory_with(dir.path().to_str().unwrap(), &options).unwrap());
+        let path = app.scripts[0].path.clone();
+        app.loading_metadata.insert(path.clone());
+
+        app.ensure_metadata(&path);
+
+        assert_eq!(app.scripts[0].description, Some("Theirs".to_string()));
+        assert_eq!(app.scripts[0].confirm_message, Some("Mine too".to_string()));
+    }
+
+    #[test]
+    fn empty_category_moves_a_script_to_the_root() {
+        let manifest = Manifest::parse("[scripts.\"a.sh\"]\ncategory = \"\"\n").unwrap();
+        let mut scripts = vec![make_script("a.sh", Some("nested"))];
+
+        assert!(manifest.apply(&mut scripts).is_empty());
+        assert_eq!(scripts[0].category, None);
+    }
+}
+
+mod palette_tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use rusty_herring::input::{Runner, View, current_view, handle_key};
+    use rusty_herring::palette::{fuzzy_score, score_script};
+
+    #[derive(Default)]
+    struct CountingRunner {
+        runs: Vec<String>,
+    }
+
+    impl Runner for CountingRunner {
+        fn run_selected(&mut self, app: &mut App) -> std::io::Result<()> {
+            self.runs.push(app.scripts[app.selected_index].name.clone());
+            Ok(())
+        }
+    }
+
+    fn scripts() -> App {
+        App::new(vec![
+            Script {
+                description: Some("Rebuild the search index".to_string()),
+                ..make_script("reindex.sh", Some("search"))
+            },
+            Script {
+                tags: vec!["deploy".to_string()],
+                ..make_script("ship.sh", Some("ops"))
+            },
+            make_script("deploy.sh", Some("ops")),
+        ])
+    }
+
+    fn type_query(app: &mut App, runner: &mut CountingRunner, query: &str) {
+        let ctrl = KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL);
+        handle_key(app, ctrl, runner);
+        for c in query.chars() {
+            handle_key(app, KeyEvent::from(KeyCode::Char(c)), runner);
+        }
+    }
+
+    #[test]
+    fn fuzzy_score_needs_every_character_in_order() {
+        assert!(fuzzy_score("dpl", "deploy.sh").is_some());
+        assert!(fuzzy_score("DEP", "deploy.sh").is_some());
+        assert_eq!(fuzzy_score("pld", "deploy.sh"), None);
+        assert_eq!(fuzzy_score("x", "deploy.sh"), None);
+    }
+
+    #[test]
+    fn adjacent_and_word_start_matches_score_higher() {
+        let run = fuzzy_score("dep", "deploy.sh").unwrap();
+        let spread = fuzzy_score("dep", "do-the-prep").unwrap();
+        assert!(run > spread, "{} vs {}", run, spread);
+
+        let start = fuzzy_score("db", "db-reset").unwrap();
+        let middle = fuzzy_score("db", "oddball").unwrap();
+        assert!(start > middle, "{} vs {}", start, middle);
+    }
+
+    #[test]
+    fn names_outrank_tags_and_descriptions() {
+        let named = make_script("deploy.sh", None);
+        let tagged = Script {
+            tags: vec!["deploy".to_string()],
+            ..make_script("ship.sh", None)
+        };
+        let described = Script {
+            description: Some("deploy the site".to_string()),
+            ..make_script("site.sh", None)
+        };
+
+        let named = score_script("deploy", &named).unwrap();
+        let tagged = score_script("deploy", &tagged).unwrap();
+        let described = score_script("deploy", &described).unwrap();
+        assert!(named > tagged && tagged > described);
+    }
+
+    #[test]
+    fn palette_ranks_matches_across_fields() {
+        let mut app = scripts();
+        let mut runner = CountingRunner::default();
+
+        type_query(&mut app, &mut runner, "deploy");
+
+        assert_eq!(current_view(&app), View::Palette);
+        let names: Vec<&str> = app.palette_matches().iter().map(|&i| app.scripts[i].name.as_str()).collect();
+        assert_eq!(names, vec!["deploy.sh", "ship.sh"]);
+    }
+
+    #[test]
+    fn empty_palette_lists_recent_runs_then_favorites() {
+        let mut app = scripts();
+        app.scripts[2].favorite = true;
+        app.history.record("/tmp/ship.sh", RunStatus::from_exit_code(0));
+        app.history.record("/tmp/deploy.sh", RunStatus::from_exit_code(0));
+        app.open_palette();
+
+        assert_eq!(app.palette_matches(), vec![2, 1]);
+    }
+
+    #[test]
+    fn enter_jumps_to_the_match_past_the_list_filter() {
+        let mut app = scripts();
+        app.filter_query = "reindex".to_string();
+        let mut runner = CountingRunner::default();
+
+        type_query(&mut app, &mut runner, "ship");
+        handle_key(&mut app, KeyEvent::from(KeyCode::Enter), &mut runner);
+
+        assert_ne!(app.overlay, Some(Overlay::Palette));
+        assert_eq!(app.selected_script().unwrap().name, "ship.sh");
+        assert!(app.filter_query.is_empty());
+        assert!(runner.runs.is_empty());
+    }
+
+    #[test]
+    fn alt_enter_runs_the_match() {
+        let mut app = scripts();
+        let mut runner = CountingRunner::default();
+
+        type_query(&mut app, &mut runner, "rix");
+        handle_key(&mut app, KeyEvent::new(KeyCode::Enter, KeyModifiers::ALT), &mut runner);
+
+        assert_eq!(runner.runs, vec!["reindex.sh"]);
+    }
+
+    #[test]
+    fn esc_closes_the_palette_without_moving() {
+        let mut app = scripts();
+        app.selected_index = 1;
+        let mut runner = CountingRunner::default();
+
+        type_query(&mut app, &mut runner, "deploy");
+        handle_key(&mut app, KeyEvent::from(KeyCode::Esc), &mut runner);
+
+        assert_eq!(current_view(&app), View::List);
+        assert_eq!(app.selected_index, 1);
+    }
+}
+
+mod run_counts_tests {
+    use super::*;
+    use rusty_herring::run_counts::RunCounts;
+
+    #[test]
+    fn run_counts_round_trip_and_reapply_by_path() {
+        let dir = TempDir::new().unwrap();
+        let script_path = dir.path().join("deploy.sh");
+        File::create(&script_path).unwrap();
+        make_executable(&script_path);
+
+        let mut run_counts = RunCounts::default();
+        let key = script_path.canonicalize().unwrap().to_str().unwrap().to_string();
+        run_counts.increment(&key);
+        assert_eq!(run_counts.increment(&key), 2);
+        let file = dir.path().join("state").join("run-counts");
+        run_counts.save_to(&file).unwrap();
+
+        let loaded = RunCounts::load_from(&file).unwrap();
+        assert_eq!(loaded, run_counts);
+
+        let mut scripts = scan_directory(dir.path().to_str().unwrap()).unwrap();
+        mark_run_counts(&mut scripts, &loaded);
+        assert_eq!(scripts[0].run_count, 2);
+    }
+
+    #[test]
+    fn most_used_sort_puts_frequent_scripts_first() {
+        let mut scripts = vec![
+            make_script("a.sh", Some("x")),
+            make_script("b.sh", None),
+            make_script("c.sh", Some("y")),
+        ];
+        scripts[1].run_count = 3;
+        scripts[2].run_count = 7;
+
+        sort_scripts(&mut scripts, SortMode::MostUsed);
+
+        let names: Vec<_> = scripts.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["c.sh", "b.sh", "a.sh"]);
+        assert_eq!(SortMode::Modified.next(), SortMode::MostUsed);
+        assert_eq!(SortMode::MostUsed.next(), SortMode::Category);
+    }
 }